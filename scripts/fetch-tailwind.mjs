@@ -0,0 +1,24 @@
+// Downloads the pinned Tailwind Play CDN build bundled with the app so generated
+// apps render offline. Skips the download when the file is already present.
+import { existsSync, mkdirSync, writeFileSync } from 'node:fs'
+import { dirname, join } from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+const VERSION = '3.4.16'
+const root = dirname(dirname(fileURLToPath(import.meta.url)))
+const target = join(root, 'src-tauri', 'assets', 'vendor', `tailwindcss-${VERSION}.js`)
+
+if (existsSync(target)) {
+  process.exit(0)
+}
+
+try {
+  const response = await fetch(`https://cdn.tailwindcss.com/${VERSION}`)
+  if (!response.ok) throw new Error(`HTTP ${response.status}`)
+  mkdirSync(dirname(target), { recursive: true })
+  writeFileSync(target, Buffer.from(await response.arrayBuffer()))
+  console.log(`Fetched Tailwind ${VERSION}`)
+} catch (error) {
+  // Not fatal: previews fall back to the CDN when the bundled copy is missing
+  console.warn(`Could not fetch Tailwind ${VERSION}: ${error.message}`)
+}
@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use serde::Serialize;
+
+// WCAG AA for body text; the shade table below is averaged across hues, so only
+// combinations that clearly fail get flagged
+const MIN_CONTRAST: f64 = 4.5;
+
+// Rough relative luminance of each Tailwind shade, averaged over the palette
+const SHADE_LUMINANCE: &[(&str, f64)] = &[
+    ("50", 0.93),
+    ("100", 0.86),
+    ("200", 0.74),
+    ("300", 0.58),
+    ("400", 0.38),
+    ("500", 0.22),
+    ("600", 0.13),
+    ("700", 0.075),
+    ("800", 0.04),
+    ("900", 0.022),
+    ("950", 0.01),
+];
+
+// Inputs that are labelled by their own value or need no label at all
+const SELF_LABELLED_INPUTS: &[&str] = &["hidden", "submit", "reset", "button", "image"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct A11yFinding {
+    // missing_lang, missing_alt, unlabeled_input, low_contrast
+    pub kind: String,
+    pub message: String,
+    pub line: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct A11yAudit {
+    pub passed: bool,
+    pub findings: Vec<A11yFinding>,
+}
+
+struct Control {
+    id: Option<String>,
+    description: String,
+    line: u64,
+}
+
+#[derive(Default)]
+struct AuditSink {
+    has_lang: Option<bool>,
+    label_depth: usize,
+    label_targets: HashSet<String>,
+    // Controls with no label of their own; resolved against label_targets at the end
+    unlabeled: Vec<Control>,
+    findings: Vec<A11yFinding>,
+}
+
+impl AuditSink {
+    fn finding(&mut self, kind: &str, message: String, line: Option<u64>) {
+        self.findings.push(A11yFinding {
+            kind: kind.to_string(),
+            message,
+            line,
+        });
+    }
+}
+
+fn attribute(tag: &Tag, name: &str) -> Option<String> {
+    tag.attrs
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| attr.value.to_string())
+}
+
+fn has_text(value: Option<String>) -> bool {
+    value.is_some_and(|value| !value.trim().is_empty())
+}
+
+// "<input type="email" id="signup">" style summary so the model can find the element again
+fn describe(tag: &Tag) -> String {
+    let details: Vec<String> = ["type", "id", "name", "src", "placeholder"]
+        .iter()
+        .filter_map(|name| attribute(tag, name).map(|value| format!("{}=\"{}\"", name, value)))
+        .collect();
+    if details.is_empty() {
+        format!("<{}>", tag.name)
+    } else {
+        format!("<{} {}>", tag.name, details.join(" "))
+    }
+}
+
+fn luminance(color: &str) -> Option<f64> {
+    match color {
+        "white" => return Some(1.0),
+        "black" => return Some(0.0),
+        _ => {}
+    }
+    let (_, shade) = color.rsplit_once('-')?;
+    // Opacity modifiers like text-gray-400/80 are judged on the base shade
+    let shade = shade.split('/').next()?;
+    SHADE_LUMINANCE.iter().find(|(name, _)| *name == shade).map(|(_, value)| *value)
+}
+
+fn contrast(a: f64, b: f64) -> f64 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+// Only unprefixed utilities on the same element; hover:, dark: and friends apply conditionally
+fn color_classes(classes: &str) -> (Option<&str>, Option<&str>) {
+    let mut text = None;
+    let mut background = None;
+    for class in classes.split_whitespace().filter(|class| !class.contains(':')) {
+        if let Some(color) = class.strip_prefix("text-").filter(|color| luminance(color).is_some()) {
+            text = Some(color);
+        } else if let Some(color) = class.strip_prefix("bg-").filter(|color| luminance(color).is_some()) {
+            background = Some(color);
+        }
+    }
+    (text, background)
+}
+
+impl TokenSink for AuditSink {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, line: u64) -> TokenSinkResult<()> {
+        let Token::TagToken(tag) = token else {
+            return TokenSinkResult::Continue;
+        };
+        let name = tag.name.to_string();
+
+        if tag.kind == TagKind::EndTag {
+            if name == "label" {
+                self.label_depth = self.label_depth.saturating_sub(1);
+            }
+            return TokenSinkResult::Continue;
+        }
+
+        if let Some(classes) = attribute(&tag, "class") {
+            if let (Some(text), Some(background)) = color_classes(&classes) {
+                let ratio = contrast(luminance(text).unwrap_or(0.0), luminance(background).unwrap_or(0.0));
+                if ratio < MIN_CONTRAST {
+                    let message = format!(
+                        "{} uses text-{} on bg-{} (roughly {:.1}:1 contrast, needs {}:1)",
+                        describe(&tag), text, background, ratio, MIN_CONTRAST
+                    );
+                    self.finding("low_contrast", message, Some(line));
+                }
+            }
+        }
+
+        match name.as_str() {
+            "html" => self.has_lang = Some(has_text(attribute(&tag, "lang"))),
+            "label" => {
+                if let Some(target) = attribute(&tag, "for") {
+                    self.label_targets.insert(target);
+                }
+                if !tag.self_closing {
+                    self.label_depth += 1;
+                }
+            }
+            "img" if attribute(&tag, "alt").is_none() => {
+                // alt="" is fine: it marks the image as decorative
+                let message = format!("{} has no alt attribute", describe(&tag));
+                self.finding("missing_alt", message, Some(line));
+            }
+            "input" | "select" | "textarea" => {
+                let input_type = attribute(&tag, "type").unwrap_or_default().to_ascii_lowercase();
+                let labelled = self.label_depth > 0
+                    || SELF_LABELLED_INPUTS.contains(&input_type.as_str())
+                    || has_text(attribute(&tag, "aria-label"))
+                    || has_text(attribute(&tag, "aria-labelledby"))
+                    || has_text(attribute(&tag, "title"));
+                if !labelled {
+                    self.unlabeled.push(Control {
+                        id: attribute(&tag, "id"),
+                        description: describe(&tag),
+                        line,
+                    });
+                }
+            }
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+pub fn audit(html: &str) -> A11yAudit {
+    let mut tokenizer = Tokenizer::new(AuditSink::default(), TokenizerOpts::default());
+    let mut input = BufferQueue::new();
+    input.push_back(StrTendril::from_slice(html));
+    let _ = tokenizer.feed(&mut input);
+    tokenizer.end();
+
+    let mut sink = tokenizer.sink;
+
+    // <label for> may come after the control it names
+    let unlabeled = std::mem::take(&mut sink.unlabeled);
+    for control in unlabeled {
+        if control.id.as_ref().is_some_and(|id| sink.label_targets.contains(id)) {
+            continue;
+        }
+        let message = format!(
+            "{} has no label (wrap it in <label>, point a <label for> at its id, or add aria-label)",
+            control.description
+        );
+        sink.finding("unlabeled_input", message, Some(control.line));
+    }
+
+    if sink.has_lang != Some(true) {
+        sink.finding("missing_lang", "<html> has no lang attribute".to_string(), None);
+    }
+
+    A11yAudit {
+        passed: sink.findings.is_empty(),
+        findings: sink.findings,
+    }
+}
+
+pub fn format_findings(audit: &A11yAudit) -> String {
+    audit
+        .findings
+        .iter()
+        .map(|finding| match finding.line {
+            Some(line) => format!("- Accessibility, line {}: {}", line, finding.message),
+            None => format!("- Accessibility: {}", finding.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tauri::command]
+pub async fn audit_a11y(html: String) -> Result<A11yAudit, String> {
+    Ok(audit(&html))
+}
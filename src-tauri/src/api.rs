@@ -0,0 +1,244 @@
+use std::sync::Arc;
+use std::time::Duration;
+use axum::extract::State as ApiState;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::JoinHandle;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::oneshot;
+
+use crate::{extract, generate_vibe_stream, healing, mcp, quality, AppState};
+
+// In-flight requests get this long to finish before a restart stops waiting for them
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Handle to the running server; sending on (or dropping) `shutdown` stops it
+pub struct ApiServer {
+    info: ApiServerInfo,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    pub fn info(&self) -> ApiServerInfo {
+        self.info.clone()
+    }
+
+    // Signals the server and waits for its task, which releases the port once it ends
+    pub async fn stop(self) {
+        let _ = self.shutdown.send(());
+        if tokio::time::timeout(STOP_TIMEOUT, self.task).await.is_err() {
+            tracing::warn!("API server still had requests in flight after {}s", STOP_TIMEOUT.as_secs());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerInfo {
+    pub url: String,
+    pub port: u16,
+    // Callers send it as "Authorization: Bearer <token>"
+    pub token: String,
+}
+
+#[derive(Clone)]
+struct ApiContext {
+    app_handle: AppHandle,
+    token: Arc<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateRequest {
+    prompt: String,
+    session_id: Option<String>,
+    use_components: Option<Vec<String>>,
+    preset: Option<String>,
+    constraints: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GenerateResponse {
+    response: String,
+    html: Option<String>,
+    quality: Option<quality::QualityScore>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HealRequest {
+    prompt: String,
+    max_attempts: Option<u32>,
+    fix_accessibility: Option<bool>,
+    use_components: Option<Vec<String>>,
+    session_id: Option<String>,
+    project_id: Option<String>,
+    preset: Option<String>,
+    constraints: Option<Vec<String>>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+fn failed(e: String) -> ApiError {
+    ApiError(StatusCode::INTERNAL_SERVER_ERROR, crate::keys::redact(&e))
+}
+
+// Compares every byte so the response time doesn't leak how much of the token matched
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn authorize(context: &ApiContext, headers: &HeaderMap) -> Result<(), ApiError> {
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if token_matches(given.trim(), &context.token) {
+        Ok(())
+    } else {
+        Err(ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token".to_string()))
+    }
+}
+
+async fn generate(
+    ApiState(context): ApiState<ApiContext>,
+    headers: HeaderMap,
+    Json(request): Json<GenerateRequest>,
+) -> Result<Json<GenerateResponse>, ApiError> {
+    authorize(&context, &headers)?;
+    if request.prompt.trim().is_empty() {
+        return Err(ApiError(StatusCode::BAD_REQUEST, "Prompt cannot be empty".to_string()));
+    }
+    let state: State<'_, AppState> = context.app_handle.state();
+    let silent = Channel::new(|_| Ok(()));
+    let response = generate_vibe_stream(
+        request.prompt,
+        None,
+        request.use_components,
+        request.session_id,
+        request.preset,
+        request.constraints,
+        silent,
+        context.app_handle.clone(),
+        state,
+    )
+    .await
+    .map_err(failed)?;
+
+    let html = extract::extract(&response).html;
+    let quality = html.as_deref().map(quality::score);
+    Ok(Json(GenerateResponse { response, html, quality }))
+}
+
+async fn heal(
+    ApiState(context): ApiState<ApiContext>,
+    headers: HeaderMap,
+    Json(request): Json<HealRequest>,
+) -> Result<Json<healing::HealResult>, ApiError> {
+    authorize(&context, &headers)?;
+    let state: State<'_, AppState> = context.app_handle.state();
+    let silent = Channel::new(|_| Ok(()));
+    let result = healing::generate_and_heal(
+        request.prompt,
+        request.max_attempts,
+        request.fix_accessibility,
+        request.use_components,
+        request.session_id,
+        request.project_id,
+        request.preset,
+        request.constraints,
+        silent,
+        context.app_handle.clone(),
+        state,
+    )
+    .await
+    .map_err(failed)?;
+    Ok(Json(result))
+}
+
+// Model Context Protocol over plain JSON-RPC POSTs; `vibecherry mcp` bridges stdio clients to it
+async fn mcp(
+    ApiState(context): ApiState<ApiContext>,
+    headers: HeaderMap,
+    Json(message): Json<serde_json::Value>,
+) -> Result<Response, ApiError> {
+    authorize(&context, &headers)?;
+    Ok(match mcp::handle(&context.app_handle, message).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    })
+}
+
+// Opt-in and loopback only: editors, scripts and browser extensions on this machine can
+// request vibes from the running app. Starting again replaces the previous server.
+#[tauri::command]
+pub async fn start_api_server(
+    port: Option<u16>,
+    token: Option<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ApiServerInfo, String> {
+    let token = match token.map(|t| t.trim().to_string()) {
+        Some(token) if token.len() < 16 => return Err("API token must be at least 16 characters".to_string()),
+        Some(token) => token,
+        None => uuid::Uuid::new_v4().simple().to_string(),
+    };
+    let previous = state.api_server.lock().unwrap().take();
+    if let Some(previous) = previous {
+        // Rebinding the same port only works once the old listener is gone
+        previous.stop().await;
+    }
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("Failed to bind API server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| format!("Failed to read API server address: {}", e))?.port();
+
+    let context = ApiContext { app_handle, token: Arc::new(token.clone()) };
+    let router = Router::new()
+        .route("/generate", post(generate))
+        .route("/heal", post(heal))
+        .route("/mcp", post(mcp))
+        .with_state(context);
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    let task = tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = stopped.await;
+        });
+        if let Err(e) = server.await {
+            tracing::error!("API server stopped: {}", e);
+        }
+    });
+
+    let info = ApiServerInfo { url: format!("http://127.0.0.1:{}", port), port, token };
+    tracing::info!(port, "API server listening");
+    *state.api_server.lock().unwrap() = Some(ApiServer { info: info.clone(), shutdown, task });
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_api_server(state: State<'_, AppState>) -> Result<bool, String> {
+    let server = state.api_server.lock().unwrap().take();
+    match server {
+        Some(server) => {
+            server.stop().await;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub async fn get_api_server(state: State<'_, AppState>) -> Result<Option<ApiServerInfo>, String> {
+    Ok(state.api_server.lock().unwrap().as_ref().map(ApiServer::info))
+}
@@ -0,0 +1,145 @@
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::{build_full_prompt_for, current_provider, current_settings, extract, healing, keys, run_generation_with, AppState};
+
+const MAX_MODELS: usize = 8;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub model: String,
+    // The model produced a response at all
+    pub completed: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub ttft_ms: Option<u64>,
+    pub total_tokens: u64,
+    pub tokens_per_sec: f64,
+    // An HTML document came back and passed static validation
+    pub valid: bool,
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub prompt: String,
+    pub results: Vec<BenchmarkResult>,
+    // Markdown comparison table of the results
+    pub table: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BenchmarkProgress {
+    model: String,
+    index: usize,
+    total: usize,
+}
+
+fn table(results: &[BenchmarkResult]) -> String {
+    let mut rows = vec![
+        "| Model | Result | Time | First token | Tokens/sec | Tokens |".to_string(),
+        "|---|---|---|---|---|---|".to_string(),
+    ];
+    for result in results {
+        let outcome = match (&result.error, result.valid) {
+            (Some(_), _) => "error".to_string(),
+            (None, true) => "valid".to_string(),
+            (None, false) => format!("{} problem(s)", result.problems.len()),
+        };
+        rows.push(format!(
+            "| {} | {} | {:.1}s | {} | {:.1} | {} |",
+            result.model,
+            outcome,
+            result.duration_ms as f64 / 1000.0,
+            result.ttft_ms.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "-".to_string()),
+            result.tokens_per_sec,
+            result.total_tokens
+        ));
+    }
+    rows.join("\n")
+}
+
+async fn run_one(state: &AppState, app_handle: &AppHandle, model: &str, prompt: &str) -> BenchmarkResult {
+    let mut result = BenchmarkResult {
+        model: model.to_string(),
+        completed: false,
+        error: None,
+        duration_ms: 0,
+        ttft_ms: None,
+        total_tokens: 0,
+        tokens_per_sec: 0.0,
+        valid: false,
+        problems: Vec::new(),
+    };
+
+    let mut settings = current_settings(state);
+    settings.model = Some(model.to_string());
+    let provider = settings.build_provider();
+    let options = settings.generation_options();
+    // A replayed response would time the cache, not the model
+    settings.response_cache = false;
+    // Files attached for the next real generation stay pending rather than going to one contestant
+    let state = AppState {
+        settings: Arc::new(Mutex::new(settings)),
+        context_files: Arc::new(Mutex::new(Vec::new())),
+        ..state.clone()
+    };
+
+    // Time generation, not the model loading from disk
+    if let Err(e) = provider.warm_up(&options).await {
+        tracing::warn!("Failed to warm up {} before benchmarking: {}", model, keys::redact(&e));
+    }
+
+    let full_prompt = build_full_prompt_for(&state, app_handle, provider.as_ref(), prompt).await;
+    let generated = match run_generation_with(&state, provider, prompt, &full_prompt, &options, &|_| {}).await {
+        Ok(generated) => generated,
+        Err(e) => {
+            result.error = Some(keys::redact(&e));
+            return result;
+        }
+    };
+
+    result.completed = true;
+    result.duration_ms = generated.metrics.duration_ms;
+    result.ttft_ms = generated.metrics.ttft_ms;
+    result.total_tokens = generated.metrics.total_tokens;
+    result.tokens_per_sec = generated.metrics.tokens_per_sec;
+    result.problems = match extract::extract(&generated.response).html {
+        Some(html) => healing::static_problems(&html),
+        None => vec!["- The response did not contain an HTML code block".to_string()],
+    };
+    result.valid = result.problems.is_empty();
+    result
+}
+
+// Runs one model at a time so they don't compete for the same RAM and GPU
+#[tauri::command]
+pub async fn benchmark_models(
+    prompt: String,
+    models: Vec<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BenchmarkReport, String> {
+    if models.is_empty() || models.len() > MAX_MODELS {
+        return Err(format!("Pick between 1 and {} models to benchmark", MAX_MODELS));
+    }
+    let installed = current_provider(&state)
+        .list_models()
+        .await
+        .map_err(|e| format!("Failed to list installed models: {}", e))?;
+    if let Some(missing) = models.iter().find(|model| !installed.contains(model)) {
+        return Err(format!("{} is not installed", missing));
+    }
+
+    let mut results = Vec::new();
+    for (index, model) in models.iter().enumerate() {
+        let progress = BenchmarkProgress { model: model.clone(), index, total: models.len() };
+        if let Err(e) = app_handle.emit("benchmark-progress", progress) {
+            tracing::warn!("Failed to emit benchmark progress: {}", e);
+        }
+        results.push(run_one(&state, &app_handle, model, &prompt).await);
+    }
+
+    Ok(BenchmarkReport { table: table(&results), prompt, results })
+}
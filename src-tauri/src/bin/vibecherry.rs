@@ -0,0 +1,4 @@
+// Headless entry point: `vibecherry gen "<prompt>" -o out.html`
+fn main() {
+    std::process::exit(vibe_cherry_lib::cli::main())
+}
@@ -0,0 +1,180 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::export;
+use crate::project_files;
+use crate::projects::{self, Project, ProjectMeta, HTML_FILE, THUMBNAIL_FILE};
+use crate::versions::{self, VersionInfo};
+use crate::{git, now_millis, shutdown};
+
+// A .vibe file is a zip holding everything needed to recreate a project elsewhere:
+//   manifest.json        format marker, project metadata and the version index
+//   index.html           the current document
+//   thumbnail.png        when a preview has been captured
+//   versions/v<n>.html   every recorded version
+//   files/<path>         sources of multi-file projects
+
+const MANIFEST_FILE: &str = "manifest.json";
+const FORMAT: &str = "vibe";
+const FORMAT_VERSION: u32 = 1;
+// Generated apps are a few hundred KB at most; anything far bigger isn't one of ours
+const MAX_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    format_version: u32,
+    // Vibe Cherry version that wrote the bundle
+    app_version: String,
+    exported_at: u64,
+    project: ProjectMeta,
+    versions: Vec<VersionInfo>,
+}
+
+fn build(app_handle: &AppHandle, project_id: &str) -> Result<Vec<u8>, String> {
+    let project = projects::read_project(app_handle, project_id)?;
+    let history = versions::list(app_handle, project_id)?;
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        format_version: FORMAT_VERSION,
+        app_version: app_handle.package_info().version.to_string(),
+        exported_at: now_millis(),
+        project: project.meta.clone(),
+        versions: history.clone(),
+    };
+    let manifest = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+
+    let mut entries: Vec<(String, Vec<u8>)> = vec![
+        (MANIFEST_FILE.to_string(), manifest.into_bytes()),
+        (HTML_FILE.to_string(), project.html.into_bytes()),
+    ];
+    if let Ok(thumbnail) = std::fs::read(projects::thumbnail_path(app_handle, project_id)?) {
+        entries.push((THUMBNAIL_FILE.to_string(), thumbnail));
+    }
+    for version in &history {
+        let html = versions::read_version(app_handle, project_id, version.number)?;
+        entries.push((format!("versions/v{}.html", version.number), html.into_bytes()));
+    }
+    for path in &project.meta.files {
+        let content = std::fs::read(project_files::file_path(app_handle, project_id, path)?)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        entries.push((format!("files/{}", path), content));
+    }
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .map_err(|e| format!("Failed to build bundle: {}", e))?;
+        writer.write_all(&contents).map_err(|e| format!("Failed to build bundle: {}", e))?;
+    }
+    let cursor = writer.finish().map_err(|e| format!("Failed to build bundle: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+fn read_entry<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {} from bundle: {}", name, e)),
+    };
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(format!("{} in the bundle is too large", name));
+    }
+    let mut contents = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut contents).map_err(|e| format!("Failed to read {} from bundle: {}", name, e))?;
+    Ok(Some(contents))
+}
+
+fn read_text<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Result<String, String> {
+    let contents = read_entry(archive, name)?.ok_or_else(|| format!("The bundle is missing {}", name))?;
+    String::from_utf8(contents).map_err(|_| format!("{} in the bundle is not valid UTF-8", name))
+}
+
+// Writes <project>.vibe to path, or asks where to save it when no path is given.
+// None means the dialog was cancelled.
+#[tauri::command]
+pub async fn export_vibe(project_id: String, path: Option<String>, app_handle: AppHandle) -> Result<Option<String>, String> {
+    let meta = projects::read_meta(&app_handle, &project_id)?;
+    let path: PathBuf = match path {
+        Some(path) => path.into(),
+        None => {
+            let name = export::file_name(&meta.name, "vibe");
+            match export::pick_save_path(&app_handle, &name, "Vibe project", &["vibe"]).await? {
+                Some(path) => path,
+                None => return Ok(None),
+            }
+        }
+    };
+    let bundle = build(&app_handle, &project_id)?;
+    std::fs::write(&path, bundle).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    tracing::info!(project = %project_id, path = %path.display(), "Exported bundle");
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+// Recreates the bundled project, versions included, under a fresh id so importing the same
+// bundle twice (or one exported from this machine) never overwrites anything
+#[tauri::command]
+pub async fn import_vibe(path: String, app_handle: AppHandle) -> Result<Project, String> {
+    let file = std::fs::File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|_| format!("{} is not a .vibe bundle", path))?;
+
+    let manifest: Manifest = serde_json::from_str(&read_text(&mut archive, MANIFEST_FILE)?)
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.format != FORMAT {
+        return Err(format!("{} is not a .vibe bundle", path));
+    }
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "This bundle was made by a newer Vibe Cherry ({}); update to import it",
+            manifest.app_version
+        ));
+    }
+
+    let html = read_text(&mut archive, HTML_FILE)?;
+    let mut history = Vec::with_capacity(manifest.versions.len());
+    for version in &manifest.versions {
+        let html = read_text(&mut archive, &format!("versions/v{}.html", version.number))?;
+        history.push((version.clone(), html));
+    }
+    let mut files = Vec::with_capacity(manifest.project.files.len());
+    for path in &manifest.project.files {
+        let path = project_files::validate_path(path)?;
+        let content = read_entry(&mut archive, &format!("files/{}", path))?
+            .ok_or_else(|| format!("The bundle is missing {}", path))?;
+        files.push((path, content));
+    }
+    let thumbnail = read_entry(&mut archive, THUMBNAIL_FILE)?;
+
+    let _saving = shutdown::saving();
+    let source = manifest.project;
+    let meta = ProjectMeta {
+        id: uuid::Uuid::new_v4().to_string(),
+        updated_at: now_millis(),
+        // Links only mean something if the other projects came along too
+        remixed_from: source.remixed_from.filter(|id| projects::read_meta(&app_handle, id).is_ok()),
+        merged_from: source.merged_from.into_iter().filter(|id| projects::read_meta(&app_handle, id).is_ok()).collect(),
+        ..source
+    };
+    projects::write_meta(&app_handle, &meta)?;
+    std::fs::write(projects::html_path(&app_handle, &meta.id)?, &html)
+        .map_err(|e| format!("Failed to write project HTML: {}", e))?;
+    if let Some(thumbnail) = thumbnail {
+        std::fs::write(projects::thumbnail_path(&app_handle, &meta.id)?, thumbnail)
+            .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+    }
+    for (path, content) in &files {
+        let target = project_files::file_path(&app_handle, &meta.id, path)?;
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&target, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    versions::import(&app_handle, &meta.id, &history)?;
+    git::commit_quietly(&app_handle, &meta.id, &format!("Import {}", meta.name));
+
+    tracing::info!(project = %meta.id, versions = history.len(), "Imported bundle");
+    Ok(Project { meta, html })
+}
@@ -0,0 +1,183 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::providers::GenerationOptions;
+use crate::{now_millis, AppState};
+
+const MEMORY_CAPACITY: usize = 64;
+// Responses are tens of KB, so this keeps the disk tier to a few tens of MB
+const DISK_CAPACITY: usize = 500;
+// Models and prompts move on; a month-old response is rarely requested again
+const DISK_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub response: String,
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub seed: Option<u32>,
+    pub created_at: u64,
+}
+
+// Sent alongside the replayed tokens so the UI can badge the result
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheHit {
+    pub cached: bool,
+    pub provider: String,
+    pub model: String,
+    pub created_at: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, CachedResponse>,
+    // Most recently used at the back
+    order: VecDeque<String>,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) {
+        self.order.retain(|k| k != key);
+        self.order.push_back(key.to_string());
+    }
+
+    fn insert(&mut self, key: &str, value: CachedResponse) {
+        self.entries.insert(key.to_string(), value);
+        self.touch(key);
+        while self.order.len() > MEMORY_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+// Drops expired entries, then the least recently written ones beyond DISK_CAPACITY
+fn prune(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let now = SystemTime::now();
+    let mut kept = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let modified = entry.metadata().and_then(|meta| meta.modified()).unwrap_or(now);
+        if now.duration_since(modified).unwrap_or_default() > DISK_MAX_AGE {
+            if let Err(e) = std::fs::remove_file(&path) {
+                tracing::warn!("Failed to remove cache entry: {}", e);
+            }
+        } else {
+            kept.push((modified, path));
+        }
+    }
+
+    if kept.len() > DISK_CAPACITY {
+        kept.sort();
+        for (_, path) in &kept[..kept.len() - DISK_CAPACITY] {
+            if let Err(e) = std::fs::remove_file(path) {
+                tracing::warn!("Failed to remove cache entry: {}", e);
+            }
+        }
+    }
+}
+
+// Two tiers: a small in-memory LRU in front of one JSON file per key on disk
+pub struct ResponseCache {
+    dir: Option<PathBuf>,
+    memory: Mutex<Lru>,
+}
+
+impl ResponseCache {
+    pub fn open(dir: &Path) -> Self {
+        let dir = match std::fs::create_dir_all(dir) {
+            Ok(()) => Some(dir.to_path_buf()),
+            Err(e) => {
+                tracing::warn!("Failed to create cache dir: {}, caching in memory only", e);
+                None
+            }
+        };
+        if let Some(dir) = &dir {
+            prune(dir);
+        }
+        Self { dir, memory: Mutex::new(Lru::default()) }
+    }
+
+    pub fn in_memory() -> Self {
+        Self { dir: None, memory: Mutex::new(Lru::default()) }
+    }
+
+    // Identical provider, model, options and prompt means an identical request
+    pub fn key(provider: &str, model: &str, options: &GenerationOptions, prompt: &str) -> String {
+        let options = serde_json::to_string(options).unwrap_or_default();
+        let mut hasher = blake3::Hasher::new();
+        for part in [provider, model, &options, prompt] {
+            hasher.update(part.as_bytes());
+            hasher.update(&[0]);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut memory = self.memory.lock().unwrap();
+        if let Some(hit) = memory.entries.get(key).cloned() {
+            memory.touch(key);
+            return Some(hit);
+        }
+
+        let path = self.dir.as_ref()?.join(format!("{}.json", key));
+        let hit: CachedResponse = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        memory.insert(key, hit.clone());
+        Some(hit)
+    }
+
+    pub fn put(&self, key: &str, provider: &str, model: &str, seed: Option<u32>, response: &str) {
+        let entry = CachedResponse {
+            response: response.to_string(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            seed,
+            created_at: now_millis(),
+        };
+
+        if let Some(dir) = &self.dir {
+            let written = serde_json::to_string(&entry)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(dir.join(format!("{}.json", key)), json).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                tracing::warn!("Failed to write cached response: {}", e);
+            }
+            prune(dir);
+        }
+        self.memory.lock().unwrap().insert(key, entry);
+    }
+
+    pub fn clear(&self) -> Result<usize, String> {
+        let mut memory = self.memory.lock().unwrap();
+        let mut removed = memory.entries.len();
+        *memory = Lru::default();
+
+        if let Some(dir) = &self.dir {
+            let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read cache dir: {}", e))?;
+            removed = 0;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove cache entry: {}", e))?;
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[tauri::command]
+pub async fn clear_response_cache(state: State<'_, AppState>) -> Result<usize, String> {
+    state.cache.clear()
+}
@@ -0,0 +1,225 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::guardrails::{self, GuardrailMode};
+use crate::{extract, healing, settings};
+
+const USAGE: &str = "Usage:
+  vibecherry gen \"<prompt>\" [-o out.html] [-m model] [-q]
+  vibecherry mcp [--url <url>] [--token <token>]
+
+gen generates a single-page app with the model configured in Vibe Cherry, without the GUI.
+mcp serves the running app's tools to an MCP client (e.g. Claude Desktop) over stdio;
+turn on the API server in the app first. The url and token default to the
+VIBECHERRY_API_URL and VIBECHERRY_API_TOKEN environment variables.
+
+Options:
+  -o, --output <file>   Write the HTML here instead of stdout
+  -m, --model <name>    Use this model instead of the configured one
+  -q, --quiet           Don't stream tokens to stderr while generating
+  -h, --help            Show this help";
+
+struct McpArgs {
+    url: String,
+    token: String,
+}
+
+struct GenArgs {
+    prompt: String,
+    output: Option<PathBuf>,
+    model: Option<String>,
+    quiet: bool,
+}
+
+fn parse(args: &[String]) -> Result<GenArgs, String> {
+    let mut prompt = None;
+    let mut output = None;
+    let mut model = None;
+    let mut quiet = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => {
+                output = Some(PathBuf::from(args.next().ok_or("--output needs a file name")?));
+            }
+            "-m" | "--model" => model = Some(args.next().ok_or("--model needs a model name")?.clone()),
+            "-q" | "--quiet" => quiet = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => return Err(format!("Unknown option: {}", flag)),
+            text if prompt.is_none() => prompt = Some(text.to_string()),
+            text => return Err(format!("Unexpected argument: {}", text)),
+        }
+    }
+
+    let prompt = prompt.filter(|p| !p.trim().is_empty()).ok_or("Missing prompt")?;
+    Ok(GenArgs { prompt, output, model, quiet })
+}
+
+fn parse_mcp(args: &[String]) -> Result<McpArgs, String> {
+    let mut url = std::env::var("VIBECHERRY_API_URL").ok();
+    let mut token = std::env::var("VIBECHERRY_API_TOKEN").ok();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => url = Some(args.next().ok_or("--url needs a value")?.clone()),
+            "--token" => token = Some(args.next().ok_or("--token needs a value")?.clone()),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+
+    Ok(McpArgs {
+        url: url.ok_or("Missing --url (or VIBECHERRY_API_URL)")?,
+        token: token.ok_or("Missing --token (or VIBECHERRY_API_TOKEN)")?,
+    })
+}
+
+fn block_on<F: std::future::Future<Output = Result<(), String>>>(future: F) -> Result<(), String> {
+    tokio::runtime::Runtime::new()
+        .map_err(|e| format!("Failed to start async runtime: {}", e))?
+        .block_on(future)
+}
+
+// Entry point of the vibecherry binary; returns the process exit code
+pub fn main() -> i32 {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None | Some("-h") | Some("--help") | Some("help") => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Some("gen") => parse(&args[1..]).and_then(|args| block_on(generate(args))),
+        Some("mcp") => parse_mcp(&args[1..]).and_then(|args| block_on(serve_mcp(args))),
+        Some(command) => Err(format!("Unknown command: {}\n\n{}", command, USAGE)),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+async fn generate(args: GenArgs) -> Result<(), String> {
+    let mut settings = settings::load_standalone();
+    if let Some(model) = args.model {
+        settings.model = Some(model);
+    }
+    let provider = settings.build_provider();
+    let options = settings.generation_options();
+
+    // Same system prompt and chat template as the app, minus the bits that live in app data
+    // (personas, few-shot examples, snippet retrieval)
+    let template = provider.template().await;
+    let full_prompt = template.assemble(&settings.system_prompt_for(template), &args.prompt);
+
+    eprintln!("Generating with {} ({})...", provider.model(), provider.name());
+    let quiet = args.quiet;
+    let response = provider
+        .stream(&full_prompt, &options, &|token| {
+            if !quiet {
+                eprint!("{}", token);
+                let _ = std::io::stderr().flush();
+            }
+        })
+        .await
+        .map_err(|e| format!("{} failed: {}", provider.name(), crate::keys::redact(&e)))?;
+    if !quiet {
+        eprintln!();
+    }
+
+    let mut html = extract::extract(&response).html.ok_or("The response did not contain an HTML code block")?;
+    if settings.guardrails != GuardrailMode::Off {
+        let (stripped, findings) = guardrails::strip(&html, &settings.blocked_patterns);
+        for finding in &findings {
+            eprintln!("guardrail: {} ({})", finding.reason, finding.excerpt);
+        }
+        if !findings.is_empty() && settings.guardrails == GuardrailMode::Block {
+            return Err("Generation blocked by content guardrails".to_string());
+        }
+        html = stripped;
+    }
+    for problem in healing::static_problems(&html) {
+        eprintln!("warning:\n{}", problem);
+    }
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, &html).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => println!("{}", html),
+    }
+    Ok(())
+}
+
+// Newline-delimited JSON-RPC on stdin/stdout, each message forwarded to the app's /mcp endpoint.
+// stdout carries protocol messages only; diagnostics go to stderr.
+async fn serve_mcp(args: McpArgs) -> Result<(), String> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let endpoint = format!("{}/mcp", args.url.trim_end_matches('/'));
+    let client = reqwest::Client::new();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await.map_err(|e| format!("Failed to read stdin: {}", e))? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                eprintln!("Ignoring invalid JSON-RPC message: {}", e);
+                continue;
+            }
+        };
+        let id = message.get("id").cloned();
+
+        let reply = match forward(&client, &endpoint, &args.token, &message).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                eprintln!("{}", e);
+                // Requests still need an answer or the client waits forever
+                id.map(|id| serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32603, "message": e }
+                }))
+            }
+        };
+        if let Some(reply) = reply {
+            let mut out = reply.to_string();
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await.map_err(|e| format!("Failed to write stdout: {}", e))?;
+            stdout.flush().await.map_err(|e| format!("Failed to write stdout: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+async fn forward(
+    client: &reqwest::Client,
+    endpoint: &str,
+    token: &str,
+    message: &serde_json::Value,
+) -> Result<Option<serde_json::Value>, String> {
+    let response = client
+        .post(endpoint)
+        .bearer_auth(token)
+        .json(message)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Vibe Cherry at {} (is the API server on?): {}", endpoint, e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::ACCEPTED {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Vibe Cherry API error ({}): {}", status, text));
+    }
+    response.json().await.map(Some).map_err(|e| format!("Invalid response from Vibe Cherry: {}", e))
+}
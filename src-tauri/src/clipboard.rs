@@ -0,0 +1,45 @@
+use base64::Engine;
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardFormat {
+    #[default]
+    Html,
+    // Fenced for pasting into chats, issues and READMEs
+    Markdown,
+    // Pastes straight into an address bar to run the app without saving it
+    DataUrl,
+}
+
+fn format_content(content: &str, format: ClipboardFormat) -> String {
+    match format {
+        ClipboardFormat::Html => content.to_string(),
+        ClipboardFormat::Markdown => format!("```html\n{}\n```", content.trim_end()),
+        ClipboardFormat::DataUrl => format!(
+            "data:text/html;charset=utf-8;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(content)
+        ),
+    }
+}
+
+// Goes through the native clipboard so the copy button also works where
+// navigator.clipboard is unavailable in the webview (notably Linux/Wayland)
+#[tauri::command]
+pub async fn copy_to_clipboard(
+    content: String,
+    format: Option<ClipboardFormat>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    if content.trim().is_empty() {
+        return Err("Nothing to copy".to_string());
+    }
+    let text = format_content(&content, format.unwrap_or_default());
+    app_handle
+        .clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok("Copied to clipboard".to_string())
+}
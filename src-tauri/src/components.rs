@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::now_millis;
+
+const COMPONENTS_FILE: &str = "components.json";
+// Every registered component's root element carries this, which is how we find it in the output
+const MARKER_ATTRIBUTE: &str = "data-component";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Component {
+    // Lowercase letters, numbers and dashes, e.g. "navbar" or "pricing-card"
+    pub name: String,
+    pub description: String,
+    // HTML, optionally with its own <style>/<script>
+    pub source: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ComponentAction {
+    List,
+    Register { name: String, description: Option<String>, source: String },
+    Remove { name: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentCheck {
+    pub requested: Vec<String>,
+    pub found: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+fn components_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+    Ok(dir.join(COMPONENTS_FILE))
+}
+
+pub fn load(app_handle: &AppHandle) -> Vec<Component> {
+    components_path(app_handle)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(app_handle: &AppHandle, components: &[Component]) -> Result<(), String> {
+    let path = components_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(components)
+        .map_err(|e| format!("Failed to serialize components: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write components: {}", e))
+}
+
+fn marker(name: &str) -> String {
+    format!("{}=\"{}\"", MARKER_ATTRIBUTE, name)
+}
+
+// Tag the first element in the source so the component can be recognised after generation
+fn with_marker(name: &str, source: &str) -> String {
+    if source.contains(&marker(name)) {
+        return source.to_string();
+    }
+    let lower = source.to_ascii_lowercase();
+    let root = lower.match_indices('<').map(|(pos, _)| pos).find(|&pos| {
+        let rest = &lower[pos + 1..];
+        rest.starts_with(|c: char| c.is_ascii_alphabetic()) && !rest.starts_with("style") && !rest.starts_with("script")
+    });
+    let Some(start) = root else { return source.to_string() };
+    let name_end = source[start + 1..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .map(|pos| start + 1 + pos)
+        .unwrap_or(source.len());
+    format!("{} {}{}", &source[..name_end], marker(name), &source[name_end..])
+}
+
+// Resolve requested names, failing on the first unknown one so typos surface immediately
+pub fn find(app_handle: &AppHandle, names: &[String]) -> Result<Vec<Component>, String> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let registered = load(app_handle);
+    names
+        .iter()
+        .map(|name| {
+            registered
+                .iter()
+                .find(|component| &component.name == name)
+                .cloned()
+                .ok_or_else(|| format!("Component {} is not registered", name))
+        })
+        .collect()
+}
+
+// Appends the component sources to the request the model sees
+pub fn with_components(request: &str, components: &[Component]) -> String {
+    if components.is_empty() {
+        return request.to_string();
+    }
+    let sections: Vec<String> = components
+        .iter()
+        .map(|component| {
+            let heading = match component.description.trim() {
+                "" => component.name.clone(),
+                description => format!("{}: {}", component.name, description),
+            };
+            format!("--- {} ---\n```html\n{}\n```", heading, component.source.trim())
+        })
+        .collect();
+    format!(
+        "{}\n\nUSE THESE EXISTING COMPONENTS. Include each one in the page with its markup, styles and scripts as given; \
+        only change text, links and data to fit the app, and keep the {} attribute on its root element:\n\n{}",
+        request,
+        MARKER_ATTRIBUTE,
+        sections.join("\n\n")
+    )
+}
+
+pub fn verify(html: &str, components: &[Component]) -> ComponentCheck {
+    let (found, missing): (Vec<String>, Vec<String>) = components
+        .iter()
+        .map(|component| component.name.clone())
+        .partition(|name| html.contains(&marker(name)));
+    ComponentCheck {
+        requested: components.iter().map(|component| component.name.clone()).collect(),
+        found,
+        missing,
+    }
+}
+
+pub fn emit_check(app_handle: &AppHandle, check: &ComponentCheck) {
+    if let Err(e) = app_handle.emit("vibe-components", check) {
+        tracing::warn!("Failed to emit component check: {}", e);
+    }
+}
+
+// Healing-loop problems for components the model dropped
+pub fn missing_problems(check: &ComponentCheck) -> Vec<String> {
+    check
+        .missing
+        .iter()
+        .map(|name| format!("- The {} component is missing: include it as given, with {}", name, marker(name)))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn manage_components(action: ComponentAction, app_handle: AppHandle) -> Result<Vec<Component>, String> {
+    let mut components = load(&app_handle);
+
+    match action {
+        ComponentAction::List => return Ok(components),
+        ComponentAction::Register { name, description, source } => {
+            let name = name.trim().to_ascii_lowercase();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(format!("Invalid component name: {} (use letters, numbers and dashes)", name));
+            }
+            if source.trim().is_empty() {
+                return Err("Components need some source".to_string());
+            }
+            // Registering an existing name replaces it
+            let created_at = components.iter().find(|c| c.name == name).map(|c| c.created_at).unwrap_or_else(now_millis);
+            components.retain(|c| c.name != name);
+            components.push(Component {
+                source: with_marker(&name, &source),
+                description: description.unwrap_or_default(),
+                name,
+                created_at,
+            });
+        }
+        ComponentAction::Remove { name } => {
+            let before = components.len();
+            components.retain(|c| c.name != name);
+            if components.len() == before {
+                return Err(format!("Component {} is not registered", name));
+            }
+        }
+    }
+
+    save(&app_handle, &components)?;
+    Ok(components)
+}
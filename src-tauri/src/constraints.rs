@@ -0,0 +1,168 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+// Hard requirements the caller attaches to one generation, e.g. "no external libraries",
+// "no localStorage" or "must work offline". All of them go into the prompt; the ones
+// recognized in check() are also verified against the output.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintResult {
+    pub constraint: String,
+    // None when there's no automatic check for this constraint
+    pub satisfied: Option<bool>,
+    // What broke it: the offending URLs or identifier
+    pub evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintCheck {
+    pub results: Vec<ConstraintResult>,
+    pub violated: Vec<String>,
+}
+
+// Where a URL can appear, and what kind of reference each spot is
+const URL_SPOTS: &[(&str, &str)] = &[
+    ("src=\"", "tag"),
+    ("src='", "tag"),
+    ("href=\"", "tag"),
+    ("href='", "tag"),
+    ("url(", "css"),
+    ("url(\"", "css"),
+    ("url('", "css"),
+    ("@import \"", "import"),
+    ("@import '", "import"),
+    ("@import url(", "import"),
+    ("fetch(\"", "fetch"),
+    ("fetch('", "fetch"),
+    ("fetch(`", "fetch"),
+];
+
+// Trimmed, non-empty and without duplicates
+pub fn normalize(constraints: Option<Vec<String>>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for constraint in constraints.unwrap_or_default() {
+        let constraint = constraint.trim().trim_end_matches('.').to_string();
+        if !constraint.is_empty() && !normalized.iter().any(|c| c.eq_ignore_ascii_case(&constraint)) {
+            normalized.push(constraint);
+        }
+    }
+    normalized
+}
+
+// Appends a CONSTRAINTS section to the request the model sees
+pub fn with_constraints(request: &str, constraints: &[String]) -> String {
+    if constraints.is_empty() {
+        return request.to_string();
+    }
+    let lines: Vec<String> = constraints.iter().map(|constraint| format!("- {}", constraint)).collect();
+    format!(
+        "{}\n\nCONSTRAINTS. These are hard requirements and override every other instruction, including \
+        the Tailwind CDN script; a page that breaks one of them is wrong:\n{}",
+        request,
+        lines.join("\n")
+    )
+}
+
+// Every http(s) URL in the page with the kind of reference it is: the tag name for
+// src/href attributes (script, link, img, a, ...), otherwise css, import or fetch
+fn urls(html: &str) -> Vec<(String, String)> {
+    // ASCII lowercasing keeps byte offsets, so positions in `lower` index `html` too
+    let lower = html.to_ascii_lowercase();
+    let mut found = Vec::new();
+    for (spot, kind) in URL_SPOTS {
+        for (at, _) in lower.match_indices(spot) {
+            let start = at + spot.len();
+            if !lower[start..].starts_with("http://") && !lower[start..].starts_with("https://") {
+                continue;
+            }
+            let end = html[start..]
+                .find(|c: char| matches!(c, '"' | '\'' | '`' | ')' | '>') || c.is_whitespace())
+                .map_or(html.len(), |len| start + len);
+            let kind = match (*kind, lower[..at].rfind('<')) {
+                ("tag", Some(open)) => lower[open + 1..]
+                    .split(|c: char| c.is_whitespace() || c == '>')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                (kind, _) => kind.to_string(),
+            };
+            found.push((kind, html[start..end].to_string()));
+        }
+    }
+    found.sort();
+    found.dedup();
+    found
+}
+
+// Anything the page loads over the network; links the user follows away don't count
+fn network_urls(html: &str) -> Vec<String> {
+    let mut urls: Vec<String> = urls(html).into_iter().filter(|(kind, _)| kind != "a").map(|(_, url)| url).collect();
+    urls.sort();
+    urls.dedup();
+    urls
+}
+
+// Scripts and stylesheets pulled in from elsewhere
+fn external_libraries(html: &str) -> Vec<String> {
+    urls(html)
+        .into_iter()
+        .filter(|(kind, _)| matches!(kind.as_str(), "script" | "link" | "import"))
+        .map(|(_, url)| url)
+        .collect()
+}
+
+// "no localStorage", "don't use eval()", "without jQuery" -> the identifier to look for
+fn forbidden_word(constraint: &str) -> Option<String> {
+    let lower = constraint.to_ascii_lowercase();
+    let word = ["no ", "don't use ", "do not use ", "without ", "never use ", "avoid "]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))?
+        .trim()
+        .trim_end_matches("()");
+    // Phrases ("no dark colors") can't be checked by searching for them
+    let identifier = word.len() > 2 && word.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '$'));
+    identifier.then(|| word.to_string())
+}
+
+fn check(html: &str, constraint: &str) -> ConstraintResult {
+    let lower = constraint.to_ascii_lowercase();
+    let evidence = if lower.contains("offline") || lower.contains("no network") || lower.contains("no internet") {
+        Some(network_urls(html))
+    } else if lower.contains("external librar") || lower.contains("no cdn") || lower.contains("no dependencies") {
+        Some(external_libraries(html))
+    } else {
+        let html = html.to_ascii_lowercase();
+        forbidden_word(constraint).map(|word| if html.contains(&word) { vec![word] } else { Vec::new() })
+    };
+    ConstraintResult {
+        constraint: constraint.to_string(),
+        satisfied: evidence.as_ref().map(Vec::is_empty),
+        evidence: evidence.unwrap_or_default(),
+    }
+}
+
+pub fn verify(html: &str, constraints: &[String]) -> ConstraintCheck {
+    let results: Vec<ConstraintResult> = constraints.iter().map(|constraint| check(html, constraint)).collect();
+    let violated = results
+        .iter()
+        .filter(|result| result.satisfied == Some(false))
+        .map(|result| result.constraint.clone())
+        .collect();
+    ConstraintCheck { results, violated }
+}
+
+pub fn emit_check(app_handle: &AppHandle, check: &ConstraintCheck) {
+    if let Err(e) = app_handle.emit("vibe-constraints", check) {
+        tracing::warn!("Failed to emit constraint check: {}", e);
+    }
+}
+
+// Healing-loop problems for the constraints the page breaks
+pub fn violation_problems(check: &ConstraintCheck) -> Vec<String> {
+    check
+        .results
+        .iter()
+        .filter(|result| result.satisfied == Some(false))
+        .map(|result| format!("- The page breaks the constraint \"{}\" ({})", result.constraint, result.evidence.join(", ")))
+        .collect()
+}
@@ -0,0 +1,172 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::prompts::estimate_tokens;
+use crate::{current_provider, current_settings, jobs, keys, AppState};
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You condense conversations between a user and an app-building assistant. \
+Write a compact bullet list of every requirement, feature, design decision and bug the user mentioned, \
+in the order they came up. Drop pleasantries and never include code. Keep it under 200 words.";
+// Each entry is one session's prefix; sessions beyond this re-summarize when they come back
+const SUMMARY_CACHE_CAPACITY: usize = 32;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySummarized {
+    pub summarized_turns: usize,
+    pub kept_turns: usize,
+    pub summary: String,
+}
+
+struct Turn {
+    role: String,
+    content: String,
+}
+
+#[derive(Default)]
+struct SummaryCache {
+    entries: HashMap<u64, String>,
+    // Oldest first
+    order: VecDeque<u64>,
+}
+
+impl SummaryCache {
+    fn insert(&mut self, key: u64, summary: String) {
+        if self.entries.insert(key, summary).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > SUMMARY_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+// Summaries keyed by the hash of the turns they cover, so each follow-up in a
+// long session doesn't re-summarize the same prefix
+fn summary_cache() -> &'static Mutex<SummaryCache> {
+    static CACHE: OnceLock<Mutex<SummaryCache>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+fn parse_turns(history: &[Value]) -> Vec<Turn> {
+    history
+        .iter()
+        .filter_map(|message| {
+            let role = message["role"].as_str()?;
+            let content = message["content"].as_str()?.trim();
+            (!content.is_empty()).then(|| Turn {
+                role: role.to_string(),
+                content: content.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn render(turns: &[Turn]) -> String {
+    turns
+        .iter()
+        .map(|turn| format!("{}: {}", turn.role.to_uppercase(), turn.content))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+async fn summarize(state: &AppState, turns: &[Turn]) -> Result<String, String> {
+    let transcript = render(turns);
+    let mut hasher = DefaultHasher::new();
+    transcript.hash(&mut hasher);
+    let key = hasher.finish();
+
+    if let Some(summary) = summary_cache().lock().unwrap().entries.get(&key) {
+        return Ok(summary.clone());
+    }
+
+    let settings = current_settings(state);
+    let provider = current_provider(state);
+    let prompt = provider.template().await.assemble(SUMMARY_SYSTEM_PROMPT, &transcript);
+
+    // A summary is a generation like any other: it queues, can be cancelled and times out
+    let job = state.jobs.start("summary", "Summarizing earlier conversation");
+    tokio::select! {
+        _ = job.wait_for_slot(settings.max_concurrent_generations) => {}
+        _ = job.cancelled() => return Err(jobs::CANCELLED.to_string()),
+    }
+    let generation = async {
+        tokio::select! {
+            result = provider.generate(&prompt, &settings.generation_options()) => Some(result),
+            _ = job.cancelled() => None,
+        }
+    };
+    let summary = match tokio::time::timeout(Duration::from_secs(settings.generation_timeout_secs), generation).await {
+        Ok(Some(result)) => result?.trim().to_string(),
+        Ok(None) => return Err(jobs::CANCELLED.to_string()),
+        Err(_) => return Err(format!("Summarization timed out after {}s", settings.generation_timeout_secs)),
+    };
+
+    summary_cache().lock().unwrap().insert(key, summary.clone());
+    Ok(summary)
+}
+
+// Fold prior chat turns into the user turn. When they no longer fit the context
+// budget, the oldest turns are replaced by a model-written summary.
+pub async fn with_history(
+    state: &AppState,
+    app_handle: &AppHandle,
+    history: &[Value],
+    prompt: &str,
+) -> String {
+    let turns = parse_turns(history);
+    if turns.is_empty() {
+        return prompt.to_string();
+    }
+
+    let budget = current_settings(state).context_token_budget;
+    if estimate_tokens(&render(&turns)) <= budget {
+        return format!("CONVERSATION SO FAR:\n{}\n\nCURRENT REQUEST:\n{}", render(&turns), prompt);
+    }
+
+    // Keep as many recent turns as fit in half the budget, verbatim
+    let mut kept = 0;
+    let mut used = 0;
+    for turn in turns.iter().rev() {
+        let cost = estimate_tokens(&turn.content);
+        if kept > 0 && used + cost > budget / 2 {
+            break;
+        }
+        used += cost;
+        kept += 1;
+    }
+    let split = turns.len() - kept;
+    let (older, recent) = turns.split_at(split);
+
+    let summary = match summarize(state, older).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            // Better to lose old turns than to fail the generation
+            tracing::warn!("History summarization failed: {}", keys::redact(&e));
+            return format!("CONVERSATION SO FAR:\n{}\n\nCURRENT REQUEST:\n{}", render(recent), prompt);
+        }
+    };
+
+    let payload = HistorySummarized {
+        summarized_turns: older.len(),
+        kept_turns: recent.len(),
+        summary: summary.clone(),
+    };
+    if let Err(e) = app_handle.emit("history-summarized", payload) {
+        tracing::warn!("Failed to emit history summary: {}", e);
+    }
+
+    format!(
+        "SUMMARY OF EARLIER CONVERSATION:\n{}\n\nRECENT CONVERSATION:\n{}\n\nCURRENT REQUEST:\n{}",
+        summary,
+        render(recent),
+        prompt
+    )
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+use serde::Serialize;
+use tauri::State;
+
+use crate::prompts::estimate_tokens;
+use crate::{current_provider, current_settings, keys, AppState};
+
+// Anything bigger is almost certainly not a hand-written spec
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+// PDFs carry fonts and images, so the file is far larger than its text
+const MAX_PDF_BYTES: u64 = 25 * 1024 * 1024;
+const TEXT_EXTENSIONS: &[&str] = &["md", "markdown", "txt", "json", "pdf"];
+
+const SPEC_SUMMARY_SYSTEM_PROMPT: &str = "You condense product specs for an app-building assistant. \
+Rewrite the document as a compact, complete list of requirements: screens, features, data, behaviours, \
+copy and visual style. Keep every concrete detail (names, numbers, colors, wording); drop background, \
+motivation and filler. Never write code.";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextFile {
+    pub name: String,
+    pub path: String,
+    pub tokens: usize,
+    // The original didn't fit the context budget and was condensed by the model
+    pub summarized: bool,
+    // Still over budget after summarizing (or summarizing failed), so the text was cut off
+    pub truncated: bool,
+    pub content: String,
+}
+
+fn read_document(path: &Path) -> Result<String, String> {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+    if !TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("Unsupported file type: {} (use .md, .txt, .json or .pdf)", path.display()));
+    }
+    let limit = if extension == "pdf" { MAX_PDF_BYTES } else { MAX_FILE_BYTES };
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if size > limit {
+        return Err(format!("{} is too large ({} KB, max {} KB)", path.display(), size / 1024, limit / 1024));
+    }
+
+    if extension == "pdf" {
+        return read_pdf(path);
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if extension != "json" {
+        return Ok(text);
+    }
+    // Minified JSON specs read far better (and cost no more) pretty-printed
+    Ok(serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or(text))
+}
+
+// Text layer only: scanned briefs without one come back empty and are rejected by the caller
+fn read_pdf(path: &Path) -> Result<String, String> {
+    let text = pdf_extract::extract_text(path).map_err(|e| format!("Failed to read PDF {}: {}", path.display(), e))?;
+
+    // Extraction leaves ragged whitespace and long runs of blank lines between blocks
+    let mut cleaned = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() && cleaned.last().is_some_and(|last: &&str| last.trim().is_empty()) {
+            continue;
+        }
+        cleaned.push(line);
+    }
+    Ok(cleaned.join("\n"))
+}
+
+fn truncate_to_tokens(text: &str, tokens: usize) -> String {
+    text.chars().take(tokens * 4).collect()
+}
+
+async fn summarize(state: &AppState, text: &str) -> Result<String, String> {
+    let provider = current_provider(state);
+    let prompt = provider.template().await.assemble(SPEC_SUMMARY_SYSTEM_PROMPT, text);
+    let summary = provider.generate(&prompt, &current_settings(state).generation_options()).await?;
+    Ok(summary.trim().to_string())
+}
+
+// Pending files go into the user turn of the next generation, then are cleared
+pub fn attach_pending(state: &AppState, user_turn: &str) -> String {
+    let files = std::mem::take(&mut *state.context_files.lock().unwrap());
+    if files.is_empty() {
+        return user_turn.to_string();
+    }
+    let sections: Vec<String> = files
+        .iter()
+        .map(|file| format!("--- {} ---\n{}", file.name, file.content.trim()))
+        .collect();
+    format!("REFERENCE DOCUMENTS:\n{}\n\nREQUEST:\n{}", sections.join("\n\n"), user_turn)
+}
+
+async fn load(state: &AppState, path: &Path, text: String) -> ContextFile {
+    // Leave room for the conversation and the request itself
+    let budget = current_settings(state).context_token_budget / 2;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+
+    let (content, summarized, truncated) = if estimate_tokens(&text) <= budget {
+        (text, false, false)
+    } else {
+        match summarize(state, &text).await {
+            Ok(summary) if estimate_tokens(&summary) <= budget => (summary, true, false),
+            Ok(summary) => (truncate_to_tokens(&summary, budget), true, true),
+            Err(e) => {
+                tracing::warn!("Failed to summarize {}: {}", name, keys::redact(&e));
+                (truncate_to_tokens(&text, budget), false, true)
+            }
+        }
+    };
+
+    ContextFile {
+        name,
+        path: path.display().to_string(),
+        tokens: estimate_tokens(&content),
+        summarized,
+        truncated,
+        content,
+    }
+}
+
+#[tauri::command]
+pub async fn load_context_file(path: String, state: State<'_, AppState>) -> Result<ContextFile, String> {
+    let path = std::path::PathBuf::from(path);
+    // PDF extraction can take a while on long documents
+    let reading = path.clone();
+    let text = tauri::async_runtime::spawn_blocking(move || read_document(&reading))
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))??;
+    if text.trim().is_empty() {
+        return Err(format!("{} has no text to use", path.display()));
+    }
+
+    let file = load(&state, &path, text).await;
+    let mut pending = state.context_files.lock().unwrap();
+    // Dropping the same file twice replaces the earlier copy
+    pending.retain(|existing| existing.path != file.path);
+    pending.push(file.clone());
+    Ok(file)
+}
+
+#[tauri::command]
+pub async fn list_context_files(state: State<'_, AppState>) -> Result<Vec<ContextFile>, String> {
+    Ok(state.context_files.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn clear_context_files(state: State<'_, AppState>) -> Result<String, String> {
+    state.context_files.lock().unwrap().clear();
+    Ok("Context files cleared".to_string())
+}
@@ -0,0 +1,96 @@
+use std::backtrace::Backtrace;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::now_millis;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub app_version: String,
+    pub timestamp: u64,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub last_command: Option<String>,
+    pub os: String,
+    pub arch: String,
+    pub backtrace: String,
+}
+
+fn last_command() -> &'static Mutex<Option<String>> {
+    static LAST_COMMAND: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+    LAST_COMMAND.get_or_init(Default::default)
+}
+
+// Called for every IPC invoke so a report can say what the user was doing
+pub fn note_command(name: &str) {
+    if let Ok(mut last) = last_command().lock() {
+        *last = Some(name.to_string());
+    }
+}
+
+pub fn crash_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?
+        .join("crashes"))
+}
+
+// Writes crash-<millis>.json for every panic, then defers to the default hook
+pub fn install(dir: PathBuf, app_version: String) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+
+        let report = CrashReport {
+            app_version: app_version.clone(),
+            timestamp: now_millis(),
+            message,
+            location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            thread: std::thread::current().name().map(|name| name.to_string()),
+            // try_lock: the panic may have happened while holding it
+            last_command: last_command().try_lock().ok().and_then(|last| last.clone()),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+
+        match write_report(&dir, &report) {
+            Ok(path) => tracing::error!("Panic: {} (report written to {})", report.message, path.display()),
+            Err(e) => tracing::error!("Panic: {} (failed to write report: {})", report.message, e),
+        }
+        previous(info);
+    }));
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create crash dir: {}", e))?;
+    let path = dir.join(format!("crash-{}.json", report.timestamp));
+    let json = serde_json::to_string_pretty(report).map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write crash report: {}", e))?;
+    Ok(path)
+}
+
+#[tauri::command]
+pub async fn get_last_crash_report(app_handle: AppHandle) -> Result<Option<CrashReport>, String> {
+    let dir = crash_dir(&app_handle)?;
+    let Ok(entries) = std::fs::read_dir(&dir) else { return Ok(None) };
+
+    // Millisecond timestamps have the same width for centuries, so name order is time order
+    let newest = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .max();
+    let Some(path) = newest else { return Ok(None) };
+
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read crash report: {}", e))?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse crash report: {}", e))
+}
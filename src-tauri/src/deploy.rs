@@ -0,0 +1,251 @@
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::{keys, projects};
+
+const NETLIFY_API: &str = "https://api.netlify.com/api/v1";
+const VERCEL_API: &str = "https://api.vercel.com";
+const CLOUDFLARE_API: &str = "https://api.cloudflare.com/client/v4";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeployTarget {
+    // Omit site_id to create a fresh site
+    Netlify { site_id: Option<String> },
+    Vercel { project_name: Option<String> },
+    CloudflarePages { account_id: String, project_name: String },
+}
+
+impl DeployTarget {
+    fn key_name(&self) -> &'static str {
+        match self {
+            DeployTarget::Netlify { .. } => "netlify",
+            DeployTarget::Vercel { .. } => "vercel",
+            DeployTarget::CloudflarePages { .. } => "cloudflare",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployProgress {
+    pub project_id: String,
+    pub target: String,
+    pub stage: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployResult {
+    pub url: String,
+    pub deploy_id: String,
+}
+
+struct Progress<'a> {
+    app_handle: &'a AppHandle,
+    project_id: &'a str,
+    target: &'static str,
+}
+
+impl Progress<'_> {
+    fn emit(&self, stage: &str, message: impl Into<String>) {
+        let payload = DeployProgress {
+            project_id: self.project_id.to_string(),
+            target: self.target.to_string(),
+            stage: stage.to_string(),
+            message: message.into(),
+        };
+        if let Err(e) = self.app_handle.emit("deploy-progress", payload) {
+            tracing::warn!("Failed to emit deploy progress: {}", e);
+        }
+    }
+}
+
+async fn check(response: reqwest::Response, service: &str) -> Result<Value, String> {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("{} API error ({}): {}", service, status, keys::redact(&text)));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {} response: {}", service, e))
+}
+
+fn zip_site(html: &str) -> Result<Vec<u8>, String> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer.start_file("index.html", zip::write::SimpleFileOptions::default())
+        .map_err(|e| format!("Failed to build site archive: {}", e))?;
+    writer.write_all(html.as_bytes())
+        .map_err(|e| format!("Failed to build site archive: {}", e))?;
+    let cursor = writer.finish().map_err(|e| format!("Failed to build site archive: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+async fn deploy_netlify(
+    client: &reqwest::Client,
+    token: &str,
+    site_id: Option<String>,
+    html: &str,
+    progress: &Progress<'_>,
+) -> Result<DeployResult, String> {
+    let site_id = match site_id {
+        Some(id) => id,
+        None => {
+            progress.emit("creating", "Creating Netlify site");
+            let site = check(
+                client.post(format!("{}/sites", NETLIFY_API)).bearer_auth(token).json(&json!({})).send().await
+                    .map_err(|e| format!("Failed to reach Netlify: {}", e))?,
+                "Netlify",
+            ).await?;
+            site["id"].as_str().unwrap_or_default().to_string()
+        }
+    };
+
+    progress.emit("uploading", "Uploading site archive");
+    let deploy = check(
+        client.post(format!("{}/sites/{}/deploys", NETLIFY_API, site_id))
+            .bearer_auth(token)
+            .header("Content-Type", "application/zip")
+            .body(zip_site(html)?)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Netlify: {}", e))?,
+        "Netlify",
+    ).await?;
+
+    let url = deploy["ssl_url"].as_str()
+        .or_else(|| deploy["url"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    Ok(DeployResult {
+        url,
+        deploy_id: deploy["id"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+async fn deploy_vercel(
+    client: &reqwest::Client,
+    token: &str,
+    project_name: Option<String>,
+    fallback_name: &str,
+    html: &str,
+    progress: &Progress<'_>,
+) -> Result<DeployResult, String> {
+    progress.emit("uploading", "Creating Vercel deployment");
+    let deployment = check(
+        client.post(format!("{}/v13/deployments", VERCEL_API))
+            .bearer_auth(token)
+            .json(&json!({
+                "name": project_name.unwrap_or_else(|| fallback_name.to_string()),
+                "target": "production",
+                "projectSettings": { "framework": null },
+                "files": [{ "file": "index.html", "data": html, "encoding": "utf-8" }],
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Vercel: {}", e))?,
+        "Vercel",
+    ).await?;
+
+    Ok(DeployResult {
+        url: format!("https://{}", deployment["url"].as_str().unwrap_or_default()),
+        deploy_id: deployment["id"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+// Same asset hash wrangler uses for Pages direct uploads
+fn pages_asset_hash(contents: &[u8], extension: &str) -> String {
+    use base64::Engine;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(contents);
+    let hash = blake3::hash(format!("{}{}", encoded, extension).as_bytes());
+    hash.to_hex()[..32].to_string()
+}
+
+async fn deploy_cloudflare(
+    client: &reqwest::Client,
+    token: &str,
+    account_id: &str,
+    project_name: &str,
+    html: &str,
+    progress: &Progress<'_>,
+) -> Result<DeployResult, String> {
+    let project_url = format!("{}/accounts/{}/pages/projects", CLOUDFLARE_API, account_id);
+
+    // Creating an existing project fails harmlessly, so just try it
+    progress.emit("creating", format!("Ensuring Pages project {}", project_name));
+    let _ = client.post(&project_url)
+        .bearer_auth(token)
+        .json(&json!({ "name": project_name, "production_branch": "main" }))
+        .send()
+        .await;
+
+    progress.emit("uploading", "Uploading to Cloudflare Pages");
+    let hash = pages_asset_hash(html.as_bytes(), "html");
+    let manifest = json!({ "/index.html": hash }).to_string();
+    let file = reqwest::multipart::Part::bytes(html.as_bytes().to_vec())
+        .file_name("index.html")
+        .mime_str("text/html")
+        .map_err(|e| format!("Failed to build upload: {}", e))?;
+    let form = reqwest::multipart::Form::new()
+        .text("manifest", manifest)
+        .part(hash, file);
+
+    let deployment = check(
+        client.post(format!("{}/{}/deployments", project_url, project_name))
+            .bearer_auth(token)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Cloudflare: {}", e))?,
+        "Cloudflare",
+    ).await?;
+
+    Ok(DeployResult {
+        url: deployment["result"]["url"].as_str().unwrap_or_default().to_string(),
+        deploy_id: deployment["result"]["id"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn deploy_project(
+    project_id: String,
+    target: DeployTarget,
+    token: Option<String>,
+    app_handle: AppHandle,
+) -> Result<DeployResult, String> {
+    let progress = Progress {
+        app_handle: &app_handle,
+        project_id: &project_id,
+        target: target.key_name(),
+    };
+
+    let token = token
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| keys::get_api_key(target.key_name()))
+        .ok_or_else(|| format!("No API token stored for {}", target.key_name()))?;
+
+    progress.emit("preparing", "Loading project");
+    let project = projects::read_project(&app_handle, &project_id)?;
+    let fallback_name = crate::export::file_name(&project.meta.name, "html")
+        .trim_end_matches(".html")
+        .to_string();
+    let client = reqwest::Client::new();
+
+    let result = match target {
+        DeployTarget::Netlify { site_id } => {
+            deploy_netlify(&client, &token, site_id, &project.html, &progress).await
+        }
+        DeployTarget::Vercel { project_name } => {
+            deploy_vercel(&client, &token, project_name, &fallback_name, &project.html, &progress).await
+        }
+        DeployTarget::CloudflarePages { account_id, project_name } => {
+            deploy_cloudflare(&client, &token, &account_id, &project_name, &project.html, &progress).await
+        }
+    };
+
+    match &result {
+        Ok(deployed) => progress.emit("done", deployed.url.clone()),
+        Err(e) => progress.emit("failed", e.clone()),
+    }
+    result
+}
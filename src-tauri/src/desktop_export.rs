@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use serde_json::json;
+use tauri::AppHandle;
+
+use crate::postprocess::{self, Stage};
+use crate::{export, html, projects};
+
+const ICONS: [(&str, &[u8]); 5] = [
+    ("32x32.png", include_bytes!("../icons/32x32.png")),
+    ("128x128.png", include_bytes!("../icons/128x128.png")),
+    ("128x128@2x.png", include_bytes!("../icons/128x128@2x.png")),
+    ("icon.icns", include_bytes!("../icons/icon.icns")),
+    ("icon.ico", include_bytes!("../icons/icon.ico")),
+];
+
+const MAIN_RS: &str = r#"// Prevents additional console window on Windows in release, DO NOT REMOVE!!
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+fn main() {
+    tauri::Builder::default()
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+"#;
+
+const BUILD_RS: &str = "fn main() {\n    tauri_build::build()\n}\n";
+
+fn cargo_toml(crate_name: &str, description: &str) -> String {
+    format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+description = {:?}
+edition = "2021"
+
+[build-dependencies]
+tauri-build = {{ version = "2", features = [] }}
+
+[dependencies]
+tauri = {{ version = "2", features = [] }}
+"#,
+        crate_name, description
+    )
+}
+
+fn readme(name: &str) -> String {
+    format!(
+        "# {}\n\nMade with Vibe Cherry.\n\n## Run\n\n```sh\nnpm install\nnpm run tauri dev\n```\n\n\
+        ## Build an installer\n\n```sh\nnpm run tauri build\n```\n\n\
+        The app itself is `dist/index.html`; edit it and rebuild.\n",
+        name
+    )
+}
+
+// Crate names can't start with a digit, and Cargo dislikes an all-numeric name
+fn crate_name(slug: &str) -> String {
+    if slug.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("vibe-{}", slug)
+    } else {
+        slug.to_string()
+    }
+}
+
+// Writes a ready-to-build Tauri 2 project into <path>/<app-name> and returns that folder
+#[tauri::command]
+pub async fn export_tauri(project_id: String, path: String, app_handle: AppHandle) -> Result<String, String> {
+    let project = projects::read_project(&app_handle, &project_id)?;
+    let name = html::extract_title(&project.html).unwrap_or_else(|| project.meta.name.clone());
+    let slug = crate_name(&export::slug(&name));
+
+    let root = PathBuf::from(path).join(&slug);
+    let occupied = std::fs::read_dir(&root).map(|mut entries| entries.next().is_some()).unwrap_or(false);
+    if occupied {
+        return Err(format!("{} already exists and is not empty", root.display()));
+    }
+
+    let package = json!({
+        "name": slug,
+        "private": true,
+        "version": "0.1.0",
+        "scripts": { "tauri": "tauri" },
+        "devDependencies": { "@tauri-apps/cli": "^2" }
+    });
+    let config = json!({
+        "$schema": "https://schema.tauri.app/config/2",
+        "productName": name,
+        "version": "0.1.0",
+        "identifier": format!("com.vibecherry.{}", slug),
+        "build": { "frontendDist": "../dist" },
+        "app": {
+            "windows": [{ "title": name, "width": 1024, "height": 768 }],
+            "security": { "csp": null }
+        },
+        "bundle": {
+            "active": true,
+            "targets": "all",
+            "icon": ICONS.iter().map(|(file, _)| format!("icons/{}", file)).collect::<Vec<_>>()
+        }
+    });
+    let capability = json!({
+        "identifier": "default",
+        "description": "Capability for the main window",
+        "windows": ["main"],
+        "permissions": ["core:default"]
+    });
+
+    let to_json = |value: &serde_json::Value| {
+        serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize {}: {}", slug, e))
+    };
+    let page = postprocess::run(&app_handle, Stage::Export, &project.html).html;
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("package.json".to_string(), to_json(&package)?.into_bytes()),
+        ("README.md".to_string(), readme(&name).into_bytes()),
+        (".gitignore".to_string(), b"node_modules\nsrc-tauri/target\nsrc-tauri/gen\n".to_vec()),
+        ("dist/index.html".to_string(), page.into_bytes()),
+        ("src-tauri/Cargo.toml".to_string(), cargo_toml(&slug, &project.meta.prompt).into_bytes()),
+        ("src-tauri/build.rs".to_string(), BUILD_RS.as_bytes().to_vec()),
+        ("src-tauri/src/main.rs".to_string(), MAIN_RS.as_bytes().to_vec()),
+        ("src-tauri/tauri.conf.json".to_string(), to_json(&config)?.into_bytes()),
+        ("src-tauri/capabilities/default.json".to_string(), to_json(&capability)?.into_bytes()),
+    ];
+    files.extend(ICONS.iter().map(|(file, bytes)| (format!("src-tauri/icons/{}", file), bytes.to_vec())));
+
+    for (file, contents) in &files {
+        let target = root.join(file);
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&target, contents).map_err(|e| format!("Failed to write {}: {}", file, e))?;
+    }
+
+    Ok(root.to_string_lossy().to_string())
+}
@@ -0,0 +1,191 @@
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+
+use crate::{context, extract, keys, prompts, sessions};
+use crate::{build_full_prompt, stream_from_provider, AppState, StreamEvent};
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EditResult {
+    pub html: String,
+    // patch when edit blocks were applied, full_rewrite when the model ignored the format
+    pub mode: String,
+    pub applied: usize,
+    // SEARCH sections that could not be located in the document
+    pub failed: Vec<String>,
+}
+
+struct EditBlock {
+    search: String,
+    replace: String,
+}
+
+fn parse_blocks(response: &str) -> Vec<EditBlock> {
+    let mut blocks = Vec::new();
+    let mut lines = response.lines();
+
+    while let Some(line) = lines.next() {
+        if line.trim() != SEARCH_MARKER {
+            continue;
+        }
+        let search: Vec<&str> = lines.by_ref().take_while(|l| l.trim() != DIVIDER).collect();
+        let replace: Vec<&str> = lines.by_ref().take_while(|l| l.trim() != REPLACE_MARKER).collect();
+        blocks.push(EditBlock {
+            search: search.join("\n"),
+            replace: replace.join("\n"),
+        });
+    }
+
+    blocks
+}
+
+// Exact match first, then a line-by-line match that ignores indentation drift
+fn apply_block(html: &str, block: &EditBlock) -> Option<String> {
+    if block.search.trim().is_empty() {
+        return None;
+    }
+    if let Some(pos) = html.find(&block.search) {
+        return Some(format!("{}{}{}", &html[..pos], block.replace, &html[pos + block.search.len()..]));
+    }
+
+    // Lines keep their terminators so CRLF documents and the final newline survive the splice
+    let doc_lines: Vec<&str> = html.split_inclusive('\n').collect();
+    let search_lines: Vec<&str> = block.search.lines().map(str::trim).collect();
+    let start = (0..(doc_lines.len() + 1).saturating_sub(search_lines.len())).find(|&i| {
+        search_lines
+            .iter()
+            .enumerate()
+            .all(|(j, wanted)| doc_lines.get(i + j).map(|l| l.trim()) == Some(*wanted))
+    })?;
+    let end = start + search_lines.len();
+
+    let newline = if html.contains("\r\n") { "\r\n" } else { "\n" };
+    let last = doc_lines[end - 1];
+    let ending = &last[last.trim_end_matches(['\r', '\n']).len()..];
+    let replace = if block.replace.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", block.replace.lines().collect::<Vec<_>>().join(newline), ending)
+    };
+    Some(format!("{}{}{}", doc_lines[..start].concat(), replace, doc_lines[end..].concat()))
+}
+
+pub fn apply_edits(html: &str, response: &str) -> Option<EditResult> {
+    let blocks = parse_blocks(response);
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let mut current = html.to_string();
+    let mut applied = 0;
+    let mut failed = Vec::new();
+    for block in &blocks {
+        match apply_block(&current, block) {
+            Some(updated) => {
+                current = updated;
+                applied += 1;
+            }
+            None => failed.push(block.search.clone()),
+        }
+    }
+
+    Some(EditResult {
+        html: current,
+        mode: "patch".to_string(),
+        applied,
+        failed,
+    })
+}
+
+#[tauri::command]
+pub async fn edit_vibe(
+    existing_html: String,
+    instruction: String,
+    session_id: Option<String>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<EditResult, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+
+    let user_turn = format!(
+        "{}\n\nCURRENT CODE:\n```html\n{}\n```\n\nREQUESTED CHANGE: {}",
+        prompts::EDIT_INSTRUCTIONS,
+        existing_html,
+        instruction
+    );
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let user_turn = context::with_history(&state, &app_handle, &history, &user_turn).await;
+    let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
+
+    let response = stream_from_provider(&state, &app_handle, &on_token, &instruction, &full_prompt, 1)
+        .await
+        .map_err(|e| keys::redact(&e))?;
+
+    let patch = apply_edits(&existing_html, &response);
+    let result = match patch {
+        Some(result) if result.applied > 0 => result,
+        // Some models ignore the edit format and send the whole document back
+        patch => match extract::extract(&response).html {
+            Some(html) => EditResult {
+                html,
+                mode: "full_rewrite".to_string(),
+                applied: 0,
+                failed: patch.map(|result| result.failed).unwrap_or_default(),
+            },
+            None if patch.is_some() => return Err("None of the model's edits matched the current code".to_string()),
+            None => return Err("The model did not return any applicable edits".to_string()),
+        },
+    };
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &instruction, &response);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(search: &str, replace: &str) -> String {
+        format!("{}\n{}\n{}\n{}\n{}", SEARCH_MARKER, search, DIVIDER, replace, REPLACE_MARKER)
+    }
+
+    #[test]
+    fn exact_match_is_replaced_in_place() {
+        let html = "<h1>Old</h1>\n<p>Keep</p>\n";
+        let result = apply_edits(html, &edit("<h1>Old</h1>", "<h1>New</h1>")).unwrap();
+        assert_eq!(result.html, "<h1>New</h1>\n<p>Keep</p>\n");
+        assert_eq!(result.applied, 1);
+        assert!(result.failed.is_empty());
+    }
+
+    #[test]
+    fn fuzzy_match_keeps_crlf_and_trailing_newline() {
+        let html = "<body>\r\n    <div>\r\n        <p>Old</p>\r\n    </div>\r\n</body>\r\n";
+        let result = apply_edits(html, &edit("<div>\n<p>Old</p>", "    <div>\n        <p>New</p>")).unwrap();
+        assert_eq!(result.html, "<body>\r\n    <div>\r\n        <p>New</p>\r\n    </div>\r\n</body>\r\n");
+        assert_eq!(result.applied, 1);
+    }
+
+    #[test]
+    fn fuzzy_match_on_the_last_line_without_newline() {
+        let html = "<p>Keep</p>\n  <p>Old</p>";
+        let result = apply_edits(html, &edit("    <p>Old</p>", "  <p>New</p>")).unwrap();
+        assert_eq!(result.html, "<p>Keep</p>\n  <p>New</p>");
+    }
+
+    #[test]
+    fn unmatched_search_is_reported_as_failed() {
+        let html = "<p>Keep</p>\n";
+        let result = apply_edits(html, &edit("<p>Missing</p>", "<p>New</p>")).unwrap();
+        assert_eq!(result.html, html);
+        assert_eq!(result.applied, 0);
+        assert_eq!(result.failed, vec!["<p>Missing</p>".to_string()]);
+    }
+}
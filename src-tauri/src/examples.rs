@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::now_millis;
+use crate::prompts::estimate_tokens;
+
+const EXAMPLES_FILE: &str = "examples.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Example {
+    pub id: String,
+    pub prompt: String,
+    pub html: String,
+    pub enabled: bool,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ExampleAction {
+    List,
+    Add { prompt: String, html: String },
+    Remove { id: String },
+    SetEnabled { id: String, enabled: bool },
+    Clear,
+}
+
+fn examples_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?;
+    Ok(dir.join(EXAMPLES_FILE))
+}
+
+pub fn load(app_handle: &AppHandle) -> Vec<Example> {
+    examples_path(app_handle)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(app_handle: &AppHandle, examples: &[Example]) -> Result<(), String> {
+    let path = examples_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(examples)
+        .map_err(|e| format!("Failed to serialize examples: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write examples: {}", e))
+}
+
+// Newest enabled examples first, stopping before the token budget is exceeded
+pub fn few_shot_section(app_handle: &AppHandle, token_budget: usize) -> Option<String> {
+    let mut examples: Vec<Example> = load(app_handle).into_iter().filter(|e| e.enabled).collect();
+    examples.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let mut used = 0;
+    let mut parts = Vec::new();
+    for example in examples {
+        let part = format!(
+            "User: {}\nAssistant:\n```html\n{}\n```",
+            example.prompt.trim(),
+            example.html.trim()
+        );
+        let cost = estimate_tokens(&part);
+        if used + cost > token_budget {
+            continue;
+        }
+        used += cost;
+        parts.push(part);
+    }
+
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "EXAMPLES OF THE STRUCTURE AND STYLE THIS USER LIKES:\n\n{}",
+        parts.join("\n\n")
+    ))
+}
+
+#[tauri::command]
+pub async fn manage_examples(action: ExampleAction, app_handle: AppHandle) -> Result<Vec<Example>, String> {
+    let mut examples = load(&app_handle);
+
+    match action {
+        ExampleAction::List => return Ok(examples),
+        ExampleAction::Add { prompt, html } => {
+            if prompt.trim().is_empty() || html.trim().is_empty() {
+                return Err("Examples need both a prompt and HTML".to_string());
+            }
+            examples.push(Example {
+                id: uuid::Uuid::new_v4().to_string(),
+                prompt,
+                html,
+                enabled: true,
+                created_at: now_millis(),
+            });
+        }
+        ExampleAction::Remove { id } => examples.retain(|e| e.id != id),
+        ExampleAction::SetEnabled { id, enabled } => {
+            let example = examples.iter_mut().find(|e| e.id == id)
+                .ok_or_else(|| format!("Example {} not found", id))?;
+            example.enabled = enabled;
+        }
+        ExampleAction::Clear => examples.clear(),
+    }
+
+    save(&app_handle, &examples)?;
+    Ok(examples)
+}
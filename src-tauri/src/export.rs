@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::postprocess::{self, Stage};
+use crate::{html, projects};
+
+const PWA_ICON_256: &[u8] = include_bytes!("../icons/128x128@2x.png");
+const PWA_ICON_512: &[u8] = include_bytes!("../icons/icon.png");
+
+// Cache-first worker so the installed app keeps working offline
+const SERVICE_WORKER: &str = r#"const CACHE = 'vibe-cache-v1';
+const ASSETS = ['./', './index.html', './manifest.json', './icon-256.png', './icon-512.png'];
+
+self.addEventListener('install', (event) => {
+  event.waitUntil(caches.open(CACHE).then((cache) => cache.addAll(ASSETS)));
+  self.skipWaiting();
+});
+
+self.addEventListener('activate', (event) => {
+  event.waitUntil(
+    caches.keys().then((keys) =>
+      Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key)))
+    )
+  );
+  self.clients.claim();
+});
+
+self.addEventListener('fetch', (event) => {
+  event.respondWith(
+    caches.match(event.request).then((cached) =>
+      cached ||
+      fetch(event.request).then((response) => {
+        const copy = response.clone();
+        caches.open(CACHE).then((cache) => cache.put(event.request, copy));
+        return response;
+      })
+    )
+  );
+});
+"#;
+
+const PWA_HEAD: &str = r##"    <link rel="manifest" href="manifest.json">
+    <meta name="theme-color" content="#581c87">
+    <meta name="mobile-web-app-capable" content="yes">
+    <meta name="apple-mobile-web-app-capable" content="yes">
+    <link rel="apple-touch-icon" href="icon-256.png">"##;
+
+const PWA_REGISTER: &str = r#"    <script>
+        if ('serviceWorker' in navigator) {
+            navigator.serviceWorker.register('sw.js');
+        }
+    </script>"#;
+
+// Ask the user where to save; None means the dialog was cancelled
+pub async fn pick_save_path(
+    app_handle: &AppHandle,
+    suggested_name: &str,
+    filter_name: &str,
+    extensions: &[&str],
+) -> Result<Option<PathBuf>, String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .set_file_name(suggested_name)
+        .add_filter(filter_name, extensions)
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+
+    match rx.await.map_err(|_| "Save dialog closed unexpectedly".to_string())? {
+        Some(path) => path.into_path().map(Some).map_err(|e| format!("Invalid save path: {}", e)),
+        None => Ok(None),
+    }
+}
+
+// Turn "My Cool App" into "my-cool-app"
+pub fn slug(suggested: &str) -> String {
+    let stem: String = suggested
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let stem = stem.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    if stem.is_empty() { "vibe".to_string() } else { stem }
+}
+
+// Turn "My Cool App" into "my-cool-app.html"
+pub fn file_name(suggested: &str, extension: &str) -> String {
+    let stem = slug(suggested.trim().trim_end_matches(&format!(".{}", extension)));
+    format!("{}.{}", stem, extension)
+}
+
+#[tauri::command]
+pub async fn export_html(
+    content: String,
+    suggested_name: Option<String>,
+    app_handle: AppHandle,
+) -> Result<Option<String>, String> {
+    let name = file_name(suggested_name.as_deref().unwrap_or("vibe"), "html");
+    let Some(path) = pick_save_path(&app_handle, &name, "HTML", &["html", "htm"]).await? else {
+        return Ok(None);
+    };
+
+    let content = postprocess::run(&app_handle, Stage::Export, &content).html;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+// Writes an installable copy of the project (manifest, icons, service worker)
+// into <project>/pwa and returns that folder
+#[tauri::command]
+pub async fn export_pwa(project_id: String, app_handle: AppHandle) -> Result<String, String> {
+    let project = projects::read_project(&app_handle, &project_id)?;
+    let name = html::extract_title(&project.html).unwrap_or_else(|| project.meta.name.clone());
+    let short_name: String = name.chars().take(12).collect();
+
+    let manifest = json!({
+        "name": name,
+        "short_name": short_name,
+        "description": project.meta.prompt,
+        "start_url": "./index.html",
+        "scope": "./",
+        "display": "standalone",
+        "background_color": "#000000",
+        "theme_color": "#581c87",
+        "icons": [
+            { "src": "icon-256.png", "sizes": "256x256", "type": "image/png", "purpose": "any" },
+            { "src": "icon-512.png", "sizes": "512x512", "type": "image/png", "purpose": "any maskable" }
+        ]
+    });
+
+    let page = postprocess::run(&app_handle, Stage::Export, &project.html).html;
+    let page = html::inject_into_head(&page, PWA_HEAD);
+    let page = html::inject_into_body(&page, PWA_REGISTER);
+
+    let dir = projects::project_dir(&app_handle, &project_id)?.join("pwa");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create PWA dir: {}", e))?;
+
+    let manifest = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    let files: [(&str, &[u8]); 5] = [
+        ("index.html", page.as_bytes()),
+        ("manifest.json", manifest.as_bytes()),
+        ("sw.js", SERVICE_WORKER.as_bytes()),
+        ("icon-256.png", PWA_ICON_256),
+        ("icon-512.png", PWA_ICON_512),
+    ];
+    for (name, contents) in files {
+        std::fs::write(dir.join(name), contents)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+
+    Ok(dir.to_string_lossy().to_string())
+}
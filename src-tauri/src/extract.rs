@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeBlock {
+    pub language: String,
+    // From a fence like ```css styles.css, used by multi-file generation
+    pub filename: Option<String>,
+    pub code: String,
+    // The closing fence was missing, usually a sign the model got cut off
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractedCode {
+    pub html: Option<String>,
+    pub other_blocks: Vec<CodeBlock>,
+    pub prose: String,
+}
+
+pub fn parse_blocks(response: &str) -> (Vec<CodeBlock>, String) {
+    let mut blocks = Vec::new();
+    let mut prose = Vec::new();
+    let mut current: Option<(String, String, Vec<&str>)> = None;
+    // The info string after the fence: "<language> [filename]"
+    let split_info = |info: &str| -> (String, Option<String>) {
+        let mut parts = info.split_whitespace();
+        let language = parts.next().unwrap_or_default().to_lowercase();
+        (language, parts.next().map(|name| name.to_string()))
+    };
+
+    for line in response.lines() {
+        let trimmed = line.trim_start();
+        let fence = if trimmed.starts_with("```") {
+            Some("```")
+        } else if trimmed.starts_with("~~~") {
+            Some("~~~")
+        } else {
+            None
+        };
+
+        let closes_block = matches!(
+            (&current, fence),
+            (Some((open, _, _)), Some(fence)) if open == fence && trimmed.trim() == fence
+        );
+
+        if closes_block {
+            let (_, info, lines) = current.take().unwrap();
+            let (language, filename) = split_info(&info);
+            blocks.push(CodeBlock {
+                language,
+                filename,
+                code: lines.join("\n"),
+                truncated: false,
+            });
+        } else if let Some((_, _, lines)) = current.as_mut() {
+            lines.push(line);
+        } else if let Some(fence) = fence {
+            let info = trimmed.trim_start_matches(fence).trim().to_string();
+            current = Some((fence.to_string(), info, Vec::new()));
+        } else {
+            prose.push(line);
+        }
+    }
+
+    if let Some((_, info, lines)) = current {
+        let (language, filename) = split_info(&info);
+        blocks.push(CodeBlock {
+            language,
+            filename,
+            code: lines.join("\n"),
+            truncated: true,
+        });
+    }
+
+    (blocks, prose.join("\n").trim().to_string())
+}
+
+fn looks_like_html(code: &str) -> bool {
+    let lower = code.to_ascii_lowercase();
+    lower.contains("<!doctype html") || lower.contains("<html")
+}
+
+// Models sometimes skip the fences entirely and just print the document
+fn bare_html(response: &str) -> Option<(String, String)> {
+    let lower = response.to_ascii_lowercase();
+    let start = lower.find("<!doctype html").or_else(|| lower.find("<html"))?;
+    let end = lower.rfind("</html>").map(|i| i + "</html>".len()).unwrap_or(response.len());
+    if end <= start {
+        return None;
+    }
+
+    let prose = format!("{}\n{}", &response[..start], &response[end..]);
+    Some((response[start..end].to_string(), prose.trim().to_string()))
+}
+
+pub fn extract(response: &str) -> ExtractedCode {
+    let (mut blocks, prose) = parse_blocks(response);
+
+    let primary = blocks
+        .iter()
+        .position(|b| b.language == "html" || b.language == "htm")
+        .or_else(|| blocks.iter().position(|b| looks_like_html(&b.code)));
+
+    if let Some(index) = primary {
+        let block = blocks.remove(index);
+        return ExtractedCode {
+            html: Some(block.code),
+            other_blocks: blocks,
+            prose,
+        };
+    }
+
+    if blocks.is_empty() {
+        if let Some((html, prose)) = bare_html(response) {
+            return ExtractedCode {
+                html: Some(html),
+                other_blocks: Vec::new(),
+                prose,
+            };
+        }
+    }
+
+    ExtractedCode {
+        html: None,
+        other_blocks: blocks,
+        prose,
+    }
+}
+
+#[tauri::command]
+pub async fn extract_code(response: String) -> Result<ExtractedCode, String> {
+    Ok(extract(&response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unclosed_fence_is_marked_truncated() {
+        let extracted = extract("Here you go:\n```html\n<!DOCTYPE html>\n<html><body>");
+        assert_eq!(extracted.html.as_deref(), Some("<!DOCTYPE html>\n<html><body>"));
+        let (blocks, _) = parse_blocks("```html\n<div>");
+        assert!(blocks[0].truncated);
+    }
+
+    #[test]
+    fn html_block_is_found_after_other_blocks() {
+        let response = "```css\nbody { margin: 0; }\n```\n\n```html\n<html></html>\n```";
+        let extracted = extract(response);
+        assert_eq!(extracted.html.as_deref(), Some("<html></html>"));
+        assert_eq!(extracted.other_blocks.len(), 1);
+        assert_eq!(extracted.other_blocks[0].language, "css");
+    }
+
+    #[test]
+    fn untagged_block_with_a_document_counts_as_html() {
+        let extracted = extract("```\n<!DOCTYPE html><html></html>\n```");
+        assert_eq!(extracted.html.as_deref(), Some("<!DOCTYPE html><html></html>"));
+    }
+
+    #[test]
+    fn prose_only_response_has_no_html() {
+        let extracted = extract("I can't build that, but here is an idea.");
+        assert!(extracted.html.is_none());
+        assert!(extracted.other_blocks.is_empty());
+        assert_eq!(extracted.prose, "I can't build that, but here is an idea.");
+    }
+
+    #[test]
+    fn tilde_fences_are_blocks() {
+        let (blocks, prose) = parse_blocks("Intro\n~~~js\nlet a = 1;\n```\n~~~\nOutro");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].language, "js");
+        // A backtick fence inside a tilde block is content, not a close
+        assert_eq!(blocks[0].code, "let a = 1;\n```");
+        assert!(!blocks[0].truncated);
+        assert_eq!(prose, "Intro\nOutro");
+    }
+
+    #[test]
+    fn info_string_carries_the_filename() {
+        let (blocks, _) = parse_blocks("```CSS styles.css\nbody {}\n```");
+        assert_eq!(blocks[0].language, "css");
+        assert_eq!(blocks[0].filename.as_deref(), Some("styles.css"));
+    }
+
+    #[test]
+    fn unfenced_document_falls_back_to_bare_html() {
+        let extracted = extract("Sure!\n<!DOCTYPE html>\n<html><body></body></html>\nEnjoy.");
+        assert_eq!(extracted.html.as_deref(), Some("<!DOCTYPE html>\n<html><body></body></html>"));
+        assert!(extracted.prose.starts_with("Sure!") && extracted.prose.ends_with("Enjoy."));
+    }
+}
@@ -0,0 +1,399 @@
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, FileName, SourceMap};
+use swc_ecma_codegen::{text_writer::JsWriter, Config, Emitter};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+
+use crate::validate::VOID_ELEMENTS;
+
+const INDENT: &str = "    ";
+
+// Elements whose contents are not markup; script and style get their own formatter,
+// pre and textarea are whitespace-sensitive and copied verbatim
+const RAW_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+
+fn indent(depth: usize) -> String {
+    INDENT.repeat(depth)
+}
+
+struct OpenTag {
+    name: String,
+    closing: bool,
+    quote: Option<char>,
+    name_done: bool,
+}
+
+#[derive(Default)]
+struct LineTags {
+    // Closing tags before any other content, which pull the line itself back out
+    leading_closes: usize,
+    opens: usize,
+    closes: usize,
+    // A raw element opened on this line and not closed on it
+    raw: Option<String>,
+}
+
+// Carries tag and comment state across lines so attributes split over several lines still count once
+#[derive(Default)]
+struct Scanner {
+    tag: Option<OpenTag>,
+    in_comment: bool,
+}
+
+impl Scanner {
+    fn scan_line(&mut self, line: &str) -> LineTags {
+        let mut tags = LineTags::default();
+        let mut only_closers = true;
+        let lower = line.to_ascii_lowercase();
+        let mut i = 0;
+
+        while i < line.len() {
+            let rest = &line[i..];
+            let Some(c) = rest.chars().next() else { break };
+
+            if self.in_comment {
+                match rest.find("-->") {
+                    Some(end) => {
+                        self.in_comment = false;
+                        i += end + 3;
+                    }
+                    None => i = line.len(),
+                }
+                continue;
+            }
+
+            if let Some(tag) = self.tag.as_mut() {
+                i += c.len_utf8();
+                match tag.quote {
+                    Some(quote) => {
+                        if c == quote {
+                            tag.quote = None;
+                        }
+                        continue;
+                    }
+                    None if c == '"' || c == '\'' => {
+                        tag.quote = Some(c);
+                        tag.name_done = true;
+                        continue;
+                    }
+                    None if c != '>' => {
+                        if !tag.name_done && (c.is_ascii_alphanumeric() || c == '-' || c == '!') {
+                            tag.name.push(c.to_ascii_lowercase());
+                        } else if !(tag.closing && tag.name.is_empty() && c == '/') {
+                            tag.name_done = true;
+                        }
+                        continue;
+                    }
+                    None => {}
+                }
+
+                let Some(tag) = self.tag.take() else { break };
+                let self_closing = line[..i - 1].trim_end().ends_with('/');
+                // <html> children stay flush left, matching how the prompts lay pages out
+                let counted = tag.name != "html" && !tag.name.starts_with('!');
+                if counted && tag.closing {
+                    tags.closes += 1;
+                    if only_closers {
+                        tags.leading_closes += 1;
+                    }
+                } else if counted && !self_closing && !VOID_ELEMENTS.contains(&tag.name.as_str()) {
+                    tags.opens += 1;
+                    only_closers = false;
+                    if RAW_ELEMENTS.contains(&tag.name.as_str()) {
+                        match lower[i..].find(&format!("</{}", tag.name)) {
+                            // Closed on the same line: skip the contents and scan the end tag
+                            Some(close) => i += close,
+                            None => {
+                                tags.raw = Some(tag.name);
+                                return tags;
+                            }
+                        }
+                    }
+                } else if !tag.closing {
+                    only_closers = false;
+                }
+                continue;
+            }
+
+            if rest.starts_with("<!--") {
+                self.in_comment = true;
+                only_closers = false;
+                i += 4;
+                continue;
+            }
+
+            let mut next = rest.chars().skip(1);
+            let (closing, starts_tag) = match next.next() {
+                Some('/') => (true, next.next().is_some_and(|n| n.is_ascii_alphabetic())),
+                Some(n) => (false, n.is_ascii_alphabetic() || n == '!'),
+                None => (false, false),
+            };
+            if c == '<' && starts_tag {
+                self.tag = Some(OpenTag {
+                    name: String::new(),
+                    closing,
+                    quote: None,
+                    name_done: false,
+                });
+                i += if closing { 2 } else { 1 };
+                continue;
+            }
+
+            if !c.is_whitespace() {
+                only_closers = false;
+            }
+            i += c.len_utf8();
+        }
+
+        tags
+    }
+}
+
+// Strip the shared leading whitespace, then indent every line to depth
+fn reindent(code: &str, depth: usize) -> Vec<String> {
+    let common = code
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    code.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent(depth), line.get(common..).unwrap_or(line).trim_end())
+            }
+        })
+        .collect()
+}
+
+fn format_embedded(element: &str, body: &str, depth: usize) -> Vec<String> {
+    if body.trim().is_empty() {
+        return Vec::new();
+    }
+    let formatted = match element {
+        "script" => format_js(body).ok(),
+        "style" => Some(format_css(body)),
+        _ => None,
+    };
+    // Scripts that don't parse (JSON, templates, broken code) keep their own layout
+    reindent(formatted.as_deref().unwrap_or(body), depth)
+}
+
+// Re-indents markup by nesting depth without moving tags between lines, so inline
+// whitespace (and therefore rendering) is left exactly as the model wrote it
+pub fn format_html(html: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut scanner = Scanner::default();
+    let mut depth = 0usize;
+    let mut lines = html.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if out.last().is_some_and(|last| !last.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+
+        let continuation = scanner.tag.is_some() || scanner.in_comment;
+        let tags = scanner.scan_line(trimmed);
+        let print_depth = depth.saturating_sub(tags.leading_closes) + usize::from(continuation);
+        out.push(format!("{}{}", indent(print_depth), trimmed));
+        depth = (depth + tags.opens).saturating_sub(tags.closes);
+
+        let Some(element) = tags.raw else { continue };
+        let end_tag = format!("</{}", element);
+        let mut body = Vec::new();
+        let mut closing_line = None;
+        for line in lines.by_ref() {
+            match line.to_ascii_lowercase().find(&end_tag) {
+                Some(pos) => {
+                    body.push(&line[..pos]);
+                    closing_line = Some(&line[pos..]);
+                    break;
+                }
+                None => body.push(line),
+            }
+        }
+
+        if element == "pre" || element == "textarea" {
+            out.extend(body.iter().map(|line| line.to_string()));
+            if let Some(rest) = closing_line {
+                match out.last_mut() {
+                    Some(last) => last.push_str(rest),
+                    None => out.push(rest.to_string()),
+                }
+            }
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+
+        out.extend(format_embedded(&element, &body.join("\n"), depth));
+        if let Some(rest) = closing_line {
+            let rest = rest.trim();
+            let tags = scanner.scan_line(rest);
+            out.push(format!("{}{}", indent(depth.saturating_sub(tags.leading_closes)), rest));
+            depth = (depth + tags.opens).saturating_sub(tags.closes);
+        }
+    }
+
+    while out.last().is_some_and(|last| last.is_empty()) {
+        out.pop();
+    }
+    out.join("\n") + "\n"
+}
+
+fn flush_css(out: &mut String, line: &mut String, depth: usize) {
+    let trimmed = line.trim();
+    if !trimmed.is_empty() {
+        out.push_str(&indent(depth));
+        out.push_str(trimmed);
+        out.push('\n');
+    }
+    line.clear();
+}
+
+// "color:red" -> "color: red"; only called for declarations, never selectors like a:hover
+fn flush_declaration(out: &mut String, line: &mut String, depth: usize) {
+    if let Some((property, value)) = line.split_once(':') {
+        if !property.contains(['(', '/', '"', '\'']) {
+            *line = format!("{}: {}", property.trim(), value.trim());
+        }
+    }
+    flush_css(out, line, depth);
+}
+
+// One declaration per line, rules separated by a blank line; strings, comments and
+// url(...) contents pass through untouched
+pub fn format_css(css: &str) -> String {
+    let mut out = String::new();
+    let mut line = String::new();
+    let mut depth = 0usize;
+    let mut parens = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = css.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            line.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    line.push(escaped);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                line.push(c);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                line.push(c);
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    line.push(next);
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                flush_css(&mut out, &mut line, depth);
+            }
+            '(' => {
+                parens += 1;
+                line.push(c);
+            }
+            ')' => {
+                parens = parens.saturating_sub(1);
+                line.push(c);
+            }
+            '{' if parens == 0 => {
+                let selector = line.trim().to_string();
+                line = format!("{} {{", selector);
+                flush_css(&mut out, &mut line, depth);
+                depth += 1;
+            }
+            ';' if parens == 0 => {
+                line.push(c);
+                flush_declaration(&mut out, &mut line, depth);
+            }
+            '}' if parens == 0 => {
+                flush_declaration(&mut out, &mut line, depth);
+                depth = depth.saturating_sub(1);
+                line.push(c);
+                flush_css(&mut out, &mut line, depth);
+                if depth == 0 {
+                    out.push('\n');
+                }
+            }
+            c if c.is_whitespace() => {
+                if !line.is_empty() && !line.ends_with(' ') {
+                    line.push(' ');
+                }
+            }
+            _ => line.push(c),
+        }
+    }
+
+    flush_css(&mut out, &mut line, depth);
+    out.trim_end().to_string() + "\n"
+}
+
+pub fn format_js(code: &str) -> Result<String, String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Anon, code.to_string());
+    let comments = SingleThreadedComments::default();
+    let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        Default::default(),
+        StringInput::from(&*fm),
+        Some(&comments),
+    );
+    let mut parser = Parser::new_from(lexer);
+    let program = parser
+        .parse_program()
+        .map_err(|e| format!("Failed to parse JavaScript: {}", e.kind().msg()))?;
+
+    let mut buffer = Vec::new();
+    {
+        let mut emitter = Emitter {
+            cfg: Config::default(),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut buffer, None),
+        };
+        emitter
+            .emit_program(&program)
+            .map_err(|e| format!("Failed to print JavaScript: {}", e))?;
+    }
+    String::from_utf8(buffer).map_err(|e| format!("Failed to print JavaScript: {}", e))
+}
+
+fn detect_language(content: &str) -> &'static str {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('<') {
+        "html"
+    } else if format_js(content).is_ok() {
+        "javascript"
+    } else {
+        "css"
+    }
+}
+
+#[tauri::command]
+pub async fn format_code(content: String, language: Option<String>) -> Result<String, String> {
+    let language = language
+        .map(|language| language.to_ascii_lowercase())
+        .unwrap_or_else(|| detect_language(&content).to_string());
+    match language.as_str() {
+        "html" | "htm" => Ok(format_html(&content)),
+        "css" => Ok(format_css(&content)),
+        "javascript" | "js" | "mjs" => format_js(&content),
+        other => Err(format!("Unsupported language: {}", other)),
+    }
+}
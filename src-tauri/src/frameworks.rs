@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+
+use crate::{context, current_provider, extract, keys, project_files, sessions, shutdown, stream_from_provider, AppState, StreamEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framework {
+    React,
+    Vue,
+    Svelte,
+}
+
+impl Framework {
+    fn name(&self) -> &'static str {
+        match self {
+            Framework::React => "React",
+            Framework::Vue => "Vue",
+            Framework::Svelte => "Svelte",
+        }
+    }
+
+    // The component the scaffold's main file mounts; the model must always write it
+    fn entry(&self) -> &'static str {
+        match self {
+            Framework::React => "src/App.jsx",
+            Framework::Vue => "src/App.vue",
+            Framework::Svelte => "src/App.svelte",
+        }
+    }
+
+    fn entry_languages(&self) -> &'static [&'static str] {
+        match self {
+            Framework::React => &["jsx", "javascript", "js", "react"],
+            Framework::Vue => &["vue"],
+            Framework::Svelte => &["svelte"],
+        }
+    }
+
+    fn system_prompt(&self) -> String {
+        let specifics = match self {
+            Framework::React => "Use React 18 function components and hooks. Components are .jsx files under src/components/. \
+                Import CSS files directly (import './App.css').",
+            Framework::Vue => "Use Vue 3 single-file components with <script setup>. Components are .vue files under src/components/. \
+                Put styles in <style scoped> blocks.",
+            Framework::Svelte => "Use Svelte 4 components. Components are .svelte files under src/components/. \
+                Put styles in each component's <style> block.",
+        };
+        format!(
+            "You are Vibe Cherry, an expert {name} developer building small, beautiful, fully working apps.\n\n\
+            The Vite project, package.json and src/main entry already exist. Write {entry} as the root component \
+            plus any components and CSS it imports.\n{specifics}\n\n\
+            OUTPUT FORMAT: every file in its own code block with its path after the language on the fence, e.g.\n\
+            ```{lang} {entry}\n...\n```\n\n\
+            Rules:\n\
+            - Only write files under src/\n\
+            - No dependencies beyond {name} itself\n\
+            - Every import must point at a file you wrote\n\
+            - Modern, polished design with smooth transitions; dark mode with pops of color by default",
+            name = self.name(),
+            entry = self.entry(),
+            lang = self.entry_languages()[0],
+            specifics = specifics,
+        )
+    }
+
+    fn scaffold(&self, name: &str) -> Vec<(&'static str, String)> {
+        let (dependencies, dev_dependencies, plugin_import, plugin_call, main_file, main_source, mount_id) = match self {
+            Framework::React => (
+                json!({ "react": "^18.3.1", "react-dom": "^18.3.1" }),
+                json!({ "vite": "^5.4.0", "@vitejs/plugin-react": "^4.3.0" }),
+                "import react from '@vitejs/plugin-react'",
+                "react()",
+                "src/main.jsx",
+                "import React from 'react'\nimport ReactDOM from 'react-dom/client'\nimport App from './App.jsx'\n\n\
+                ReactDOM.createRoot(document.getElementById('root')).render(\n  <React.StrictMode>\n    <App />\n  </React.StrictMode>,\n)\n",
+                "root",
+            ),
+            Framework::Vue => (
+                json!({ "vue": "^3.4.0" }),
+                json!({ "vite": "^5.4.0", "@vitejs/plugin-vue": "^5.1.0" }),
+                "import vue from '@vitejs/plugin-vue'",
+                "vue()",
+                "src/main.js",
+                "import { createApp } from 'vue'\nimport App from './App.vue'\n\ncreateApp(App).mount('#app')\n",
+                "app",
+            ),
+            Framework::Svelte => (
+                json!({}),
+                json!({ "vite": "^5.4.0", "svelte": "^4.2.0", "@sveltejs/vite-plugin-svelte": "^3.1.0" }),
+                "import { svelte } from '@sveltejs/vite-plugin-svelte'",
+                "svelte()",
+                "src/main.js",
+                "import App from './App.svelte'\n\nconst app = new App({ target: document.getElementById('app') })\n\nexport default app\n",
+                "app",
+            ),
+        };
+
+        let package = json!({
+            "name": name,
+            "private": true,
+            "version": "0.0.0",
+            "type": "module",
+            "scripts": { "dev": "vite", "build": "vite build", "preview": "vite preview" },
+            "dependencies": dependencies,
+            "devDependencies": dev_dependencies,
+        });
+        vec![
+            ("package.json", serde_json::to_string_pretty(&package).unwrap_or_default()),
+            (
+                "vite.config.js",
+                format!("import {{ defineConfig }} from 'vite'\n{}\n\nexport default defineConfig({{\n  plugins: [{}],\n}})\n", plugin_import, plugin_call),
+            ),
+            (
+                "index.html",
+                format!(
+                    "<!DOCTYPE html>\n<html lang=\"en\">\n  <head>\n    <meta charset=\"UTF-8\" />\n    \
+                    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\" />\n    <title>{}</title>\n  </head>\n  \
+                    <body>\n    <div id=\"{}\"></div>\n    <script type=\"module\" src=\"/{}\"></script>\n  </body>\n</html>\n",
+                    name, mount_id, main_file
+                ),
+            ),
+            (main_file, main_source.to_string()),
+            (".gitignore", "node_modules\ndist\n".to_string()),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameworkApp {
+    pub id: String,
+    pub framework: Framework,
+    pub path: String,
+    pub files: Vec<String>,
+    pub running: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppLog {
+    pub app_id: String,
+    // stdout, stderr or status
+    pub stream: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppReady {
+    pub app_id: String,
+    pub url: String,
+}
+
+// Dev servers we started; dropping a Child kills it (kill_on_drop)
+fn running_apps() -> &'static Mutex<HashMap<String, Child>> {
+    static APPS: OnceLock<Mutex<HashMap<String, Child>>> = OnceLock::new();
+    APPS.get_or_init(Default::default)
+}
+
+fn apps_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?
+        .join("apps"))
+}
+
+fn npm() -> &'static str {
+    if cfg!(windows) { "npm.cmd" } else { "npm" }
+}
+
+fn emit_log(app_handle: &AppHandle, app_id: &str, stream: &str, line: &str) {
+    let payload = AppLog {
+        app_id: app_id.to_string(),
+        stream: stream.to_string(),
+        line: line.to_string(),
+    };
+    if let Err(e) = app_handle.emit("framework-app-log", payload) {
+        tracing::warn!("Failed to emit app log: {}", e);
+    }
+}
+
+fn forward_lines(app_handle: AppHandle, app_id: String, stream: &'static str, pipe: impl AsyncRead + Unpin + Send + 'static) {
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(pipe).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            // Vite prints "Local:   http://localhost:5173/" once the server is up
+            if let Some(url) = line.split_whitespace().find(|word| word.starts_with("http://localhost")) {
+                let ready = AppReady { app_id: app_id.clone(), url: url.to_string() };
+                if let Err(e) = app_handle.emit("framework-app-ready", ready) {
+                    tracing::warn!("Failed to emit app ready: {}", e);
+                }
+            }
+            emit_log(&app_handle, &app_id, stream, &line);
+        }
+    });
+}
+
+fn spawn_logged(app_handle: &AppHandle, app_id: &str, dir: &Path, args: &[&str]) -> Result<Child, String> {
+    let mut cmd = Command::new(npm());
+    cmd.args(args)
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // kill_on_drop only reaches npm itself, not the node processes it starts
+    let mut child = shutdown::own(&mut cmd)
+        .spawn()
+        .map_err(|e| format!("Failed to run npm {}: {}", args.join(" "), e))?;
+    shutdown::track(&child);
+
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(app_handle.clone(), app_id.to_string(), "stdout", stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(app_handle.clone(), app_id.to_string(), "stderr", stderr);
+    }
+    Ok(child)
+}
+
+// npm install, then keep `npm run dev` running under our control
+async fn install_and_run(app_handle: AppHandle, app_id: String, dir: PathBuf) {
+    emit_log(&app_handle, &app_id, "status", "Running npm install");
+    let installed = match spawn_logged(&app_handle, &app_id, &dir, &["install"]) {
+        Ok(mut child) => {
+            let pid = child.id();
+            let installed = child.wait().await.map(|status| status.success()).unwrap_or(false);
+            if let Some(pid) = pid {
+                shutdown::untrack(pid);
+            }
+            installed
+        }
+        Err(e) => {
+            emit_log(&app_handle, &app_id, "status", &e);
+            false
+        }
+    };
+    if !installed {
+        emit_log(&app_handle, &app_id, "status", "npm install failed");
+        return;
+    }
+
+    emit_log(&app_handle, &app_id, "status", "Starting dev server");
+    match spawn_logged(&app_handle, &app_id, &dir, &["run", "dev"]) {
+        Ok(child) => {
+            running_apps().lock().unwrap().insert(app_id, child);
+        }
+        Err(e) => emit_log(&app_handle, &app_id, "status", &e),
+    }
+}
+
+fn parse_components(framework: Framework, response: &str) -> Vec<(String, String)> {
+    let (blocks, _) = extract::parse_blocks(response);
+    let mut files: Vec<(String, String)> = Vec::new();
+    for block in blocks {
+        let name = block.filename.clone().or_else(|| {
+            framework.entry_languages().contains(&block.language.as_str()).then(|| framework.entry().to_string())
+        });
+        let Some(path) = name.and_then(|name| project_files::validate_path(&name).ok()) else { continue };
+        // The scaffold owns everything outside src/
+        if !path.starts_with("src/") {
+            continue;
+        }
+        files.retain(|(existing, _)| existing != &path);
+        files.push((path, block.code));
+    }
+    files
+}
+
+fn write_files(dir: &Path, files: &[(String, String)]) -> Result<(), String> {
+    for (path, contents) in files {
+        let target = dir.join(path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&target, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn generate_framework_app(
+    prompt: String,
+    framework: Framework,
+    run: Option<bool>,
+    session_id: Option<String>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<FrameworkApp, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let user_turn = context::with_history(&state, &app_handle, &history, &prompt).await;
+    let provider = current_provider(&state);
+    let full_prompt = provider.template().await.assemble(&framework.system_prompt(), &user_turn);
+    let response = stream_from_provider(&state, &app_handle, &on_token, &prompt, &full_prompt, 1)
+        .await
+        .map_err(|e| keys::redact(&e))?;
+
+    let components = parse_components(framework, &response);
+    if !components.iter().any(|(path, _)| path == framework.entry()) {
+        return Err(format!("The model did not return {}", framework.entry()));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let dir = apps_dir(&app_handle)?.join(&id);
+    let package_name = format!("vibe-{}", &id[..8]);
+    let scaffold: Vec<(String, String)> = framework
+        .scaffold(&package_name)
+        .into_iter()
+        .map(|(path, contents)| (path.to_string(), contents))
+        .collect();
+    write_files(&dir, &scaffold)?;
+    write_files(&dir, &components)?;
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &prompt, &response);
+
+    let running = run.unwrap_or(false);
+    if running {
+        tauri::async_runtime::spawn(install_and_run(app_handle.clone(), id.clone(), dir.clone()));
+    }
+
+    Ok(FrameworkApp {
+        id,
+        framework,
+        path: dir.to_string_lossy().to_string(),
+        files: scaffold.into_iter().chain(components).map(|(path, _)| path).collect(),
+        running,
+    })
+}
+
+#[tauri::command]
+pub async fn stop_framework_app(app_id: String) -> Result<String, String> {
+    let child = running_apps().lock().unwrap().remove(&app_id);
+    match child {
+        Some(mut child) => {
+            if let Some(pid) = child.id() {
+                shutdown::kill_tree(pid);
+            }
+            child.kill().await.map_err(|e| format!("Failed to stop dev server: {}", e))?;
+            Ok(format!("Stopped {}", app_id))
+        }
+        None => Err(format!("App {} is not running", app_id)),
+    }
+}
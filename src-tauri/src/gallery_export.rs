@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::export;
+use crate::html::escape_html;
+use crate::postprocess::{self, Stage};
+use crate::projects::{self, ProjectMeta};
+
+const GALLERY_STYLE: &str = r#"        * { box-sizing: border-box; }
+        body { margin: 0; font-family: system-ui, -apple-system, sans-serif; background: #0b0b12; color: #f4f4f5; }
+        header { padding: 48px 24px 24px; text-align: center; }
+        header h1 { margin: 0 0 8px; font-size: 2.5rem; }
+        header p { margin: 0; color: #a1a1aa; }
+        main { display: grid; grid-template-columns: repeat(auto-fill, minmax(280px, 1fr)); gap: 24px; padding: 24px; max-width: 1200px; margin: 0 auto; }
+        .card { display: flex; flex-direction: column; background: #18181b; border: 1px solid #27272a; border-radius: 16px; overflow: hidden; color: inherit; text-decoration: none; transition: transform .15s, border-color .15s; }
+        .card:hover { transform: translateY(-4px); border-color: #a855f7; }
+        .thumb { aspect-ratio: 16 / 10; width: 100%; object-fit: cover; object-position: top; background: linear-gradient(135deg, #7c3aed, #db2777); display: flex; align-items: center; justify-content: center; font-size: 3rem; }
+        .body { padding: 16px; display: flex; flex-direction: column; gap: 8px; }
+        .body h2 { margin: 0; font-size: 1.1rem; }
+        .body p { margin: 0; color: #a1a1aa; font-size: .9rem; }
+        .prompt { font-style: italic; display: -webkit-box; -webkit-line-clamp: 3; -webkit-box-orient: vertical; overflow: hidden; }
+        .tags { display: flex; flex-wrap: wrap; gap: 6px; }
+        .tags span { background: #27272a; border-radius: 999px; padding: 2px 10px; font-size: .75rem; color: #d4d4d8; }
+        footer { padding: 32px; text-align: center; color: #71717a; font-size: .85rem; }"#;
+
+// Folder name under apps/, unique within this export
+fn unique_slug(meta: &ProjectMeta, taken: &mut HashSet<String>) -> String {
+    let base = export::slug(&meta.name);
+    let mut slug = base.clone();
+    let mut n = 2;
+    while !taken.insert(slug.clone()) {
+        slug = format!("{}-{}", base, n);
+        n += 1;
+    }
+    slug
+}
+
+fn card(meta: &ProjectMeta, slug: &str, has_thumbnail: bool) -> String {
+    let emoji = meta.emoji.as_deref().unwrap_or("🍒");
+    let thumb = if has_thumbnail {
+        format!("<img class=\"thumb\" src=\"apps/{}/thumbnail.png\" alt=\"\" loading=\"lazy\">", slug)
+    } else {
+        format!("<div class=\"thumb\">{}</div>", escape_html(emoji))
+    };
+    let description = meta
+        .description
+        .as_deref()
+        .filter(|description| !description.is_empty())
+        .map(|description| format!("\n                <p>{}</p>", escape_html(description)))
+        .unwrap_or_default();
+    let tags = if meta.tags.is_empty() {
+        String::new()
+    } else {
+        let tags: Vec<String> = meta.tags.iter().map(|tag| format!("<span>{}</span>", escape_html(tag))).collect();
+        format!("\n                <div class=\"tags\">{}</div>", tags.join(""))
+    };
+    format!(
+        r#"        <a class="card" href="apps/{slug}/index.html">
+            {thumb}
+            <div class="body">
+                <h2>{emoji} {name}</h2>{description}
+                <p class="prompt">“{prompt}”</p>{tags}
+            </div>
+        </a>"#,
+        slug = slug,
+        thumb = thumb,
+        emoji = escape_html(emoji),
+        name = escape_html(&meta.name),
+        description = description,
+        prompt = escape_html(meta.prompt.trim()),
+        tags = tags,
+    )
+}
+
+// Writes a static showcase of every saved project into <path>: an index.html with a card per
+// app (thumbnail, prompt, tags) and each app under apps/<slug>/. Favorites come first.
+// Returns the path of the index page.
+#[tauri::command]
+pub async fn export_gallery(path: String, title: Option<String>, app_handle: AppHandle) -> Result<String, String> {
+    let root = PathBuf::from(path);
+    let occupied = std::fs::read_dir(&root).map(|mut entries| entries.next().is_some()).unwrap_or(false);
+    if occupied {
+        return Err(format!("{} already exists and is not empty", root.display()));
+    }
+
+    let mut metas = projects::list_metas(&app_handle)?;
+    if metas.is_empty() {
+        return Err("There are no saved projects to export".to_string());
+    }
+    // Stable sort keeps most-recent-first within each group
+    metas.sort_by_key(|meta| !meta.favorite);
+
+    let mut taken = HashSet::new();
+    let mut cards = Vec::with_capacity(metas.len());
+    for meta in &metas {
+        let project = match projects::read_project(&app_handle, &meta.id) {
+            Ok(project) => project,
+            Err(e) => {
+                tracing::warn!("Leaving {} out of the gallery: {}", meta.id, e);
+                continue;
+            }
+        };
+        let slug = unique_slug(meta, &mut taken);
+        let dir = root.join("apps").join(&slug);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+        let page = postprocess::run(&app_handle, Stage::Export, &project.html).html;
+        std::fs::write(dir.join("index.html"), page)
+            .map_err(|e| format!("Failed to write {}: {}", dir.display(), e))?;
+        let thumbnail = projects::thumbnail_path(&app_handle, &meta.id)?;
+        let has_thumbnail = thumbnail.exists() && std::fs::copy(&thumbnail, dir.join("thumbnail.png")).is_ok();
+        cards.push(card(meta, &slug, has_thumbnail));
+    }
+
+    let title = title.filter(|title| !title.trim().is_empty()).unwrap_or_else(|| "My vibes".to_string());
+    let count = if cards.len() == 1 { "1 app".to_string() } else { format!("{} apps", cards.len()) };
+    let index = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <style>
+{style}
+    </style>
+</head>
+<body>
+    <header>
+        <h1>{title}</h1>
+        <p>{count}, each generated from the prompt on its card</p>
+    </header>
+    <main>
+{cards}
+    </main>
+    <footer>Made with Vibe Cherry</footer>
+</body>
+</html>
+"#,
+        title = escape_html(title.trim()),
+        style = GALLERY_STYLE,
+        count = count,
+        cards = cards.join("\n"),
+    );
+    let index_path = root.join("index.html");
+    std::fs::write(&index_path, index).map_err(|e| format!("Failed to write {}: {}", index_path.display(), e))?;
+    tracing::info!(apps = cards.len(), path = %root.display(), "Exported gallery");
+    Ok(index_path.to_string_lossy().to_string())
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+use git2::{Commit, IndexAddOption, Repository, Signature, Sort};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::projects;
+
+// Bookkeeping the app regenerates on its own; the repo tracks just the app's source
+const GITIGNORE: &str = "project.json\nthumbnail.png\nversions/\npwa/\n";
+const DEFAULT_LOG_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectCommit {
+    pub id: String,
+    pub short_id: String,
+    pub message: String,
+    pub author: String,
+    pub committed_at: u64,
+}
+
+fn open_or_init(dir: &Path) -> Result<Repository, String> {
+    if let Ok(repo) = Repository::open(dir) {
+        return Ok(repo);
+    }
+    let repo = Repository::init(dir).map_err(|e| format!("Failed to create project repo: {}", e))?;
+    std::fs::write(dir.join(".gitignore"), GITIGNORE).map_err(|e| format!("Failed to write .gitignore: {}", e))?;
+    Ok(repo)
+}
+
+// The user's own git identity when they have one configured
+fn signature(repo: &Repository) -> Result<Signature<'static>, String> {
+    repo.signature()
+        .or_else(|_| Signature::now("Vibe Cherry", "vibes@vibecherry.app"))
+        .map_err(|e| format!("Failed to create commit signature: {}", e))
+}
+
+// Commit everything in the project; None when nothing changed since the last commit
+pub fn commit(app_handle: &AppHandle, project_id: &str, message: &str) -> Result<Option<String>, String> {
+    let dir = projects::project_dir(app_handle, project_id)?;
+    let repo = open_or_init(&dir)?;
+
+    let mut index = repo.index().map_err(|e| format!("Failed to read repo index: {}", e))?;
+    index
+        .add_all(["*"], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all(["*"], None))
+        .and_then(|_| index.write())
+        .map_err(|e| format!("Failed to stage project files: {}", e))?;
+    let tree_id = index.write_tree().map_err(|e| format!("Failed to write tree: {}", e))?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    if parent.as_ref().is_some_and(|parent| parent.tree_id() == tree_id) {
+        return Ok(None);
+    }
+    let tree = repo.find_tree(tree_id).map_err(|e| format!("Failed to read tree: {}", e))?;
+    let signature = signature(&repo)?;
+    let message = match message.trim() {
+        "" => "Update project",
+        message => message,
+    };
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let id = repo
+        .commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .map_err(|e| format!("Failed to commit project: {}", e))?;
+    Ok(Some(id.to_string()))
+}
+
+// Git history is a convenience; failing to keep it never fails the save itself
+pub fn commit_quietly(app_handle: &AppHandle, project_id: &str, message: &str) {
+    if let Err(e) = commit(app_handle, project_id, message) {
+        tracing::warn!("Failed to commit project {}: {}", project_id, e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_project_log(
+    project_id: String,
+    limit: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<ProjectCommit>, String> {
+    let dir = projects::project_dir(&app_handle, &project_id)?;
+    // Projects saved before git integration have no repo until their next save
+    let Ok(repo) = Repository::open(&dir) else { return Ok(Vec::new()) };
+    if repo.head().is_err() {
+        return Ok(Vec::new());
+    }
+
+    let mut walk = repo.revwalk().map_err(|e| format!("Failed to read project log: {}", e))?;
+    walk.push_head()
+        .and_then(|_| walk.set_sorting(Sort::TIME))
+        .map_err(|e| format!("Failed to read project log: {}", e))?;
+
+    let commits = walk
+        .filter_map(|id| id.ok())
+        .filter_map(|id| repo.find_commit(id).ok())
+        .take(limit.unwrap_or(DEFAULT_LOG_LIMIT))
+        .map(|commit| {
+            let id = commit.id().to_string();
+            ProjectCommit {
+                short_id: id.chars().take(7).collect(),
+                id,
+                message: commit.message().unwrap_or_default().trim().to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                committed_at: commit.time().seconds().max(0) as u64 * 1000,
+            }
+        })
+        .collect();
+    Ok(commits)
+}
@@ -0,0 +1,176 @@
+use std::ops::Range;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{current_settings, AppState};
+
+// Stops a pathological page from looping forever while stripping
+const MAX_STRIPS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardrailMode {
+    Off,
+    // Remove the offending script, tag or line and keep the rest of the page
+    Strip,
+    // Reject the whole generation
+    Block,
+}
+
+struct Rule {
+    id: &'static str,
+    reason: &'static str,
+    // Any of these (lowercase) flags the surrounding script, tag or line...
+    patterns: &'static [&'static str],
+    // ...as long as it also contains one of these, when there are any
+    with: &'static [&'static str],
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        id: "remote_eval",
+        reason: "Runs code downloaded at runtime",
+        patterns: &["eval(", "new function("],
+        with: &["fetch(", "xmlhttprequest", "$.get(", "$.ajax(", "axios."],
+    },
+    Rule {
+        id: "remote_import",
+        reason: "Imports a script module from an arbitrary URL at runtime",
+        patterns: &["import(\"http", "import('http", "import(`http"],
+        with: &[],
+    },
+    Rule {
+        id: "crypto_miner",
+        reason: "Loads a cryptocurrency miner",
+        patterns: &[
+            "coinhive", "coin-hive", "cryptonight", "cryptoloot", "crypto-loot", "coinimp",
+            "webminepool", "jsecoin", "deepminer", "minero.cc", "webmr.js",
+        ],
+        with: &[],
+    },
+    Rule {
+        id: "cookie_exfiltration",
+        reason: "Sends document.cookie off the page",
+        patterns: &["document.cookie"],
+        with: &["fetch(", "xmlhttprequest", "sendbeacon", "websocket", ".src=", ".src =", "location.href", "location ="],
+    },
+    Rule {
+        id: "known_bad_cdn",
+        reason: "Loads from a CDN known to have served malware",
+        patterns: &[
+            "polyfill.io", "polyfill.com", "polyfillcache.com", "bootcdn.net", "bootcss.com",
+            "staticfile.net", "staticfile.org", "unionadjs.com", "xhsbpza.com", "union.macoms.la",
+            "newcrbpc.com",
+        ],
+        with: &[],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuardrailFinding {
+    // Rule id, or "custom" for settings.blocked_patterns
+    pub rule: String,
+    pub reason: String,
+    pub pattern: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuardrailReport {
+    // stripped or blocked
+    pub action: String,
+    pub findings: Vec<GuardrailFinding>,
+}
+
+// The script element, tag or line around `at`, whichever is the smallest thing that can go
+fn enclosing(text: &str, lower: &str, at: usize) -> Range<usize> {
+    if let Some(open) = lower[..at].rfind("<script") {
+        if !lower[open..at].contains("</script") {
+            let end = lower[at..]
+                .find("</script>")
+                .map(|i| at + i + "</script>".len())
+                .unwrap_or(text.len());
+            return open..end;
+        }
+    }
+    if let Some(open) = lower[..at].rfind('<') {
+        if !lower[open..at].contains('>') {
+            let end = lower[at..].find('>').map(|i| at + i + 1).unwrap_or(text.len());
+            return open..end;
+        }
+    }
+    let start = lower[..at].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let end = lower[at..].find('\n').map(|i| at + i).unwrap_or(text.len());
+    start..end
+}
+
+fn excerpt(text: &str, range: &Range<usize>) -> String {
+    let snippet: String = text[range.clone()].chars().take(120).collect();
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// First match of any rule (built-in or custom) and the span it condemns
+fn find(text: &str, custom: &[String]) -> Option<(GuardrailFinding, Range<usize>)> {
+    // Lowercasing can change byte lengths outside ASCII, which would break the ranges
+    let lower = text.to_ascii_lowercase();
+    let custom: Vec<String> = custom.iter().map(|p| p.trim().to_ascii_lowercase()).filter(|p| !p.is_empty()).collect();
+
+    let builtin = RULES.iter().flat_map(|rule| rule.patterns.iter().map(move |p| (rule.id, rule.reason, *p, rule.with)));
+    let custom = custom.iter().map(|p| ("custom", "Matches a blocked pattern from settings", p.as_str(), &[][..]));
+    for (rule, reason, pattern, with) in builtin.chain(custom) {
+        for (at, _) in lower.match_indices(pattern) {
+            let range = enclosing(text, &lower, at);
+            if with.is_empty() || with.iter().any(|w| lower[range.clone()].contains(w)) {
+                let finding = GuardrailFinding {
+                    rule: rule.to_string(),
+                    reason: reason.to_string(),
+                    pattern: pattern.to_string(),
+                    excerpt: excerpt(text, &range),
+                };
+                return Some((finding, range));
+            }
+        }
+    }
+    None
+}
+
+// Every finding in `text`, and the text with each offending span removed
+pub fn strip(text: &str, custom: &[String]) -> (String, Vec<GuardrailFinding>) {
+    let mut text = text.to_string();
+    let mut findings = Vec::new();
+    while findings.len() < MAX_STRIPS {
+        let Some((finding, range)) = find(&text, custom) else { break };
+        text.replace_range(range, "");
+        findings.push(finding);
+    }
+    (text, findings)
+}
+
+fn emit(app_handle: &AppHandle, report: &GuardrailReport) {
+    if let Err(e) = app_handle.emit("guardrail-blocked", report) {
+        tracing::warn!("Failed to emit guardrail report: {}", e);
+    }
+}
+
+// Applied to finished generations before anything can render them
+pub fn enforce(app_handle: &AppHandle, state: &AppState, response: String) -> Result<String, String> {
+    let settings = current_settings(state);
+    if settings.guardrails == GuardrailMode::Off {
+        return Ok(response);
+    }
+    let (stripped, findings) = strip(&response, &settings.blocked_patterns);
+    if findings.is_empty() {
+        return Ok(response);
+    }
+
+    let reasons = findings.iter().map(|f| f.reason.as_str()).collect::<Vec<_>>().join("; ");
+    let blocked = settings.guardrails == GuardrailMode::Block;
+    let action = if blocked { "blocked" } else { "stripped" };
+    tracing::warn!(action, "Guardrails flagged the generation: {}", reasons);
+    emit(app_handle, &GuardrailReport { action: action.to_string(), findings });
+
+    if blocked {
+        return Err(format!("Generation blocked by content guardrails: {}", reasons));
+    }
+    Ok(stripped)
+}
@@ -0,0 +1,324 @@
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::{a11y, components, constraints, context, extract, git, keys, presets, preview, projects, quality, sessions, shutdown, validate, versions};
+use crate::{build_full_prompt, current_provider, current_settings, stream_from_provider, AppState, StreamEvent};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const MAX_ATTEMPTS_LIMIT: u32 = 8;
+// Lines of code shown either side of a runtime error when the preview didn't send a snippet
+const SNIPPET_CONTEXT: usize = 2;
+// More than this and the fix prompt is mostly stack noise from one root cause
+const MAX_RUNTIME_ERRORS: usize = 10;
+
+// A console error or exception captured from the preview
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuntimeError {
+    pub message: String,
+    // Line in the previous code, as the preview reports it
+    pub line: Option<u32>,
+    // The offending code; cut from the previous code around `line` when missing
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealProgress {
+    pub attempt: u32,
+    pub max_attempts: u32,
+    // generating, validating, smoke_testing, passed, retrying, failed
+    pub stage: String,
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealAttempt {
+    pub attempt: u32,
+    // Heuristic quality score; None when the response had no HTML to score
+    pub quality: Option<u8>,
+    pub problems: Vec<String>,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealResult {
+    pub success: bool,
+    pub html: Option<String>,
+    pub response: String,
+    pub attempts: Vec<HealAttempt>,
+}
+
+fn emit_progress(
+    app_handle: &AppHandle,
+    attempt: u32,
+    max_attempts: u32,
+    stage: &str,
+    problems: &[String],
+) {
+    let payload = HealProgress {
+        attempt,
+        max_attempts,
+        stage: stage.to_string(),
+        problems: problems.to_vec(),
+    };
+    if let Err(e) = app_handle.emit("heal-progress", payload) {
+        tracing::warn!("Failed to emit heal progress: {}", e);
+    }
+}
+
+// Everything we can find without running the page
+pub fn static_problems(html: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let html_validation = validate::validate(html);
+    if !html_validation.issues.is_empty() {
+        problems.push(validate::format_issues(&html_validation));
+    }
+    let js_validation = validate::validate_scripts(html);
+    if !js_validation.errors.is_empty() {
+        problems.push(validate::format_js_errors(&js_validation));
+    }
+
+    problems
+}
+
+fn runtime_problems(result: &preview::SmokeTestResult) -> Vec<String> {
+    let mut problems: Vec<String> = result
+        .issues
+        .iter()
+        .map(|issue| match issue.line {
+            Some(line) => format!("- Runtime {} at line {}: {}", issue.kind, line, issue.message),
+            None => format!("- Runtime {}: {}", issue.kind, issue.message),
+        })
+        .collect();
+    if result.timed_out {
+        problems.push("- The page never finished loading (possible infinite loop or crash)".to_string());
+    }
+    problems
+}
+
+// Concrete findings beat a generic "be careful" when we have the broken code: what the
+// preview reported at runtime first, then whatever the validators can still find
+pub fn fix_guidance(previous_code: Option<&str>, errors: &[RuntimeError]) -> String {
+    let html = previous_code.map(|code| extract::extract(code).html.unwrap_or_else(|| code.to_string()));
+    let mut problems: Vec<String> = errors
+        .iter()
+        .take(MAX_RUNTIME_ERRORS)
+        .map(|error| format_runtime_error(error, html.as_deref()))
+        .collect();
+    if let Some(html) = &html {
+        problems.extend(static_problems(html));
+    }
+
+    if problems.is_empty() {
+        return "Be extra careful with syntax and completeness.".to_string();
+    }
+    format_problems(&problems)
+}
+
+fn format_runtime_error(error: &RuntimeError, html: Option<&str>) -> String {
+    let mut problem = match error.line {
+        Some(line) => format!("- Runtime error at line {}: {}", line, error.message.trim()),
+        None => format!("- Runtime error: {}", error.message.trim()),
+    };
+    let snippet = error
+        .source
+        .as_deref()
+        .map(str::trim_end)
+        .filter(|source| !source.trim().is_empty())
+        .map(str::to_string)
+        .or_else(|| Some(source_snippet(html?, error.line?)));
+    if let Some(snippet) = snippet.filter(|s| !s.is_empty()) {
+        problem.push_str(&format!("\n  ```\n{}\n  ```", indent(&snippet)));
+    }
+    problem
+}
+
+// The reported line with a little context, numbered like the preview reports it
+fn source_snippet(html: &str, line: u32) -> String {
+    let line = line as usize;
+    if line == 0 {
+        return String::new();
+    }
+    let first = line.saturating_sub(SNIPPET_CONTEXT).max(1);
+    html.lines()
+        .enumerate()
+        .skip(first - 1)
+        .take(line + SNIPPET_CONTEXT + 1 - first)
+        .map(|(index, text)| {
+            let marker = if index + 1 == line { ">" } else { " " };
+            format!("{} {:>4} | {}", marker, index + 1, text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("  {}", line)).collect::<Vec<_>>().join("\n")
+}
+
+fn format_problems(problems: &[String]) -> String {
+    format!(
+        "The previous version has these problems:\n{}\nFix every one of them and return the complete document.",
+        problems.join("\n")
+    )
+}
+
+// Retries must reach the model; a cached answer would only repeat the failure being fixed
+fn without_cache(state: &AppState) -> AppState {
+    let mut settings = current_settings(state);
+    settings.response_cache = false;
+    AppState {
+        settings: Arc::new(Mutex::new(settings)),
+        ..state.clone()
+    }
+}
+
+// Each attempt becomes the project's document, a version and a commit, so the git log
+// tells the same story as the version list
+fn save_attempt(app_handle: &AppHandle, project_id: &str, html: &str, note: String) {
+    let _saving = shutdown::saving();
+    versions::record_quietly(app_handle, project_id, html, "heal", Some(note.clone()));
+    let written = projects::html_path(app_handle, project_id)
+        .and_then(|path| std::fs::write(path, html).map_err(|e| format!("Failed to write project HTML: {}", e)));
+    match written {
+        Ok(()) => git::commit_quietly(app_handle, project_id, &note),
+        Err(e) => tracing::warn!("Failed to save heal attempt of {}: {}", project_id, e),
+    }
+}
+
+#[tauri::command]
+pub async fn generate_and_heal(
+    prompt: String,
+    max_attempts: Option<u32>,
+    fix_accessibility: Option<bool>,
+    use_components: Option<Vec<String>>,
+    session_id: Option<String>,
+    // Each attempt is kept in this project's version history
+    project_id: Option<String>,
+    preset: Option<String>,
+    // Hard requirements; recognized ones that the page breaks count as problems
+    constraints: Option<Vec<String>>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<HealResult, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+    let state = presets::scoped(&state, preset.as_deref())?;
+    let components = components::find(&app_handle, &use_components.unwrap_or_default())?;
+    let constraints = constraints::normalize(constraints);
+    let request = components::with_components(&prompt, &components);
+    let request = constraints::with_constraints(&request, &constraints);
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let request = context::with_history(&state, &app_handle, &history, &request).await;
+
+    let max_attempts = max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).clamp(1, MAX_ATTEMPTS_LIMIT);
+    let retry_state = without_cache(&state);
+    let mut attempts = Vec::new();
+    let mut last_html: Option<String> = None;
+    let mut last_response = String::new();
+    let mut last_problems: Vec<String> = Vec::new();
+
+    for attempt in 1..=max_attempts {
+        emit_progress(&app_handle, attempt, max_attempts, "generating", &[]);
+
+        let user_turn = match &last_html {
+            None if attempt == 1 => request.clone(),
+            // The last response had no document to fix, so only the problem goes back
+            None => format!(
+                "FIX ATTEMPT #{}\n{}\n\nOriginal request: {}",
+                attempt - 1,
+                format_problems(&last_problems),
+                request
+            ),
+            Some(previous) => format!(
+                "FIX ATTEMPT #{}\n{}\n\nOriginal request: {}\n\nPrevious code:\n```html\n{}\n```",
+                attempt - 1,
+                format_problems(&last_problems),
+                request,
+                previous
+            ),
+        };
+        let attempt_state = if attempt == 1 { &state } else { &retry_state };
+        let full_prompt = build_full_prompt(attempt_state, &app_handle, &user_turn).await;
+
+        let response = stream_from_provider(attempt_state, &app_handle, &on_token, &prompt, &full_prompt, attempt)
+            .await
+            .map_err(|e| keys::redact(&e))?;
+        last_response = response.clone();
+
+        emit_progress(&app_handle, attempt, max_attempts, "validating", &[]);
+        let Some(html) = extract::extract(&response).html else {
+            last_problems = vec!["- The response did not contain an HTML code block".to_string()];
+            attempts.push(HealAttempt { attempt, quality: None, problems: last_problems.clone(), passed: false });
+            emit_progress(&app_handle, attempt, max_attempts, "retrying", &last_problems);
+            continue;
+        };
+
+        let mut problems = static_problems(&html);
+        if problems.is_empty() {
+            emit_progress(&app_handle, attempt, max_attempts, "smoke_testing", &[]);
+            match preview::smoke_test_html(&app_handle, &html).await {
+                Ok(result) => problems.extend(runtime_problems(&result)),
+                Err(e) => tracing::warn!("Smoke test unavailable: {}", e),
+            }
+        }
+        if !components.is_empty() {
+            let check = components::verify(&html, &components);
+            problems.extend(components::missing_problems(&check));
+            components::emit_check(&app_handle, &check);
+        }
+        if !constraints.is_empty() {
+            let check = constraints::verify(&html, &constraints);
+            problems.extend(constraints::violation_problems(&check));
+            constraints::emit_check(&app_handle, &check);
+        }
+        // Accessibility findings are only worth a retry when the caller asked for them
+        if fix_accessibility.unwrap_or(false) {
+            let audit = a11y::audit(&html);
+            if !audit.passed {
+                problems.push(a11y::format_findings(&audit));
+            }
+        }
+        // A page that passes every check can still be a placeholder-filled stub
+        let score = quality::score(&html);
+        if problems.is_empty() && quality::below_threshold(&state, &score) {
+            problems.extend(quality::format_reasons(&score));
+        }
+
+        let passed = problems.is_empty();
+        attempts.push(HealAttempt { attempt, quality: Some(score.score), problems: problems.clone(), passed });
+        if let Some(project_id) = &project_id {
+            let note = format!("Heal attempt {}: {} problem(s)", attempt, problems.len());
+            save_attempt(&app_handle, project_id, &html, note);
+        }
+        last_html = Some(html);
+
+        if passed {
+            emit_progress(&app_handle, attempt, max_attempts, "passed", &[]);
+            break;
+        }
+
+        let stage = if attempt == max_attempts { "failed" } else { "retrying" };
+        emit_progress(&app_handle, attempt, max_attempts, stage, &problems);
+        last_problems = problems;
+    }
+
+    // The healed page becomes the project's current document, the same way an editor save does
+    if let (Some(project_id), Some(html)) = (&project_id, &last_html) {
+        let model = current_provider(&state).model().to_string();
+        projects::write_project(&app_handle, Some(project_id.clone()), None, prompt.clone(), html.clone(), model)?;
+    }
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &prompt, &last_response);
+
+    Ok(HealResult {
+        success: attempts.last().is_some_and(|attempt| attempt.passed),
+        html: last_html,
+        response: last_response,
+        attempts,
+    })
+}
@@ -0,0 +1,101 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::providers::{self, LlmProvider};
+use crate::{current_provider, current_settings, keys, now_millis, test_mode, AppState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    // Reachable, but the model isn't in memory yet (the next generation pays the load time)
+    Up,
+    Down,
+    ModelLoaded,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendHealth {
+    pub status: HealthStatus,
+    pub provider: String,
+    pub model: String,
+    pub error: Option<String>,
+    pub checked_at: u64,
+}
+
+// Last result, shared with the tray so the daemon is only pinged from one place
+fn latest_health() -> &'static Mutex<Option<BackendHealth>> {
+    static LATEST: OnceLock<Mutex<Option<BackendHealth>>> = OnceLock::new();
+    LATEST.get_or_init(Default::default)
+}
+
+pub fn latest() -> Option<BackendHealth> {
+    latest_health().lock().unwrap().clone()
+}
+
+async fn probe(provider: &dyn LlmProvider) -> BackendHealth {
+    let status = match provider.list_models().await {
+        Err(e) => Err(e),
+        // Only Ollama says what it has in memory; other backends serve once they answer
+        Ok(_) if provider.name() == "ollama" => match providers::loaded_models().await {
+            // ps always shows the tag, settings may not
+            Ok(loaded) if loaded.iter().any(|name| {
+                name == provider.model() || name.strip_suffix(":latest") == Some(provider.model())
+            }) => Ok(HealthStatus::ModelLoaded),
+            _ => Ok(HealthStatus::Up),
+        },
+        Ok(_) => Ok(HealthStatus::ModelLoaded),
+    };
+    let (status, error) = match status {
+        Ok(status) => (status, None),
+        Err(e) => (HealthStatus::Down, Some(keys::redact(&e))),
+    };
+    BackendHealth {
+        status,
+        provider: provider.name().to_string(),
+        model: provider.model().to_string(),
+        error,
+        checked_at: now_millis(),
+    }
+}
+
+// Probe now, remember the result and emit backend-health if anything but the time changed
+async fn check(app_handle: &AppHandle, state: &AppState) -> BackendHealth {
+    let health = probe(current_provider(state).as_ref()).await;
+    let previous = latest_health().lock().unwrap().replace(health.clone());
+    let changed = match &previous {
+        Some(previous) => {
+            (previous.status, &previous.provider, &previous.model) != (health.status, &health.provider, &health.model)
+        }
+        None => true,
+    };
+    if changed {
+        if let Err(e) = app_handle.emit("backend-health", &health) {
+            tracing::warn!("Failed to emit backend health: {}", e);
+        }
+    }
+    health
+}
+
+// Poll every settings.health_check_secs (0 pauses polling) for the UI's status dot
+pub fn spawn_polling(app_handle: AppHandle) {
+    if test_mode() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app_handle.state::<AppState>();
+            let interval = current_settings(&state).health_check_secs;
+            if interval > 0 {
+                check(&app_handle, &state).await;
+            }
+            tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_backend_health(app_handle: AppHandle, state: State<'_, AppState>) -> Result<BackendHealth, String> {
+    Ok(check(&app_handle, &state).await)
+}
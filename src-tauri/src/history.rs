@@ -0,0 +1,269 @@
+use std::path::Path;
+use std::sync::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{now_millis, AppState};
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    pub id: i64,
+    pub prompt: String,
+    pub response: String,
+    pub provider: String,
+    pub model: String,
+    pub duration_ms: u64,
+    // success, error, mock, timeout or cached
+    pub status: String,
+    pub error: Option<String>,
+    pub seed: Option<u32>,
+    pub temperature: Option<f32>,
+    pub created_at: u64,
+}
+
+pub struct NewGeneration<'a> {
+    pub prompt: &'a str,
+    pub response: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub duration_ms: u64,
+    pub status: &'a str,
+    pub error: Option<&'a str>,
+    // What it takes to reproduce the output: exact prompt sent plus sampling settings
+    pub full_prompt: Option<&'a str>,
+    pub seed: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayInput {
+    pub prompt: String,
+    pub full_prompt: String,
+    pub seed: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryFilter {
+    pub model: Option<String>,
+    pub status: Option<String>,
+    // Substring match against the prompt
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    pub items: Vec<GenerationRecord>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryStore {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+        }
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open history db: {}", e))?;
+        Self::init(conn)
+    }
+
+    // Used when the on-disk database can't be opened so generation still works
+    pub fn in_memory() -> Self {
+        let conn = Connection::open_in_memory().expect("in-memory sqlite");
+        Self::init(conn).expect("in-memory history schema")
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS generations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                prompt TEXT NOT NULL,
+                response TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                error TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_generations_created ON generations(created_at);",
+        )
+        .map_err(|e| format!("Failed to create history schema: {}", e))?;
+
+        // Columns added after the first release
+        add_column(&conn, "full_prompt", "TEXT")?;
+        add_column(&conn, "seed", "INTEGER")?;
+        add_column(&conn, "temperature", "REAL")?;
+
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    pub fn record(&self, generation: NewGeneration) -> Result<i64, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO generations
+                (prompt, response, provider, model, duration_ms, status, error, created_at, full_prompt, seed, temperature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                generation.prompt,
+                generation.response,
+                generation.provider,
+                generation.model,
+                generation.duration_ms as i64,
+                generation.status,
+                generation.error,
+                now_millis() as i64,
+                generation.full_prompt,
+                generation.seed,
+                generation.temperature,
+            ],
+        )
+        .map_err(|e| format!("Failed to record generation: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<GenerationRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, prompt, response, provider, model, duration_ms, status, error, created_at, seed, temperature
+             FROM generations WHERE id = ?1",
+            params![id],
+            row_to_record,
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read generation: {}", e))
+    }
+
+    // Everything, oldest first; used to rebuild the search index
+    pub fn all(&self) -> Result<Vec<GenerationRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, prompt, response, provider, model, duration_ms, status, error, created_at, seed, temperature
+                 FROM generations ORDER BY id",
+            )
+            .map_err(|e| format!("Failed to query history: {}", e))?;
+        let records = stmt
+            .query_map([], row_to_record)
+            .map_err(|e| format!("Failed to query history: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read history row: {}", e))?;
+        Ok(records)
+    }
+
+    pub fn replay_input(&self, id: i64) -> Result<Option<ReplayInput>, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT prompt, full_prompt, seed, temperature FROM generations WHERE id = ?1",
+            params![id],
+            |row| {
+                let full_prompt: Option<String> = row.get(1)?;
+                Ok(full_prompt.map(|full_prompt| ReplayInput {
+                    prompt: row.get(0).unwrap_or_default(),
+                    full_prompt,
+                    seed: row.get(2).unwrap_or(None),
+                    temperature: row.get(3).unwrap_or(None),
+                }))
+            },
+        )
+        .optional()
+        .map(Option::flatten)
+        .map_err(|e| format!("Failed to read generation: {}", e))
+    }
+
+    pub fn page(&self, filter: &HistoryFilter, page: u32, page_size: u32) -> Result<HistoryPage, String> {
+        let conn = self.conn.lock().unwrap();
+        let query = filter.query.as_ref().map(|q| format!("%{}%", q));
+        let where_clause = "WHERE (?1 IS NULL OR model = ?1)
+            AND (?2 IS NULL OR status = ?2)
+            AND (?3 IS NULL OR prompt LIKE ?3)";
+
+        let total: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM generations {}", where_clause),
+                params![filter.model, filter.status, query],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to count history: {}", e))?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id, prompt, response, provider, model, duration_ms, status, error, created_at, seed, temperature
+                 FROM generations {} ORDER BY created_at DESC LIMIT ?4 OFFSET ?5",
+                where_clause
+            ))
+            .map_err(|e| format!("Failed to query history: {}", e))?;
+
+        let items = stmt
+            .query_map(
+                params![filter.model, filter.status, query, page_size as i64, page as i64 * page_size as i64],
+                row_to_record,
+            )
+            .map_err(|e| format!("Failed to query history: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read history row: {}", e))?;
+
+        Ok(HistoryPage {
+            items,
+            total: total as u64,
+            page,
+            page_size,
+        })
+    }
+}
+
+fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<GenerationRecord> {
+    Ok(GenerationRecord {
+        id: row.get(0)?,
+        prompt: row.get(1)?,
+        response: row.get(2)?,
+        provider: row.get(3)?,
+        model: row.get(4)?,
+        duration_ms: row.get::<_, i64>(5)? as u64,
+        status: row.get(6)?,
+        error: row.get(7)?,
+        created_at: row.get::<_, i64>(8)? as u64,
+        seed: row.get(9)?,
+        temperature: row.get(10)?,
+    })
+}
+
+fn add_column(conn: &Connection, column: &str, definition: &str) -> Result<(), String> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('generations') WHERE name = ?1")
+        .and_then(|mut stmt| stmt.exists(params![column]))
+        .map_err(|e| format!("Failed to inspect history schema: {}", e))?;
+    if !exists {
+        conn.execute(&format!("ALTER TABLE generations ADD COLUMN {} {}", column, definition), [])
+            .map_err(|e| format!("Failed to migrate history schema: {}", e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_history(
+    page: Option<u32>,
+    page_size: Option<u32>,
+    filter: Option<HistoryFilter>,
+    state: State<'_, AppState>,
+) -> Result<HistoryPage, String> {
+    state.history.page(
+        &filter.unwrap_or_default(),
+        page.unwrap_or(0),
+        page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+    )
+}
+
+#[tauri::command]
+pub async fn get_generation(id: i64, state: State<'_, AppState>) -> Result<GenerationRecord, String> {
+    state.history.get(id)?.ok_or_else(|| format!("Generation {} not found", id))
+}
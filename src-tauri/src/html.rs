@@ -0,0 +1,52 @@
+// Small string-level helpers for the single-file HTML the model produces.
+// These deliberately avoid a full parser so they still work on sloppy output.
+
+fn find_ci(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(&needle.to_ascii_lowercase())
+}
+
+// Text and attribute values we write into a page ourselves
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Insert a snippet right before </head>, creating a head if the model forgot one
+pub fn inject_into_head(html: &str, snippet: &str) -> String {
+    if let Some(pos) = find_ci(html, "</head>") {
+        return format!("{}{}\n{}", &html[..pos], snippet, &html[pos..]);
+    }
+    if let Some(pos) = find_ci(html, "<body") {
+        return format!("{}<head>\n{}\n</head>\n{}", &html[..pos], snippet, &html[pos..]);
+    }
+    format!("<head>\n{}\n</head>\n{}", snippet, html)
+}
+
+// Insert a snippet as the first thing in <head>, for tags that must precede everything else
+pub fn prepend_to_head(html: &str, snippet: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let open_tag = lower
+        .match_indices("<head")
+        .find(|(pos, _)| matches!(lower.as_bytes().get(pos + 5), Some(b'>') | Some(b' ') | Some(b'\t') | Some(b'\n')));
+    if let Some(end) = open_tag.and_then(|(pos, _)| lower[pos..].find('>').map(|end| pos + end + 1)) {
+        return format!("{}\n{}{}", &html[..end], snippet, &html[end..]);
+    }
+    inject_into_head(html, snippet)
+}
+
+// Insert a snippet right before </body>, or append it
+pub fn inject_into_body(html: &str, snippet: &str) -> String {
+    match find_ci(html, "</body>") {
+        Some(pos) => format!("{}{}\n{}", &html[..pos], snippet, &html[pos..]),
+        None => format!("{}\n{}", html, snippet),
+    }
+}
+
+pub fn extract_title(html: &str) -> Option<String> {
+    let start = find_ci(html, "<title>")? + "<title>".len();
+    let end = start + find_ci(&html[start..], "</title>")?;
+    let title = html[start..end].trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::projects::{self, Project};
+use crate::{healing, html};
+
+// Hand-written pages are bigger than generated ones, but not by this much
+const MAX_IMPORT_BYTES: u64 = 5 * 1024 * 1024;
+// Stands in for the model name so imported projects are easy to tell apart
+const IMPORTED_MODEL: &str = "imported";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedHtml {
+    pub project: Project,
+    // What healing would fix, so the UI can offer it straight away
+    pub problems: Vec<String>,
+}
+
+fn is_document(content: &str) -> bool {
+    let lower = content.to_ascii_lowercase();
+    lower.contains("<!doctype html") || lower.contains("<html")
+}
+
+// Fragments (a <div> and a <script>, say) get the minimal document around them that
+// preview, edit_vibe and healing all expect
+fn as_document(content: &str, title: &str) -> String {
+    if is_document(content) {
+        return content.to_string();
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n    <meta charset=\"UTF-8\">\n    \
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n    <title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        html::escape_html(title),
+        content.trim()
+    )
+}
+
+// Brings an existing .html file into the project store. The original file is left alone;
+// the project gets its own copy, versioned and committed like a generated one.
+#[tauri::command]
+pub async fn import_html(path: String, name: Option<String>, app_handle: AppHandle) -> Result<ImportedHtml, String> {
+    let file = Path::new(&path);
+    let extension = file.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+    if !matches!(extension.as_str(), "html" | "htm") {
+        return Err(format!("{} is not an HTML file", path));
+    }
+    let size = std::fs::metadata(file).map_err(|e| format!("Failed to open {}: {}", path, e))?.len();
+    if size > MAX_IMPORT_BYTES {
+        return Err(format!("{} is too large to import ({} KB)", path, size / 1024));
+    }
+    let bytes = std::fs::read(file).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    if content.trim().is_empty() {
+        return Err(format!("{} is empty", path));
+    }
+
+    let file_name = file.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let stem = file.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| "Imported page".to_string());
+    let name = name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| html::extract_title(&content))
+        .unwrap_or(stem);
+    let document = as_document(&content, &name);
+
+    // There was no prompt; the file name is the closest thing to one
+    let prompt = format!("Imported from {}", file_name);
+    let project = projects::write_project(&app_handle, None, Some(name), prompt, document, IMPORTED_MODEL.to_string())?;
+    let problems = healing::static_problems(&project.html);
+    tracing::info!(project = %project.meta.id, problems = problems.len(), "Imported HTML file");
+    Ok(ImportedHtml { project, problems })
+}
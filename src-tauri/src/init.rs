@@ -0,0 +1,123 @@
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use tokio::process::Command;
+
+use crate::providers::{self, ProviderConfig};
+use crate::{current_provider, current_settings, installer, models, AppState};
+
+// The onboarding steps initialize_model runs in one go, for UIs that walk through them
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OllamaCheck {
+    pub found: bool,
+    // Where the ollama CLI was found
+    pub path: Option<String>,
+    pub version: Option<String>,
+    // Set when that version lacks APIs the app relies on
+    pub version_warning: Option<String>,
+    pub daemon_running: bool,
+    // Installed models, as `ollama list` names them
+    pub models: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelCheck {
+    pub name: String,
+    pub installed: bool,
+    // ensure_model pulled it just now
+    pub pulled: bool,
+}
+
+// `ollama list` shows "gemma3:4b" and "nomic-embed-text:latest"; settings may leave the tag off
+pub fn has_model(installed: &[String], name: &str) -> bool {
+    installed.iter().any(|model| model == name || model.strip_suffix(":latest") == Some(name))
+}
+
+// The Ollama model settings point at, even while another provider is active
+pub fn configured_model(state: &AppState) -> String {
+    match &current_settings(state).provider {
+        ProviderConfig::Ollama { .. } => current_provider(state).model().to_string(),
+        _ => providers::DEFAULT_OLLAMA_MODEL.to_string(),
+    }
+}
+
+// Finds the CLI and asks it for the model list, which also needs a running daemon
+pub async fn ollama_status(app_handle: &AppHandle, state: &AppState) -> OllamaCheck {
+    let settings = current_settings(state);
+    let ollama = installer::find_ollama(app_handle, settings.ollama_path.as_deref());
+    let mut check = OllamaCheck {
+        found: false,
+        path: ollama.as_ref().map(|path| path.display().to_string()),
+        version: None,
+        version_warning: None,
+        daemon_running: false,
+        models: Vec::new(),
+    };
+    let Some(path) = &ollama else { return check };
+
+    let mut cmd = Command::new(path);
+    cmd.args(["list"]);
+    let init_timeout = Duration::from_secs(settings.init_timeout_secs);
+    let list_result = match tokio::time::timeout(init_timeout, cmd.output()).await {
+        Ok(result) => result,
+        Err(_) => Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "ollama list timed out")),
+    };
+
+    if let Ok(output) = &list_result {
+        check.found = true;
+        check.daemon_running = output.status.success();
+        if check.daemon_running {
+            // A NAME/ID/SIZE/MODIFIED header, then one model per line
+            check.models = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+                .collect();
+        }
+    }
+    if check.found {
+        check.version = installer::ollama_version(ollama.as_deref()).await;
+        check.version_warning = check.version.as_deref().and_then(installer::version_warning);
+        if let Some(warning) = &check.version_warning {
+            tracing::warn!("{}", warning);
+        }
+    }
+    check
+}
+
+async fn model_status(name: &str) -> Result<ModelCheck, String> {
+    let installed: Vec<String> = providers::installed_models().await?.into_iter().map(|model| model.name).collect();
+    Ok(ModelCheck { name: name.to_string(), installed: has_model(&installed, name), pulled: false })
+}
+
+#[tauri::command]
+pub async fn check_ollama(app_handle: AppHandle, state: State<'_, AppState>) -> Result<OllamaCheck, String> {
+    Ok(ollama_status(&app_handle, &state).await)
+}
+
+// None checks the configured model
+#[tauri::command]
+pub async fn check_model(name: Option<String>, state: State<'_, AppState>) -> Result<ModelCheck, String> {
+    let name = name.unwrap_or_else(|| configured_model(&state));
+    model_status(&name).await
+}
+
+// check_model, then pull the model when it's missing and auto_pull allows it
+// (progress arrives as model-pull-progress events)
+#[tauri::command]
+pub async fn ensure_model(
+    name: Option<String>,
+    auto_pull: bool,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ModelCheck, String> {
+    let name = name.unwrap_or_else(|| configured_model(&state));
+    let check = model_status(&name).await?;
+    if check.installed || !auto_pull {
+        return Ok(check);
+    }
+    models::pull_with_progress(&app_handle, &name, "model-pull-progress").await?;
+    tracing::info!(model = %name, "Pulled missing model");
+    Ok(ModelCheck { installed: true, pulled: true, ..check })
+}
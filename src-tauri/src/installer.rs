@@ -0,0 +1,312 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use futures_util::StreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::providers::OLLAMA_URL;
+
+const RELEASES_URL: &str = "https://github.com/ollama/ollama/releases/latest/download";
+// The Windows installer is interactive, so give the user time to click through it
+const DAEMON_WAIT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallProgress {
+    // downloading, verifying, installing, waiting, done
+    pub stage: String,
+    pub message: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+fn emit_progress(app_handle: &AppHandle, stage: &str, message: impl Into<String>, downloaded: u64, total: Option<u64>) {
+    let payload = InstallProgress {
+        stage: stage.to_string(),
+        message: message.into(),
+        downloaded,
+        total,
+    };
+    if let Err(e) = app_handle.emit("ollama-install-progress", payload) {
+        tracing::warn!("Failed to emit install progress: {}", e);
+    }
+}
+
+// Release asset for this OS, as named in the release's sha256sum.txt
+fn release_asset() -> Result<&'static str, String> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", _) => Ok("OllamaSetup.exe"),
+        ("macos", _) => Ok("Ollama-darwin.zip"),
+        ("linux", "x86_64") => Ok("ollama-linux-amd64.tgz"),
+        ("linux", "aarch64") => Ok("ollama-linux-arm64.tgz"),
+        (os, arch) => Err(format!("No Ollama build for {} ({})", os, arch)),
+    }
+}
+
+// Where the Linux build is unpacked; the system-wide install script needs root
+pub fn install_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?
+        .join("ollama"))
+}
+
+fn binary_name() -> &'static str {
+    if cfg!(windows) { "ollama.exe" } else { "ollama" }
+}
+
+// Where the official installers, Homebrew and our own Linux unpack put the CLI
+fn standard_locations(app_handle: &AppHandle) -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    let mut locations = Vec::new();
+    match std::env::consts::OS {
+        "macos" => {
+            locations.push(PathBuf::from("/Applications/Ollama.app/Contents/Resources/ollama"));
+            locations.extend(home.as_ref().map(|home| home.join("Applications/Ollama.app/Contents/Resources/ollama")));
+            locations.push(PathBuf::from("/opt/homebrew/bin/ollama"));
+            locations.push(PathBuf::from("/usr/local/bin/ollama"));
+        }
+        "windows" => {
+            locations.extend(dirs::data_local_dir().map(|dir| dir.join("Programs").join("Ollama").join("ollama.exe")));
+            locations.extend(std::env::var_os("ProgramFiles").map(|dir| PathBuf::from(dir).join("Ollama").join("ollama.exe")));
+        }
+        _ => {
+            locations.push(PathBuf::from("/usr/local/bin/ollama"));
+            locations.push(PathBuf::from("/usr/bin/ollama"));
+            locations.extend(home.as_ref().map(|home| home.join(".local/bin/ollama")));
+        }
+    }
+    locations.extend(install_dir(app_handle).ok().map(|dir| dir.join("bin").join(binary_name())));
+    locations
+}
+
+// The ollama CLI to run: the configured path, then PATH, then the usual install locations.
+// Apps launched from Finder or the Start menu often don't get the shell's PATH.
+pub fn find_ollama(app_handle: &AppHandle, configured: Option<&str>) -> Option<PathBuf> {
+    if let Some(path) = configured.map(str::trim).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            return Some(path);
+        }
+        tracing::warn!("Configured Ollama path {} does not exist, searching instead", path.display());
+    }
+    let on_path = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).map(|dir| dir.join(binary_name())).collect::<Vec<_>>())
+        .unwrap_or_default();
+    on_path.into_iter().chain(standard_locations(app_handle)).find(|path| path.is_file())
+}
+
+async fn daemon_reachable(client: &reqwest::Client) -> bool {
+    client.get(format!("{}/api/version", OLLAMA_URL))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+        .map(|response| response.status().is_success())
+        .unwrap_or(false)
+}
+
+// Oldest Ollama releases with each API the app relies on
+const VERSION_REQUIREMENTS: &[((u32, u32, u32), &str)] = &[
+    ((0, 1, 14), "the streaming chat API"),
+    ((0, 1, 23), "keep_alive"),
+    ((0, 1, 38), "listing loaded models (/api/ps)"),
+];
+
+// "0.5.7", "0.6.0-rc1" or the CLI's "ollama version is 0.5.7"
+fn parse_version(text: &str) -> Option<(u32, u32, u32)> {
+    let version = text.split_whitespace().last()?.trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+    Some((parts.next()??, parts.next().flatten().unwrap_or(0), parts.next().flatten().unwrap_or(0)))
+}
+
+// The daemon's version, or the CLI's when the daemon isn't up
+pub async fn ollama_version(binary: Option<&Path>) -> Option<String> {
+    let from_daemon = async {
+        let body: serde_json::Value = reqwest::Client::new()
+            .get(format!("{}/api/version", OLLAMA_URL))
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()?;
+        body["version"].as_str().map(str::to_string)
+    };
+    if let Some(version) = from_daemon.await {
+        return Some(version);
+    }
+    let output = tokio::process::Command::new(binary?).arg("--version").output().await.ok()?;
+    // Without a daemon the CLI also prints a warning; the version line is the one we want
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("version"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(str::to_string)
+}
+
+// What an outdated Ollama is missing, with how to fix it
+pub fn version_warning(version: &str) -> Option<String> {
+    let parsed = parse_version(version)?;
+    let missing: Vec<&str> = VERSION_REQUIREMENTS
+        .iter()
+        .filter(|(minimum, _)| parsed < *minimum)
+        .map(|(_, feature)| *feature)
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    let (major, minor, patch) = VERSION_REQUIREMENTS.iter().map(|(minimum, _)| *minimum).max()?;
+    Some(format!(
+        "Ollama {} is missing {}. Update to {}.{}.{} or newer from https://ollama.com/download (or use Install Ollama in settings).",
+        version,
+        missing.join(", "),
+        major,
+        minor,
+        patch
+    ))
+}
+
+async fn expected_checksum(client: &reqwest::Client, asset: &str) -> Result<String, String> {
+    let sums = client.get(format!("{}/sha256sum.txt", RELEASES_URL))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch checksums: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read checksums: {}", e))?;
+
+    // Lines look like "<hex>  ./OllamaSetup.exe"
+    sums.lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, name)| name.trim().trim_start_matches("./") == asset)
+        .map(|(hash, _)| hash.to_lowercase())
+        .ok_or_else(|| format!("No published checksum for {}", asset))
+}
+
+async fn download(client: &reqwest::Client, app_handle: &AppHandle, asset: &str, dest: &Path) -> Result<String, String> {
+    let response = client.get(format!("{}/{}", RELEASES_URL, asset))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", asset, e))?;
+
+    let total = response.content_length();
+    let mut file = std::fs::File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0u64;
+    let mut last_report = Instant::now();
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download interrupted: {}", e))?;
+        hasher.update(&chunk);
+        file.write_all(&chunk).map_err(|e| format!("Failed to write installer: {}", e))?;
+        downloaded += chunk.len() as u64;
+
+        if last_report.elapsed() > Duration::from_millis(250) {
+            emit_progress(app_handle, "downloading", format!("Downloading {}", asset), downloaded, total);
+            last_report = Instant::now();
+        }
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+async fn run_command(cmd: &mut tokio::process::Command, what: &str) -> Result<(), String> {
+    let output = cmd.output().await.map_err(|e| format!("Failed to {}: {}", what, e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to {}: {}", what, String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(())
+}
+
+// Hand the verified download to the platform; returns once the installer is launched
+async fn launch(app_handle: &AppHandle, installer: &Path) -> Result<(), String> {
+    match std::env::consts::OS {
+        "windows" => {
+            std::process::Command::new(installer)
+                .spawn()
+                .map_err(|e| format!("Failed to launch installer: {}", e))?;
+        }
+        "macos" => {
+            let apps = dirs::home_dir().ok_or("Failed to resolve home dir")?.join("Applications");
+            std::fs::create_dir_all(&apps).map_err(|e| format!("Failed to create {}: {}", apps.display(), e))?;
+            // ditto keeps the bundle's symlinks and code signature intact
+            run_command(
+                tokio::process::Command::new("ditto").arg("-x").arg("-k").arg(installer).arg(&apps),
+                "unpack Ollama.app",
+            )
+            .await?;
+            run_command(
+                tokio::process::Command::new("open").arg(apps.join("Ollama.app")),
+                "open Ollama.app",
+            )
+            .await?;
+        }
+        _ => {
+            let dir = install_dir(app_handle)?;
+            std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            run_command(
+                tokio::process::Command::new("tar").arg("-xzf").arg(installer).arg("-C").arg(&dir),
+                "unpack Ollama",
+            )
+            .await?;
+            std::process::Command::new(dir.join("bin").join("ollama"))
+                .arg("serve")
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .map_err(|e| format!("Failed to start ollama serve: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn install_ollama(app_handle: AppHandle) -> Result<String, String> {
+    if crate::test_mode() {
+        emit_progress(&app_handle, "done", "Test mode: skipping install", 0, None);
+        return Ok("Test mode: Ollama install skipped".to_string());
+    }
+
+    let client = reqwest::Client::new();
+    if daemon_reachable(&client).await {
+        return Ok("Ollama is already running".to_string());
+    }
+
+    let asset = release_asset()?;
+    let download_dir = app_handle.path().app_cache_dir()
+        .map_err(|e| format!("Failed to resolve cache dir: {}", e))?;
+    std::fs::create_dir_all(&download_dir).map_err(|e| format!("Failed to create cache dir: {}", e))?;
+    let installer = download_dir.join(asset);
+
+    let expected = expected_checksum(&client, asset).await?;
+    emit_progress(&app_handle, "downloading", format!("Downloading {}", asset), 0, None);
+    let actual = download(&client, &app_handle, asset, &installer).await?;
+
+    emit_progress(&app_handle, "verifying", "Verifying checksum", 0, None);
+    if actual != expected {
+        let _ = std::fs::remove_file(&installer);
+        return Err(format!("Checksum mismatch for {}: expected {}, got {}", asset, expected, actual));
+    }
+
+    emit_progress(&app_handle, "installing", "Launching installer", 0, None);
+    launch(&app_handle, &installer).await?;
+
+    emit_progress(&app_handle, "waiting", "Waiting for the Ollama daemon", 0, None);
+    let started = Instant::now();
+    while started.elapsed() < DAEMON_WAIT {
+        if daemon_reachable(&client).await {
+            let _ = std::fs::remove_file(&installer);
+            emit_progress(&app_handle, "done", "Ollama is running", 0, None);
+            return Ok("Ollama installed and running! 🍒".to_string());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    Err("Ollama was installed but the daemon didn't start. Try launching Ollama manually.".to_string())
+}
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+
+use crate::{now_millis, AppState};
+
+// Returned by cancelled generations, which must not fall back to mock output
+pub const CANCELLED: &str = "Generation cancelled";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: u64,
+    // generation, variant, ...
+    pub kind: String,
+    pub label: String,
+    pub started_at: u64,
+    // Waiting for a free slot rather than running
+    pub queued: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct QueueEvent {
+    job_id: u64,
+    // queued or started
+    state: &'static str,
+    // 1-based place in line while queued
+    position: Option<usize>,
+    label: String,
+    waited_ms: u64,
+}
+
+struct Job {
+    info: JobInfo,
+    cancel: Arc<Notify>,
+}
+
+#[derive(Default)]
+struct Slots {
+    running: usize,
+    // Job ids in arrival order; only the front one may take a free slot
+    waiting: VecDeque<u64>,
+}
+
+// Every in-flight generation registers here so it can be counted, queued and cancelled
+pub struct JobRegistry {
+    app_handle: AppHandle,
+    next_id: AtomicU64,
+    active: Mutex<HashMap<u64, Job>>,
+    slots: Mutex<Slots>,
+    slot_freed: Notify,
+}
+
+// Unregisters the job (and frees its slot) when the work finishes, however it finishes
+pub struct JobGuard {
+    registry: Arc<JobRegistry>,
+    id: u64,
+    cancel: Arc<Notify>,
+    has_slot: AtomicBool,
+}
+
+impl JobGuard {
+    pub async fn cancelled(&self) {
+        self.cancel.notified().await
+    }
+
+    // Wait until fewer than `limit` jobs are running and everyone queued earlier has gone
+    pub async fn wait_for_slot(&self, limit: usize) {
+        let limit = limit.max(1);
+        let queued_at = now_millis();
+        let mut announced = false;
+        loop {
+            // Registered before checking so a slot freed in between still wakes us
+            let freed = self.registry.slot_freed.notified();
+            tokio::pin!(freed);
+            freed.as_mut().enable();
+
+            let position = {
+                let mut slots = self.registry.slots.lock().unwrap();
+                if !slots.waiting.contains(&self.id) {
+                    slots.waiting.push_back(self.id);
+                }
+                let position = slots.waiting.iter().position(|id| *id == self.id).unwrap_or(0);
+                if position == 0 && slots.running < limit {
+                    slots.waiting.pop_front();
+                    slots.running += 1;
+                    self.has_slot.store(true, Ordering::Relaxed);
+                    None
+                } else {
+                    Some(position + 1)
+                }
+            };
+
+            let Some(position) = position else {
+                self.registry.set_queued(self.id, false);
+                // Whoever is next in line may fit too when the limit was raised
+                self.registry.slot_freed.notify_waiters();
+                if announced {
+                    self.registry.emit(self.id, "started", None, now_millis().saturating_sub(queued_at));
+                }
+                return;
+            };
+            if !announced {
+                self.registry.set_queued(self.id, true);
+                self.registry.emit(self.id, "queued", Some(position), 0);
+                announced = true;
+            }
+            freed.await;
+        }
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        self.registry.active.lock().unwrap().remove(&self.id);
+        let mut slots = self.registry.slots.lock().unwrap();
+        slots.waiting.retain(|id| *id != self.id);
+        if self.has_slot.load(Ordering::Relaxed) {
+            slots.running = slots.running.saturating_sub(1);
+        }
+        drop(slots);
+        self.registry.slot_freed.notify_waiters();
+    }
+}
+
+impl JobRegistry {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            next_id: AtomicU64::new(0),
+            active: Mutex::new(HashMap::new()),
+            slots: Mutex::new(Slots::default()),
+            slot_freed: Notify::new(),
+        }
+    }
+
+    pub fn start(self: &Arc<Self>, kind: &str, label: &str) -> JobGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancel = Arc::new(Notify::new());
+        let info = JobInfo {
+            id,
+            kind: kind.to_string(),
+            label: label.chars().take(80).collect(),
+            started_at: now_millis(),
+            queued: false,
+        };
+        self.active.lock().unwrap().insert(id, Job { info, cancel: cancel.clone() });
+        JobGuard { registry: self.clone(), id, cancel, has_slot: AtomicBool::new(false) }
+    }
+
+    fn set_queued(&self, id: u64, queued: bool) {
+        if let Some(job) = self.active.lock().unwrap().get_mut(&id) {
+            job.info.queued = queued;
+        }
+    }
+
+    fn emit(&self, id: u64, state: &'static str, position: Option<usize>, waited_ms: u64) {
+        let label = self.active.lock().unwrap().get(&id).map(|job| job.info.label.clone()).unwrap_or_default();
+        let event = QueueEvent { job_id: id, state, position, label, waited_ms };
+        if let Err(e) = self.app_handle.emit("generation-queue", event) {
+            tracing::warn!("Failed to emit queue event: {}", e);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.active.lock().unwrap().len()
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self.active.lock().unwrap().values().map(|job| job.info.clone()).collect();
+        jobs.sort_by_key(|job| job.id);
+        jobs
+    }
+
+    // notify_one keeps a permit, so a job cancelled before it starts waiting still sees it
+    pub fn cancel_all(&self) -> usize {
+        let active = self.active.lock().unwrap();
+        for job in active.values() {
+            job.cancel.notify_one();
+        }
+        active.len()
+    }
+}
+
+#[tauri::command]
+pub async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<JobInfo>, String> {
+    Ok(state.jobs.list())
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+const KEYRING_SERVICE: &str = "com.vibecherry.app";
+
+// Prefixes used by the cloud providers we talk to
+const SECRET_PREFIXES: &[&str] = &["sk-", "gsk_", "ghp_", "github_pat_", "nfp_", "xai-"];
+
+// An API key that never shows up in Debug output or logs
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+fn entry(provider: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, provider)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+pub fn get_api_key(provider: &str) -> Option<String> {
+    entry(provider).ok()?.get_password().ok()
+}
+
+pub fn store_api_key(account: &str, key: &str) -> Result<(), String> {
+    entry(account)?
+        .set_password(key)
+        .map_err(|e| format!("Failed to store API key: {}", e))
+}
+
+// Prefer a key passed explicitly with the provider config, otherwise use the keychain
+pub fn resolve(provider: &str, explicit: &Option<Secret>) -> Option<String> {
+    match explicit {
+        Some(secret) if !secret.expose().is_empty() => Some(secret.expose().to_string()),
+        _ => get_api_key(provider),
+    }
+}
+
+// Mask anything that looks like a provider token before it reaches a log line
+pub fn redact(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            word.push(c);
+        } else {
+            push_redacted(&mut out, &word);
+            word.clear();
+            out.push(c);
+        }
+    }
+    push_redacted(&mut out, &word);
+
+    out
+}
+
+fn push_redacted(out: &mut String, word: &str) {
+    let is_secret = word.len() > 12 && SECRET_PREFIXES.iter().any(|prefix| word.starts_with(prefix));
+    if is_secret {
+        out.push_str(&word[..4]);
+        out.push_str("…[redacted]");
+    } else {
+        out.push_str(word);
+    }
+}
+
+#[tauri::command]
+pub async fn set_api_key(provider: String, key: String) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("API key is empty".to_string());
+    }
+
+    store_api_key(&provider, key)?;
+    Ok(format!("API key for {} saved to the system keychain", provider))
+}
+
+#[tauri::command]
+pub async fn delete_api_key(provider: String) -> Result<String, String> {
+    match entry(&provider)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(format!("API key for {} removed", provider)),
+        Err(e) => Err(format!("Failed to remove API key: {}", e)),
+    }
+}
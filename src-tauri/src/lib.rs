@@ -1,85 +1,707 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter, State};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Manager, State};
+use serde::Serialize;
 use serde_json::Value;
-use tokio::process::Command;
 use anyhow::Result;
 
+mod a11y;
+mod api;
+mod benchmark;
+mod bundle;
+mod cache;
+// Public for the headless vibecherry binary in src/bin
+pub mod cli;
+mod clipboard;
+mod components;
+mod constraints;
+mod context;
+mod context_files;
+mod crash;
+mod deploy;
+mod desktop_export;
+mod edit;
+mod examples;
+mod export;
+mod extract;
+mod formatter;
+mod frameworks;
+mod gallery_export;
+mod git;
+mod guardrails;
+mod healing;
+mod health;
+mod history;
+mod html;
+mod html_import;
+mod init;
+mod installer;
+mod jobs;
+mod keys;
+mod lifecycle;
+mod logging;
+mod mcp;
+mod metrics;
+mod minify;
+mod mock;
+mod modelfile;
+mod models;
+mod preview;
+mod personas;
+mod postprocess;
+mod presets;
+mod progress;
+mod project_files;
+mod projects;
 mod prompts;
+mod providers;
+mod publish;
+mod quality;
+mod quick_vibe;
+mod remix;
+mod sanitize;
+mod search;
+mod sessions;
+mod settings;
+mod shutdown;
+mod snippets;
+mod tailwind;
+mod templates;
+mod titles;
+mod transcribe;
+mod tray;
+mod validate;
+mod variants;
+mod versions;
+mod vision;
 
-use prompts::VIBE_CODING_SYSTEM_PROMPT;
+use prompts::PromptTemplate;
+use providers::{discover_local_server, GenerationOptions, LlmProvider, ProviderConfig};
+use cache::ResponseCache;
+use history::{HistoryStore, NewGeneration};
+use jobs::JobRegistry;
+use metrics::{GenerationMetrics, MetricsStore};
+use search::HistoryIndex;
+use settings::Settings;
+use snippets::SnippetIndex;
 
+#[derive(Clone)]
 pub struct AppState {
     is_initialized: Arc<Mutex<bool>>,
+    provider: Arc<Mutex<Arc<dyn LlmProvider>>>,
+    settings: Arc<Mutex<Settings>>,
+    history: Arc<HistoryStore>,
+    cache: Arc<ResponseCache>,
+    metrics: Arc<MetricsStore>,
+    // Spec documents attached to the next generation
+    context_files: Arc<Mutex<Vec<context_files::ContextFile>>>,
+    snippets: Arc<SnippetIndex>,
+    // Full-text index over history for search_history
+    search: Arc<HistoryIndex>,
+    jobs: Arc<JobRegistry>,
+    // Session that chat messages are autosaved to; None until the first message
+    session: Arc<Mutex<Option<String>>>,
+    // Local HTTP API, when the user has turned it on
+    api_server: Arc<Mutex<Option<api::ApiServer>>>,
+    // How initialize_model got ready when Ollama couldn't serve: local_server, embedded, mock or test
+    fallback_mode: Arc<Mutex<Option<String>>>,
+    last_error: Arc<Mutex<Option<LastError>>>,
 }
 
-async fn call_ollama(prompt: &str, _app_handle: &AppHandle) -> Result<String, String> {
-    let mut cmd = Command::new("ollama");
-    cmd.args(&["run", "gemma3:4b"]);
-    
-    let mut child = cmd
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to start Ollama: {}", e))?;
-    
-    // Send the prompt
-    if let Some(mut stdin) = child.stdin.take() {
-        use tokio::io::AsyncWriteExt;
-        stdin.write_all(prompt.as_bytes()).await
-            .map_err(|e| format!("Failed to write to Ollama: {}", e))?;
+#[derive(Debug, Clone, Serialize)]
+struct LastError {
+    // initialization or generation
+    kind: &'static str,
+    message: String,
+    at: u64,
+}
+
+fn record_error(state: &AppState, kind: &'static str, message: &str) {
+    *state.last_error.lock().unwrap() = Some(LastError { kind, message: keys::redact(message), at: now_millis() });
+}
+
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// --test-mode or VIBE_CHERRY_TEST_MODE=1: deterministic fixtures, no model calls
+pub(crate) fn test_mode() -> bool {
+    static TEST_MODE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *TEST_MODE.get_or_init(|| {
+        std::env::args().any(|arg| arg == "--test-mode")
+            || std::env::var("VIBE_CHERRY_TEST_MODE").is_ok_and(|value| value == "1" || value == "true")
+    })
+}
+
+fn current_provider(state: &AppState) -> Arc<dyn LlmProvider> {
+    state.provider.lock().unwrap().clone()
+}
+
+fn current_settings(state: &AppState) -> Settings {
+    state.settings.lock().unwrap().clone()
+}
+
+// Sent over the Channel each streaming command takes, so tokens arrive in order and
+// only at the request that asked for them
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Token(String),
+    // The previous model failed; tokens from here on come from `to`
+    Fallback { from: String, to: String, reason: String },
+}
+
+// Stream tokens from the active provider as they arrive, bounded by the configured timeout.
+// Falls through settings.fallback_models in order when the model errors or isn't installed.
+async fn stream_from_provider(
+    state: &AppState,
+    app_handle: &AppHandle,
+    on_token: &Channel<StreamEvent>,
+    user_prompt: &str,
+    full_prompt: &str,
+    attempt: u32,
+) -> Result<String, String> {
+    let settings = current_settings(state);
+    let options = settings.generation_options();
+    let provider = current_provider(state);
+    let primary = provider.model().to_string();
+    let template = provider.template().await;
+
+    let mut error = match stream_generation(state, app_handle, on_token, provider, user_prompt, full_prompt, &options, attempt, None).await {
+        Ok(response) => return Ok(response),
+        Err(e) => e,
+    };
+    let mut failed = primary.clone();
+    for model in settings.fallback_models.iter().filter(|m| !m.trim().is_empty() && **m != primary) {
+        if error == jobs::CANCELLED || test_mode() {
+            break;
+        }
+        tracing::warn!("{} failed, falling back to {}: {}", failed, model, keys::redact(&error));
+        let event = StreamEvent::Fallback { from: failed.clone(), to: model.clone(), reason: keys::redact(&error) };
+        if let Err(e) = on_token.send(event) {
+            tracing::warn!("Failed to send fallback event: {}", e);
+        }
+
+        let mut fallback_settings = settings.clone();
+        fallback_settings.model = Some(model.clone());
+        let provider = fallback_settings.build_provider();
+        // The prompt was laid out for the primary model's chat template
+        let prompt = template.convert(full_prompt, provider.template().await);
+        match stream_generation(state, app_handle, on_token, provider, user_prompt, &prompt, &options, attempt, Some(&primary)).await {
+            Ok(response) => return Ok(response),
+            Err(e) => error = e,
+        }
+        failed = model.clone();
     }
-    
-    // Wait for completion
-    let output = child.wait_with_output().await
-        .map_err(|e| format!("Ollama process failed: {}", e))?;
-    
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Ollama error: {}", stderr));
+    Err(error)
+}
+
+// stream_from_provider against an explicit provider and options (e.g. a vision model)
+#[allow(clippy::too_many_arguments)]
+async fn stream_generation(
+    state: &AppState,
+    app_handle: &AppHandle,
+    on_token: &Channel<StreamEvent>,
+    provider: Arc<dyn LlmProvider>,
+    user_prompt: &str,
+    full_prompt: &str,
+    options: &GenerationOptions,
+    attempt: u32,
+    fallback_from: Option<&str>,
+) -> Result<String, String> {
+    let model = provider.model().to_string();
+    let events = lifecycle::GenerationEvents::start(app_handle, provider.as_ref(), attempt, fallback_from);
+    let tokens = AtomicU64::new(0);
+    let generation = run_generation_with(
+        state,
+        provider,
+        user_prompt,
+        full_prompt,
+        options,
+        &|token| {
+            tokens.fetch_add(1, Ordering::Relaxed);
+            send_token(on_token, token)
+        },
+    );
+    // Long generations would otherwise look frozen, especially before the first token
+    let result = tokio::select! {
+        result = generation => result,
+        never = progress::heartbeat(app_handle, state, &model, &tokens) => match never {},
+    };
+    let mut generated = result.inspect_err(|e| events.failed(e))?;
+    generated.response = guardrails::enforce(app_handle, state, generated.response).inspect_err(|e| events.failed(e))?;
+    events.completed(&generated);
+
+    match (generated.cache_hit, generated.generation_id) {
+        (Some(hit), _) => {
+            if let Err(e) = app_handle.emit("vibe-cached", hit) {
+                tracing::warn!("Failed to emit cache hit: {}", e);
+            }
+        }
+        (None, Some(id)) => snippets::index_generation(state.clone(), id, user_prompt, &generated.response),
+        (None, None) => {}
     }
-    
-    let response = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(response)
+    quality::emit_for_response(app_handle, state, generated.generation_id, &generated.response);
+    let fallback_from = fallback_from.map(str::to_string);
+    metrics::emit(app_handle, &GenerationMetrics { attempt, fallback_from, ..generated.metrics });
+    Ok(generated.response)
 }
 
-#[tauri::command]
-async fn initialize_model(state: State<'_, AppState>) -> Result<String, String> {
-    // Check if Ollama is installed and Qwen2.5-Coder model is available
-    let mut cmd = Command::new("ollama");
-    cmd.args(&["list"]);
-    
-    match cmd.output().await {
-        Ok(output) => {
-            if output.status.success() {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                if output_str.contains("gemma3:4b") {
-                    let mut initialized = state.is_initialized.lock().unwrap();
-                    *initialized = true;
-                    Ok("Gemma 3 4B model ready! 🍒".to_string())
-                } else {
-                    let mut initialized = state.is_initialized.lock().unwrap();
-                    *initialized = true;
-                    Ok("Ollama found, but Gemma 3 4B model not installed. Using mock mode. Run 'ollama pull gemma3:4b' to install the model. 🍒".to_string())
+pub(crate) struct Generated {
+    pub response: String,
+    pub generation_id: Option<i64>,
+    pub seed: Option<u32>,
+    pub cache_hit: Option<cache::CacheHit>,
+    pub metrics: GenerationMetrics,
+}
+
+// Cache lookup, timeout and history bookkeeping shared by every generation path
+pub(crate) async fn run_generation(
+    state: &AppState,
+    user_prompt: &str,
+    full_prompt: &str,
+    options: &GenerationOptions,
+    on_token: &(dyn Fn(String) + Send + Sync),
+) -> Result<Generated, String> {
+    run_generation_with(state, current_provider(state), user_prompt, full_prompt, options, on_token).await
+}
+
+pub(crate) async fn run_generation_with(
+    state: &AppState,
+    provider: Arc<dyn LlmProvider>,
+    user_prompt: &str,
+    full_prompt: &str,
+    options: &GenerationOptions,
+    on_token: &(dyn Fn(String) + Send + Sync),
+) -> Result<Generated, String> {
+    let settings = current_settings(state);
+
+    // Keyed before a seed is picked, so "any seed" requests still hit the cache
+    let cache_key = ResponseCache::key(provider.name(), provider.model(), options, full_prompt);
+    let use_cache = settings.response_cache && !test_mode();
+    if use_cache {
+        if let Some(hit) = state.cache.get(&cache_key) {
+            return Ok(replay_cached(state, user_prompt, full_prompt, options, hit, on_token));
+        }
+    }
+
+    // Always sample with a known seed so regenerate() can reproduce this exact output
+    let mut options = options.clone();
+    let seed = *options.seed.get_or_insert_with(random_seed);
+
+    let first_token = Mutex::new(None);
+    let token_count = AtomicU64::new(0);
+    let counting = |token: String| {
+        first_token.lock().unwrap().get_or_insert_with(Instant::now);
+        token_count.fetch_add(1, Ordering::Relaxed);
+        on_token(token);
+    };
+    let job = state.jobs.start("generation", user_prompt);
+    // Queued time doesn't count against the generation timeout
+    tokio::select! {
+        _ = job.wait_for_slot(settings.max_concurrent_generations) => {}
+        _ = job.cancelled() => return Err(jobs::CANCELLED.to_string()),
+    }
+    // Durations and tokens/sec measure the generation itself, not the wait for a slot
+    let started = Instant::now();
+    let generation = async {
+        tokio::select! {
+            result = provider.stream(full_prompt, &options, &counting) => Some(result),
+            _ = job.cancelled() => None,
+        }
+    };
+
+    let timeout = Duration::from_secs(settings.generation_timeout_secs);
+    let (result, status) = match tokio::time::timeout(timeout, generation).await {
+        Ok(Some(Ok(response))) => (Ok(response), "success"),
+        Ok(Some(Err(e))) => (Err(format!("{} failed: {}", provider.name(), e)), "error"),
+        Ok(None) => (Err(jobs::CANCELLED.to_string()), "cancelled"),
+        Err(_) => (
+            Err(format!("{} timed out after {}s", provider.name(), settings.generation_timeout_secs)),
+            "timeout",
+        ),
+    };
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let error = result.as_ref().err().map(|e| keys::redact(e));
+    match &error {
+        None => tracing::info!(provider = provider.name(), model = provider.model(), duration_ms, "Generation finished"),
+        Some(e) => tracing::error!(provider = provider.name(), model = provider.model(), duration_ms, status, "Generation failed: {}", e),
+    }
+    let id = record_generation(state, NewGeneration {
+        prompt: user_prompt,
+        response: result.as_deref().unwrap_or(""),
+        provider: provider.name(),
+        model: provider.model(),
+        duration_ms,
+        status,
+        error: error.as_deref(),
+        full_prompt: Some(full_prompt),
+        seed: Some(seed),
+        temperature: options.temperature,
+    });
+
+    if let (Some(e), false) = (&error, status == "cancelled") {
+        state.metrics.record_failure(provider.model());
+        record_error(state, "generation", e);
+    }
+    let response = result?;
+    if use_cache {
+        state.cache.put(&cache_key, provider.name(), provider.model(), Some(seed), &response);
+    }
+
+    let total_tokens = token_count.load(Ordering::Relaxed);
+    let ttft_ms = first_token.lock().unwrap().map(|at| at.duration_since(started).as_millis() as u64);
+    let metrics = GenerationMetrics {
+        provider: provider.name().to_string(),
+        model: provider.model().to_string(),
+        attempt: 1,
+        ttft_ms,
+        duration_ms,
+        total_tokens,
+        tokens_per_sec: metrics::tokens_per_sec(total_tokens, duration_ms),
+        cached: false,
+        fallback_from: None,
+    };
+    state.metrics.record(&metrics);
+
+    Ok(Generated { response, generation_id: id, seed: Some(seed), cache_hit: None, metrics })
+}
+
+fn random_seed() -> u32 {
+    uuid::Uuid::new_v4().as_u128() as u32
+}
+
+fn replay_cached(
+    state: &AppState,
+    user_prompt: &str,
+    full_prompt: &str,
+    options: &GenerationOptions,
+    hit: cache::CachedResponse,
+    on_token: &(dyn Fn(String) + Send + Sync),
+) -> Generated {
+    let id = record_generation(state, NewGeneration {
+        prompt: user_prompt,
+        response: &hit.response,
+        provider: &hit.provider,
+        model: &hit.model,
+        duration_ms: 0,
+        status: "cached",
+        error: None,
+        full_prompt: Some(full_prompt),
+        seed: hit.seed,
+        temperature: options.temperature,
+    });
+
+    on_token(hit.response.clone());
+    let metrics = GenerationMetrics {
+        provider: hit.provider.clone(),
+        model: hit.model.clone(),
+        attempt: 1,
+        ttft_ms: Some(0),
+        duration_ms: 0,
+        total_tokens: 0,
+        tokens_per_sec: 0.0,
+        cached: true,
+        fallback_from: None,
+    };
+    state.metrics.record(&metrics);
+
+    Generated {
+        generation_id: id,
+        seed: hit.seed,
+        metrics,
+        response: hit.response,
+        cache_hit: Some(cache::CacheHit {
+            cached: true,
+            provider: hit.provider,
+            model: hit.model,
+            created_at: hit.created_at,
+        }),
+    }
+}
+
+// System prompt plus the user turn, in whichever template the active model expects
+async fn build_full_prompt(state: &AppState, app_handle: &AppHandle, user_turn: &str) -> String {
+    build_full_prompt_for(state, app_handle, current_provider(state).as_ref(), user_turn).await
+}
+
+// Same prompt, laid out in the template of a provider other than the configured one
+async fn build_full_prompt_for(
+    state: &AppState,
+    app_handle: &AppHandle,
+    provider: &dyn LlmProvider,
+    user_turn: &str,
+) -> String {
+    let settings = current_settings(state);
+    let template = provider.template().await;
+
+    let mut system_prompt = settings.system_prompt_for(template);
+    if let Some(persona) = settings.persona.as_deref().and_then(|id| personas::find(app_handle, id)) {
+        system_prompt = persona.apply(&system_prompt);
+    }
+    if let Some(examples) = examples::few_shot_section(app_handle, settings.example_token_budget) {
+        system_prompt = format!("{}\n\n{}", system_prompt, examples);
+    }
+    if let Some(patterns) = snippets::retrieve(state, user_turn).await {
+        system_prompt = format!("{}\n\n{}", system_prompt, patterns);
+    }
+
+    let user_turn = context_files::attach_pending(state, user_turn);
+    template.assemble(&system_prompt, &user_turn)
+}
+
+fn record_generation(state: &AppState, generation: NewGeneration) -> Option<i64> {
+    match state.history.record(generation) {
+        Ok(id) => {
+            // History works without search, so an indexing failure is only logged
+            match state.history.get(id) {
+                Ok(Some(record)) => {
+                    if let Err(e) = state.search.add(&record) {
+                        tracing::warn!("{}", e);
+                    }
                 }
-            } else {
-                let mut initialized = state.is_initialized.lock().unwrap();
-                *initialized = true;
-                Ok("Ollama not responding properly. Using mock mode. 🍒".to_string())
+                Ok(None) => {}
+                Err(e) => tracing::warn!("{}", e),
             }
+            Some(id)
         }
-        Err(_) => {
-            let mut initialized = state.is_initialized.lock().unwrap();
-            *initialized = true;
-            Ok("Ollama not found. Using mock mode. Install Ollama and run 'ollama pull gemma3:4b' for real AI generation. 🍒".to_string())
+        Err(e) => {
+            tracing::error!("{}", e);
+            None
         }
     }
 }
 
+fn record_mock(state: &AppState, prompt: &str, response: &str, reason: &str) {
+    record_generation(state, NewGeneration {
+        prompt,
+        response,
+        provider: "mock",
+        model: "mock",
+        duration_ms: 0,
+        status: "mock",
+        error: Some(reason),
+        full_prompt: None,
+        seed: None,
+        temperature: None,
+    });
+}
+
+fn send_token(on_token: &Channel<StreamEvent>, token: String) {
+    if let Err(e) = on_token.send(StreamEvent::Token(token)) {
+        tracing::warn!("Failed to send token: {}", e);
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct InitStatus {
+    ollama_found: bool,
+    // Where the ollama CLI was found
+    ollama_path: Option<String>,
+    ollama_version: Option<String>,
+    // Set when that version lacks APIs the app relies on
+    version_warning: Option<String>,
+    daemon_running: bool,
+    model_installed: bool,
+    model_name: String,
+    // local_server, embedded, mock or test when Ollama can't serve the model
+    fallback_mode: Option<String>,
+    ready: bool,
+    message: String,
+}
+
+struct Fallback {
+    mode: &'static str,
+    message: String,
+}
+
+// Fall back to a local OpenAI-compatible server (LM Studio etc.), then an
+// embedded GGUF, before resorting to mock mode
+async fn try_local_backends(state: &AppState, app_handle: &AppHandle) -> Option<Fallback> {
+    if let Some(message) = try_local_server(state).await {
+        return Some(Fallback { mode: "local_server", message });
+    }
+    try_embedded_model(state, app_handle).map(|message| Fallback { mode: "embedded", message })
+}
+
+#[cfg(feature = "embedded")]
+fn try_embedded_model(state: &AppState, app_handle: &AppHandle) -> Option<String> {
+    let models_dir = app_handle.path().app_data_dir().ok()?.join("models");
+    let model_path = providers::find_gguf(&models_dir)?;
+    let config = ProviderConfig::Embedded {
+        model_path: model_path.to_string_lossy().to_string(),
+    };
+
+    *state.provider.lock().unwrap() = config.build();
+    *state.is_initialized.lock().unwrap() = true;
+    Some(format!("Embedded model {} ready! 🍒", model_path.display()))
+}
+
+#[cfg(not(feature = "embedded"))]
+fn try_embedded_model(_state: &AppState, _app_handle: &AppHandle) -> Option<String> {
+    None
+}
+
+async fn try_local_server(state: &AppState) -> Option<String> {
+    let server = discover_local_server().await?;
+    let model = server.models[0].clone();
+    let config = ProviderConfig::LocalServer {
+        base_url: server.base_url.clone(),
+        model: model.clone(),
+    };
+
+    *state.provider.lock().unwrap() = config.build();
+    *state.is_initialized.lock().unwrap() = true;
+    Some(format!("Local server at {} ready with {}! 🍒", server.base_url, model))
+}
+
+// All of init's checks plus the local-server, embedded and mock fallbacks
+#[tauri::command]
+async fn initialize_model(app_handle: AppHandle, state: State<'_, AppState>) -> Result<InitStatus, String> {
+    let status = initialize(&app_handle, &state).await?;
+    *state.fallback_mode.lock().unwrap() = status.fallback_mode.clone();
+    if !status.ready {
+        record_error(&state, "initialization", &status.message);
+    }
+    Ok(status)
+}
+
+async fn initialize(app_handle: &AppHandle, state: &AppState) -> Result<InitStatus, String> {
+    if test_mode() {
+        *state.is_initialized.lock().unwrap() = true;
+        return Ok(InitStatus {
+            ollama_found: false,
+            ollama_path: None,
+            ollama_version: None,
+            version_warning: None,
+            daemon_running: false,
+            model_installed: false,
+            model_name: current_provider(state).model().to_string(),
+            fallback_mode: Some("test".to_string()),
+            ready: true,
+            message: "Test mode: serving bundled fixtures".to_string(),
+        });
+    }
+
+    let settings = current_settings(state);
+    let model_name = init::configured_model(state);
+    let ollama = init::ollama_status(app_handle, state).await;
+    let mut status = InitStatus {
+        ollama_found: ollama.found,
+        model_installed: init::has_model(&ollama.models, &model_name),
+        ollama_path: ollama.path,
+        ollama_version: ollama.version,
+        version_warning: ollama.version_warning,
+        daemon_running: ollama.daemon_running,
+        model_name: model_name.clone(),
+        fallback_mode: None,
+        ready: false,
+        message: String::new(),
+    };
+
+    if status.model_installed {
+        *state.is_initialized.lock().unwrap() = true;
+        spawn_warm_up(state, app_handle);
+        status.ready = true;
+        status.message = match &status.version_warning {
+            Some(warning) => format!("{} ready, but {}", model_name, warning),
+            None => format!("{} ready! 🍒", model_name),
+        };
+        return Ok(status);
+    }
+
+    if let Some(fallback) = try_local_backends(state, app_handle).await {
+        status.fallback_mode = Some(fallback.mode.to_string());
+        status.ready = true;
+        status.message = fallback.message;
+        return Ok(status);
+    }
+
+    status.message = if !status.ollama_found {
+        format!("Ollama not found. Install Ollama (or set its location in settings) and run 'ollama pull {}' for real AI generation.", model_name)
+    } else if !status.daemon_running {
+        "Ollama not responding properly. Start it with 'ollama serve'.".to_string()
+    } else {
+        format!("Ollama found, but {} is not installed. Run 'ollama pull {}' to install the model.", model_name, model_name)
+    };
+
+    // Without Ollama at all there's nothing real to talk to, so only fake it when asked to
+    if status.ollama_found || settings.mock_mode {
+        *state.is_initialized.lock().unwrap() = true;
+        status.fallback_mode = Some("mock".to_string());
+        status.ready = true;
+        status.message = format!("{} Using mock mode. 🍒", status.message);
+    }
+    Ok(status)
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ModelWarmed {
+    provider: String,
+    model: String,
+    duration_ms: u64,
+    error: Option<String>,
+}
+
+async fn warm_up_provider(state: &AppState) -> ModelWarmed {
+    let provider = current_provider(state);
+    let started = Instant::now();
+    let result = provider.warm_up(&current_settings(state).generation_options()).await;
+    ModelWarmed {
+        provider: provider.name().to_string(),
+        model: provider.model().to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: result.err().map(|e| keys::redact(&e)),
+    }
+}
+
+// Loading a model takes ~20s; do it now rather than on the first prompt
+fn spawn_warm_up(state: &AppState, app_handle: &AppHandle) {
+    let state = state.clone();
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let warmed = warm_up_provider(&state).await;
+        if let Err(e) = app_handle.emit("model-warmed", warmed) {
+            tracing::warn!("Failed to emit warm-up result: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+async fn warm_model(state: State<'_, AppState>) -> Result<String, String> {
+    let warmed = warm_up_provider(&state).await;
+    match warmed.error {
+        Some(e) => Err(format!("Failed to warm up {}: {}", warmed.model, e)),
+        None => Ok(format!("{} loaded in {}ms", warmed.model, warmed.duration_ms)),
+    }
+}
+
+// Frees RAM/VRAM between sessions; the next generation loads the model again
+#[tauri::command]
+async fn unload_model(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let provider = current_provider(&state);
+    let unloaded = provider.unload().await.map_err(|e| keys::redact(&e))?;
+    tracing::info!(provider = provider.name(), "Unloaded {} model(s)", unloaded.len());
+    Ok(unloaded)
+}
+
 #[tauri::command]
 async fn generate_vibe_stream(
     prompt: String,
-    _history: Vec<Value>,
+    history: Option<Vec<Value>>,
+    use_components: Option<Vec<String>>,
+    session_id: Option<String>,
+    // Name of a generation preset overriding the sampler settings for this request
+    preset: Option<String>,
+    // Hard requirements such as "no external libraries", checked against the response where possible
+    constraints: Option<Vec<String>>,
+    on_token: Channel<StreamEvent>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
@@ -90,285 +712,47 @@ async fn generate_vibe_stream(
         }
     } // Drop the mutex guard here
     
-    // Build proper ChatML prompt (following Qwen2.5-Coder format)
-    let full_prompt = format!("{VIBE_CODING_SYSTEM_PROMPT}<|im_start|>user\n{prompt}\n<|im_end|>\n<|im_start|>assistant\n");
+    // A session keeps its own conversation; otherwise the frontend sends it along
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+    let state = presets::scoped(&state, preset.as_deref())?;
+    let history = match &session_id {
+        Some(id) => sessions::history(&app_handle, id)?,
+        None => history.unwrap_or_default(),
+    };
+
+    let components = components::find(&app_handle, &use_components.unwrap_or_default())?;
+    let constraints = constraints::normalize(constraints);
+    let request = components::with_components(&prompt, &components);
+    let request = constraints::with_constraints(&request, &constraints);
+
+    // Earlier turns first, then the template the active provider expects
+    let user_turn = context::with_history(&state, &app_handle, &history, &request).await;
+    let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
     
-    // Try to use Ollama with Qwen2.5-Coder
-    match call_ollama(&full_prompt, &app_handle).await {
+    match stream_from_provider(&state, &app_handle, &on_token, &prompt, &full_prompt, 1).await {
         Ok(response) => {
-            // Stream the response
-            let response_clone = response.clone();
-            tokio::spawn(async move {
-                if let Err(e) = app_handle.emit("vibe-token", response_clone) {
-                    eprintln!("Failed to emit token: {}", e);
-                }
-            });
+            if !components.is_empty() {
+                components::emit_check(&app_handle, &components::verify(&response, &components));
+            }
+            let html = extract::extract(&response).html.unwrap_or_else(|| response.clone());
+            if !constraints.is_empty() {
+                constraints::emit_check(&app_handle, &constraints::verify(&html, &constraints));
+            }
+            titles::emit_for_response(&app_handle, &prompt, &html);
+            sessions::record_exchange(&app_handle, &state, &prompt, &response);
             Ok(response)
         }
+        Err(e) if e == jobs::CANCELLED => Err(e),
         Err(e) => {
-            eprintln!("Ollama failed: {}, falling back to mock", e);
+            tracing::warn!("{}, falling back to mock", keys::redact(&e));
             // Fallback to mock response
-            let mock_response = if prompt.to_lowercase().contains("todo") {
-                r#"Here's a beautiful Todo List App:
-
-```html
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Todo List</title>
-    <script src="https://cdn.tailwindcss.com"></script>
-</head>
-<body class="min-h-screen bg-gradient-to-br from-purple-900 via-black to-pink-900 p-4">
-    <div class="max-w-md mx-auto mt-8">
-        <h1 class="text-3xl font-bold text-white text-center mb-8">📝 Todo List</h1>
-        
-        <div class="bg-white/10 backdrop-blur-lg rounded-2xl p-6 shadow-2xl">
-            <div class="flex gap-2 mb-4">
-                <input type="text" id="todoInput" placeholder="Add a new todo..." 
-                       class="flex-1 bg-white/20 text-white placeholder-white/60 px-4 py-2 rounded-lg border border-white/20 focus:outline-none focus:border-pink-500">
-                <button onclick="addTodo()" class="bg-gradient-to-r from-pink-500 to-purple-500 px-4 py-2 rounded-lg text-white font-semibold hover:scale-105 transition-transform">
-                    Add
-                </button>
-            </div>
-            
-            <ul id="todoList" class="space-y-2">
-                <!-- Todos will be added here -->
-            </ul>
-            
-            <div class="mt-4 text-center text-white/60 text-sm">
-                <span id="todoCount">0 todos</span>
-            </div>
-        </div>
-    </div>
-
-    <script>
-        let todos = [];
-        
-        function addTodo() {
-            const input = document.getElementById('todoInput');
-            const text = input.value.trim();
-            
-            if (text) {
-                todos.push({ id: Date.now(), text: text, completed: false });
-                input.value = '';
-                renderTodos();
-            }
-        }
-        
-        function toggleTodo(id) {
-            const todo = todos.find(t => t.id === id);
-            if (todo) {
-                todo.completed = !todo.completed;
-                renderTodos();
-            }
-        }
-        
-        function deleteTodo(id) {
-            todos = todos.filter(t => t.id !== id);
-            renderTodos();
-        }
-        
-        function renderTodos() {
-            const list = document.getElementById('todoList');
-            const count = document.getElementById('todoCount');
-            
-            list.innerHTML = todos.map(todo => `
-                <li class="flex items-center gap-3 p-3 bg-white/5 rounded-lg">
-                    <input type="checkbox" ${todo.completed ? 'checked' : ''} 
-                           onchange="toggleTodo(${todo.id})" class="w-4 h-4">
-                    <span class="flex-1 ${todo.completed ? 'line-through text-white/60' : 'text-white'}">${todo.text}</span>
-                    <button onclick="deleteTodo(${todo.id})" class="text-red-400 hover:text-red-300">✕</button>
-                </li>
-            `).join('');
-            
-            count.textContent = `${todos.length} todo${todos.length !== 1 ? 's' : ''}`;
-        }
-        
-        // Allow Enter key to add todo
-        document.getElementById('todoInput').addEventListener('keypress', function(e) {
-            if (e.key === 'Enter') {
-                addTodo();
-            }
-        });
-    </script>
-</body>
-</html>
-```
-
-This todo app features:
-- Add new todos with Enter key or button
-- Mark todos as complete with checkboxes
-- Delete todos with the ✕ button
-- Beautiful glassmorphism design
-- Real-time todo counter
-- Smooth animations and hover effects"#.to_string()
-            } else if prompt.to_lowercase().contains("calculator") {
-                r#"Here's a beautiful Calculator:
-
-```html
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Calculator</title>
-    <script src="https://cdn.tailwindcss.com"></script>
-</head>
-<body class="min-h-screen bg-gradient-to-br from-purple-900 via-black to-pink-900 flex items-center justify-center p-4">
-    <div class="bg-white/10 backdrop-blur-lg rounded-3xl p-6 shadow-2xl max-w-sm w-full">
-        <div class="text-center mb-6">
-            <h1 class="text-2xl font-bold text-white mb-2">🧮 Calculator</h1>
-        </div>
-        
-        <div class="bg-black/30 rounded-2xl p-4 mb-4">
-            <input type="text" id="display" value="0" readonly 
-                   class="w-full text-right text-3xl font-bold text-white bg-transparent border-none outline-none">
-        </div>
-        
-        <div class="grid grid-cols-4 gap-3">
-            <button onclick="clearAll()" class="col-span-2 bg-red-500 hover:bg-red-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">Clear</button>
-            <button onclick="deleteLast()" class="bg-orange-500 hover:bg-orange-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">⌫</button>
-            <button onclick="appendToDisplay('/')" class="bg-blue-500 hover:bg-blue-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">÷</button>
-            
-            <button onclick="appendToDisplay('7')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">7</button>
-            <button onclick="appendToDisplay('8')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">8</button>
-            <button onclick="appendToDisplay('9')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">9</button>
-            <button onclick="appendToDisplay('*')" class="bg-blue-500 hover:bg-blue-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">×</button>
-            
-            <button onclick="appendToDisplay('4')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">4</button>
-            <button onclick="appendToDisplay('5')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">5</button>
-            <button onclick="appendToDisplay('6')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">6</button>
-            <button onclick="appendToDisplay('-')" class="bg-blue-500 hover:bg-blue-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">-</button>
-            
-            <button onclick="appendToDisplay('1')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">1</button>
-            <button onclick="appendToDisplay('2')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">2</button>
-            <button onclick="appendToDisplay('3')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">3</button>
-            <button onclick="appendToDisplay('+')" class="bg-blue-500 hover:bg-blue-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">+</button>
-            
-            <button onclick="appendToDisplay('0')" class="col-span-2 bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">0</button>
-            <button onclick="appendToDisplay('.')" class="bg-gray-600 hover:bg-gray-700 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">.</button>
-            <button onclick="calculate()" class="bg-green-500 hover:bg-green-600 text-white p-4 rounded-xl font-semibold transition-all hover:scale-105">=</button>
-        </div>
-    </div>
-
-    <script>
-        let currentInput = '0';
-        let operator = null;
-        let previousInput = null;
-        
-        function updateDisplay() {
-            document.getElementById('display').value = currentInput;
-        }
-        
-        function appendToDisplay(value) {
-            if (currentInput === '0' && value !== '.') {
-                currentInput = value;
-            } else {
-                currentInput += value;
-            }
-            updateDisplay();
-        }
-        
-        function clearAll() {
-            currentInput = '0';
-            operator = null;
-            previousInput = null;
-            updateDisplay();
-        }
-        
-        function deleteLast() {
-            if (currentInput.length > 1) {
-                currentInput = currentInput.slice(0, -1);
-            } else {
-                currentInput = '0';
-            }
-            updateDisplay();
-        }
-        
-        function calculate() {
-            if (operator && previousInput !== null) {
-                const prev = parseFloat(previousInput);
-                const current = parseFloat(currentInput);
-                let result;
-                
-                switch (operator) {
-                    case '+': result = prev + current; break;
-                    case '-': result = prev - current; break;
-                    case '*': result = prev * current; break;
-                    case '/': result = prev / current; break;
-                    default: return;
-                }
-                
-                currentInput = result.toString();
-                operator = null;
-                previousInput = null;
-                updateDisplay();
-            }
-        }
-        
-        // Handle operator clicks
-        document.querySelectorAll('button').forEach(button => {
-            button.addEventListener('click', function() {
-                const value = this.textContent;
-                if (['+', '-', '*', '/'].includes(value)) {
-                    if (operator && previousInput !== null) {
-                        calculate();
-                    }
-                    operator = value;
-                    previousInput = currentInput;
-                    currentInput = '0';
-                }
-            });
-        });
-    </script>
-</body>
-</html>
-```
-
-This calculator features:
-- Full arithmetic operations (+, -, ×, ÷)
-- Clear and backspace functions
-- Beautiful glassmorphism design
-- Smooth button animations
-- Keyboard-friendly interface"#.to_string()
-            } else {
-                format!(r#"Here's a beautiful Custom App:
-
-```html
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Custom App</title>
-    <script src="https://cdn.tailwindcss.com"></script>
-</head>
-<body class="min-h-screen bg-gradient-to-br from-purple-900 via-black to-pink-900 flex items-center justify-center">
-    <div class="text-center text-white">
-        <h1 class="text-4xl font-bold mb-4">Custom App</h1>
-        <p class="text-xl">Created based on your request: "{}"</p>
-        <div class="mt-6">
-            <button class="bg-gradient-to-r from-pink-500 to-purple-500 px-6 py-3 rounded-lg hover:scale-105 transition-transform">
-                Click me!
-            </button>
-        </div>
-    </div>
-</body>
-</html>
-```
-
-This app features beautiful gradients and interactive elements!"#, prompt)
-            };
+            let mock_response = mock::response(&app_handle, &prompt, false);
+
+            record_mock(&state, &prompt, &mock_response, &keys::redact(&e));
+            sessions::record_exchange(&app_handle, &state, &prompt, &mock_response);
 
             // Stream the mock response
-            let response_clone = mock_response.clone();
-            tokio::spawn(async move {
-                if let Err(e) = app_handle.emit("vibe-token", response_clone) {
-                    eprintln!("Failed to emit token: {}", e);
-                }
-            });
+            send_token(&on_token, mock_response.clone());
             
             Ok(mock_response)
         }
@@ -380,6 +764,12 @@ async fn generate_vibe_with_healing(
     prompt: String,
     is_fix_attempt: bool,
     attempt_number: u32,
+    previous_code: Option<String>,
+    // Console errors and exceptions the preview caught in previous_code
+    errors: Option<Vec<healing::RuntimeError>>,
+    session_id: Option<String>,
+    preset: Option<String>,
+    on_token: Channel<StreamEvent>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
@@ -389,218 +779,362 @@ async fn generate_vibe_with_healing(
             return Err("Model not initialized".to_string());
         }
     }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+    let state = presets::scoped(&state, preset.as_deref())?;
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
 
-    let full_prompt = if is_fix_attempt {
-        format!("{VIBE_CODING_SYSTEM_PROMPT}<|im_start|>user\nFIX ATTEMPT #{}\nBe extra careful with syntax and completeness.\n\n{}\n<|im_end|>\n<|im_start|>assistant\n",
+    let user_turn = if is_fix_attempt {
+        format!("FIX ATTEMPT #{}\n{}\n\n{}",
             attempt_number,
+            healing::fix_guidance(previous_code.as_deref(), &errors.unwrap_or_default()),
             prompt
         )
     } else {
-        format!("{VIBE_CODING_SYSTEM_PROMPT}<|im_start|>user\n{prompt}\n<|im_end|>\n<|im_start|>assistant\n")
+        prompt.clone()
     };
+    let user_turn = context::with_history(&state, &app_handle, &history, &user_turn).await;
+    let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
     
-    // Try to use Ollama with Qwen2.5-Coder
-    match call_ollama(&full_prompt, &app_handle).await {
+    let fallback_reason = match stream_from_provider(&state, &app_handle, &on_token, &prompt, &full_prompt, attempt_number).await {
         Ok(response) => {
-            // Stream the response
-            let response_clone = response.clone();
-            tokio::spawn(async move {
-                if let Err(e) = app_handle.emit("vibe-token", response_clone) {
-                    eprintln!("Failed to emit token: {}", e);
-                }
-            });
+            sessions::record_in(&app_handle, &state, session_id.as_deref(), &prompt, &response);
             return Ok(response);
         }
+        Err(e) if e == jobs::CANCELLED => return Err(e),
         Err(e) => {
-            eprintln!("Ollama failed: {}, falling back to mock", e);
+            tracing::warn!("{}, falling back to mock", keys::redact(&e));
+            keys::redact(&e)
         }
-    }
+    };
     
     // Fallback to mock response if Ollama fails
-    let mock_response = if is_fix_attempt {
-        r#"Here's a fixed version:
-
-```html
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Fixed App</title>
-    <script src="https://cdn.tailwindcss.com"></script>
-</head>
-<body class="bg-gradient-to-br from-blue-500 to-purple-600 min-h-screen flex items-center justify-center">
-    <div class="bg-white/10 backdrop-blur-lg rounded-2xl p-8 shadow-2xl max-w-md w-full mx-4">
-        <h1 class="text-3xl font-bold text-white text-center mb-6">✨ Fixed!</h1>
-        <p class="text-white/80 text-center">This version should work perfectly!</p>
-    </div>
-</body>
-</html>
-```
-
-Fixed issues:
-- Added proper DOCTYPE and HTML structure
-- Simplified the code to avoid errors
-- Used reliable patterns
-- Added error handling"#.to_string()
-    } else {
-        // Use the same improved responses as generate_vibe_stream
-        if prompt.to_lowercase().contains("todo") {
-            r#"Here's a beautiful Todo List App:
-
-```html
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Todo List</title>
-    <script src="https://cdn.tailwindcss.com"></script>
-</head>
-<body class="min-h-screen bg-gradient-to-br from-purple-900 via-black to-pink-900 p-4">
-    <div class="max-w-md mx-auto mt-8">
-        <h1 class="text-3xl font-bold text-white text-center mb-8">📝 Todo List</h1>
-        
-        <div class="bg-white/10 backdrop-blur-lg rounded-2xl p-6 shadow-2xl">
-            <div class="flex gap-2 mb-4">
-                <input type="text" id="todoInput" placeholder="Add a new todo..." 
-                       class="flex-1 bg-white/20 text-white placeholder-white/60 px-4 py-2 rounded-lg border border-white/20 focus:outline-none focus:border-pink-500">
-                <button onclick="addTodo()" class="bg-gradient-to-r from-pink-500 to-purple-500 px-4 py-2 rounded-lg text-white font-semibold hover:scale-105 transition-transform">
-                    Add
-                </button>
-            </div>
-            
-            <ul id="todoList" class="space-y-2">
-                <!-- Todos will be added here -->
-            </ul>
-            
-            <div class="mt-4 text-center text-white/60 text-sm">
-                <span id="todoCount">0 todos</span>
-            </div>
-        </div>
-    </div>
-
-    <script>
-        let todos = [];
-        
-        function addTodo() {
-            const input = document.getElementById('todoInput');
-            const text = input.value.trim();
-            
-            if (text) {
-                todos.push({ id: Date.now(), text: text, completed: false });
-                input.value = '';
-                renderTodos();
-            }
-        }
-        
-        function toggleTodo(id) {
-            const todo = todos.find(t => t.id === id);
-            if (todo) {
-                todo.completed = !todo.completed;
-                renderTodos();
-            }
-        }
-        
-        function deleteTodo(id) {
-            todos = todos.filter(t => t.id !== id);
-            renderTodos();
-        }
-        
-        function renderTodos() {
-            const list = document.getElementById('todoList');
-            const count = document.getElementById('todoCount');
-            
-            list.innerHTML = todos.map(todo => `
-                <li class="flex items-center gap-3 p-3 bg-white/5 rounded-lg">
-                    <input type="checkbox" ${todo.completed ? 'checked' : ''} 
-                           onchange="toggleTodo(${todo.id})" class="w-4 h-4">
-                    <span class="flex-1 ${todo.completed ? 'line-through text-white/60' : 'text-white'}">${todo.text}</span>
-                    <button onclick="deleteTodo(${todo.id})" class="text-red-400 hover:text-red-300">✕</button>
-                </li>
-            `).join('');
-            
-            count.textContent = `${todos.length} todo${todos.length !== 1 ? 's' : ''}`;
-        }
-        
-        // Allow Enter key to add todo
-        document.getElementById('todoInput').addEventListener('keypress', function(e) {
-            if (e.key === 'Enter') {
-                addTodo();
-            }
-        });
-    </script>
-</body>
-</html>
-```
-
-This todo app features:
-- Add new todos with Enter key or button
-- Mark todos as complete with checkboxes
-- Delete todos with the ✕ button
-- Beautiful glassmorphism design
-- Real-time todo counter
-- Smooth animations and hover effects"#.to_string()
-        } else {
-            format!(r#"Here's a beautiful Custom App:
-
-```html
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Custom App</title>
-    <script src="https://cdn.tailwindcss.com"></script>
-</head>
-<body class="min-h-screen bg-gradient-to-br from-purple-900 via-black to-pink-900 flex items-center justify-center">
-    <div class="text-center text-white">
-        <h1 class="text-4xl font-bold mb-4">Custom App</h1>
-        <p class="text-xl">Created based on your request: "{}"</p>
-        <div class="mt-6">
-            <button class="bg-gradient-to-r from-pink-500 to-purple-500 px-6 py-3 rounded-lg hover:scale-105 transition-transform">
-                Click me!
-            </button>
-        </div>
-    </div>
-</body>
-</html>
-```
-
-This app features beautiful gradients and interactive elements!"#, prompt)
-        }
-    };
+    let mock_response = mock::response(&app_handle, &prompt, is_fix_attempt);
     
+    record_mock(&state, &prompt, &mock_response, &fallback_reason);
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &prompt, &mock_response);
+
     // Stream the mock response
-    let response_clone = mock_response.clone();
-    tokio::spawn(async move {
-        if let Err(e) = app_handle.emit("vibe-token", response_clone) {
-            eprintln!("Failed to emit token: {}", e);
-        }
-    });
+    send_token(&on_token, mock_response.clone());
     
     Ok(mock_response)
 }
 
+#[derive(serde::Serialize)]
+struct Regenerated {
+    generation_id: Option<i64>,
+    response: String,
+    seed: Option<u32>,
+    temperature: Option<f32>,
+}
+
+// Replays the exact prompt and seed of an earlier generation; pass temperature or
+// seed to change one thing at a time and compare
 #[tauri::command]
-async fn stop_generation() -> Result<String, String> {
-    // TODO: Implement cancellation logic
-    Ok("Generation stopped".to_string())
+async fn regenerate(
+    generation_id: i64,
+    temperature: Option<f32>,
+    seed: Option<u32>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Regenerated, String> {
+    let input = state.history.replay_input(generation_id)?
+        .ok_or_else(|| format!("Generation {} can't be reproduced (no stored prompt)", generation_id))?;
+
+    let mut options = current_settings(&state).generation_options();
+    options.temperature = temperature.or(input.temperature).or(options.temperature);
+    options.seed = seed.or(input.seed);
+
+    let generated = run_generation(
+        &state,
+        &input.prompt,
+        &input.full_prompt,
+        &options,
+        &|token| send_token(&on_token, token),
+    )
+    .await
+    .map_err(|e| keys::redact(&e))?;
+    metrics::emit(&app_handle, &generated.metrics);
+    let response = guardrails::enforce(&app_handle, &state, generated.response)?;
+
+    Ok(Regenerated {
+        generation_id: generated.generation_id,
+        response,
+        seed: generated.seed,
+        temperature: options.temperature,
+    })
+}
+
+#[tauri::command]
+async fn set_provider(
+    config: ProviderConfig,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let config = config.store_inline_key()?;
+
+    // Remember the choice for the next launch
+    settings::modify(&app_handle, &state, |settings| {
+        settings.provider = config;
+        settings.model = None;
+    })?;
+
+    let provider = current_provider(&state);
+    let message = format!("Switched to {} ({})", provider.name(), provider.model());
+    Ok(message)
+}
+
+#[tauri::command]
+async fn list_models(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    current_provider(&state).list_models().await
+}
+
+#[tauri::command]
+async fn get_prompt_template(state: State<'_, AppState>) -> Result<PromptTemplate, String> {
+    Ok(current_provider(&state).template().await)
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AppStateSnapshot {
+    initialized: bool,
+    provider: String,
+    model: String,
+    fallback_mode: Option<String>,
+    // Generations are served from canned responses (fallback or the mock_mode setting)
+    mock_mode: bool,
+    active_jobs: Vec<jobs::JobInfo>,
+    last_error: Option<LastError>,
+    backend_health: Option<health::BackendHealth>,
+    session_id: Option<String>,
+    api_server: Option<api::ApiServerInfo>,
+}
+
+// Everything the UI would otherwise piece together from messages and events
+#[tauri::command]
+async fn get_app_state(state: State<'_, AppState>) -> Result<AppStateSnapshot, String> {
+    let provider = current_provider(&state);
+    let fallback_mode = state.fallback_mode.lock().unwrap().clone();
+    Ok(AppStateSnapshot {
+        initialized: *state.is_initialized.lock().unwrap(),
+        provider: provider.name().to_string(),
+        model: provider.model().to_string(),
+        mock_mode: fallback_mode.as_deref() == Some("mock") || current_settings(&state).mock_mode,
+        fallback_mode,
+        active_jobs: state.jobs.list(),
+        last_error: state.last_error.lock().unwrap().clone(),
+        backend_health: health::latest(),
+        session_id: state.session.lock().unwrap().clone(),
+        api_server: state.api_server.lock().unwrap().as_ref().map(|server| server.info()),
+    })
+}
+
+#[tauri::command]
+async fn stop_generation(state: State<'_, AppState>) -> Result<String, String> {
+    let stopped = state.jobs.cancel_all();
+    Ok(format!("Stopped {} generation(s)", stopped))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let handler = tauri::generate_handler![
+        initialize_model,
+        get_app_state,
+        init::check_ollama,
+        init::check_model,
+        init::ensure_model,
+        warm_model,
+        unload_model,
+        installer::install_ollama,
+        generate_vibe_stream,
+        quick_vibe::submit_quick_vibe,
+        generate_vibe_with_healing,
+        regenerate,
+        healing::generate_and_heal,
+        edit::edit_vibe,
+        variants::generate_variants,
+        vision::generate_from_image,
+        transcribe::transcribe_audio,
+        set_provider,
+        list_models,
+        modelfile::create_vibe_model,
+        presets::list_presets,
+        presets::save_preset,
+        presets::delete_preset,
+        health::get_backend_health,
+        models::check_model_updates,
+        models::upgrade_model,
+        models::get_model_disk_usage,
+        models::remove_model,
+        benchmark::benchmark_models,
+        api::start_api_server,
+        api::stop_api_server,
+        api::get_api_server,
+        get_prompt_template,
+        keys::set_api_key,
+        keys::delete_api_key,
+        settings::get_settings,
+        settings::update_settings,
+        settings::get_system_prompt,
+        settings::set_system_prompt,
+        settings::reset_system_prompt,
+        settings::set_vibe_theme,
+        settings::set_output_language,
+        settings::set_ollama_path,
+        personas::list_personas,
+        templates::list_templates,
+        personas::set_persona,
+        examples::manage_examples,
+        components::manage_components,
+        projects::save_project,
+        projects::load_project,
+        projects::list_projects,
+        projects::tag_project,
+        projects::set_favorite,
+        projects::delete_project,
+        projects::get_gallery,
+        remix::remix_project,
+        remix::merge_projects,
+        versions::list_versions,
+        versions::restore_version,
+        versions::diff_versions,
+        git::get_project_log,
+        project_files::generate_project_files,
+        project_files::list_project_files,
+        project_files::read_project_file,
+        project_files::write_project_file,
+        frameworks::generate_framework_app,
+        frameworks::stop_framework_app,
+        preview::open_preview_window,
+        preview::capture_preview,
+        preview::smoke_test,
+        export::export_html,
+        export::export_pwa,
+        minify::export_minified,
+        desktop_export::export_tauri,
+        gallery_export::export_gallery,
+        bundle::export_vibe,
+        bundle::import_vibe,
+        html_import::import_html,
+        publish::publish_gist,
+        deploy::deploy_project,
+        clipboard::copy_to_clipboard,
+        extract::extract_code,
+        validate::validate_html,
+        validate::validate_js,
+        quality::score_output,
+        formatter::format_code,
+        a11y::audit_a11y,
+        sanitize::sanitize_html,
+        postprocess::list_post_processors,
+        history::get_history,
+        history::get_generation,
+        search::search_history,
+        metrics::get_metrics,
+        logging::get_recent_logs,
+        crash::get_last_crash_report,
+        cache::clear_response_cache,
+        context_files::load_context_file,
+        context_files::list_context_files,
+        context_files::clear_context_files,
+        snippets::save_snippet,
+        snippets::list_snippets,
+        snippets::delete_snippet,
+        snippets::embed_text,
+        sessions::list_sessions,
+        sessions::load_session,
+        sessions::new_session,
+        sessions::create_session,
+        sessions::update_session,
+        sessions::delete_session,
+        sessions::export_transcript,
+        jobs::list_jobs,
+        stop_generation
+    ];
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(AppState {
-            is_initialized: Arc::new(Mutex::new(false)),
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .register_uri_scheme_protocol(preview::PREVIEW_SCHEME, preview::handle_protocol)
+        .setup(|app| {
+            if let Ok(dir) = app.path().app_data_dir() {
+                logging::init(&dir.join("logs"));
+            }
+            if let Ok(dir) = crash::crash_dir(app.handle()) {
+                crash::install(dir, app.package_info().version.to_string());
+            }
+            let settings = settings::load(app.handle());
+            // Test runs start from a clean slate every launch
+            let history = match app.path().app_data_dir() {
+                _ if test_mode() => HistoryStore::in_memory(),
+                Ok(dir) => HistoryStore::open(&dir.join("history.db")).unwrap_or_else(|e| {
+                    tracing::warn!("{}, keeping history in memory", e);
+                    HistoryStore::in_memory()
+                }),
+                Err(_) => HistoryStore::in_memory(),
+            };
+            let cache = match app.path().app_cache_dir() {
+                _ if test_mode() => ResponseCache::in_memory(),
+                Ok(dir) => ResponseCache::open(&dir.join("responses")),
+                Err(_) => ResponseCache::in_memory(),
+            };
+            let snippets = match app.path().app_data_dir() {
+                _ if test_mode() => SnippetIndex::in_memory(),
+                Ok(dir) => SnippetIndex::open(&dir.join("snippets")),
+                Err(_) => SnippetIndex::in_memory(),
+            };
+            let search = match app.path().app_data_dir() {
+                _ if test_mode() => HistoryIndex::in_memory(),
+                Ok(dir) => HistoryIndex::open(&dir.join("search-index")).unwrap_or_else(|e| {
+                    tracing::warn!("{}, keeping the search index in memory", e);
+                    HistoryIndex::in_memory()
+                }),
+                Err(_) => HistoryIndex::in_memory(),
+            };
+            // First launch with search, or the index was deleted: rebuild it from history
+            if search.is_empty() {
+                if let Err(e) = history.all().and_then(|records| search.add_all(&records)) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            app.manage(AppState {
+                is_initialized: Arc::new(Mutex::new(false)),
+                provider: Arc::new(Mutex::new(settings.build_provider())),
+                settings: Arc::new(Mutex::new(settings)),
+                history: Arc::new(history),
+                cache: Arc::new(cache),
+                metrics: Arc::new(MetricsStore::default()),
+                context_files: Arc::new(Mutex::new(Vec::new())),
+                snippets: Arc::new(snippets),
+                search: Arc::new(search),
+                jobs: Arc::new(JobRegistry::new(app.handle().clone())),
+                session: Arc::new(Mutex::new(None)),
+                api_server: Arc::new(Mutex::new(None)),
+                fallback_mode: Arc::new(Mutex::new(None)),
+                last_error: Arc::new(Mutex::new(None)),
+            });
+            // A shortcut another app already owns shouldn't stop startup
+            let shortcut = current_settings(&app.state::<AppState>()).quick_vibe_shortcut;
+            if let Err(e) = quick_vibe::register_shortcut(app.handle(), shortcut.as_deref()) {
+                tracing::warn!("{}", e);
+            }
+            if let Err(e) = tray::install(app.handle()) {
+                tracing::warn!("{}", e);
+            }
+            health::spawn_polling(app.handle().clone());
+            models::spawn_update_checks(app.handle().clone());
+            Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            initialize_model,
-            generate_vibe_stream,
-            generate_vibe_with_healing,
-            stop_generation
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(move |invoke| {
+            crash::note_command(invoke.message.command());
+            handler(invoke)
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::ExitRequested { api, .. } = &event {
+                shutdown::on_exit_requested(app_handle, api);
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,118 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::providers::LlmProvider;
+use crate::{jobs, keys, now_millis, Generated};
+
+// generation-started, -completed, -failed and -cancelled, all keyed by one generation_id
+// so listeners can tell concurrent generations (variants, healing) apart. Tokens go only to
+// the caller's channel; the throttled vibe-progress heartbeat (progress.rs) covers everyone else.
+
+#[derive(Debug, Clone, Serialize)]
+struct GenerationStarted<'a> {
+    generation_id: &'a str,
+    provider: &'a str,
+    model: &'a str,
+    attempt: u32,
+    // Set when the primary model failed and this is one of settings.fallback_models
+    fallback_from: Option<&'a str>,
+    started_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GenerationCompleted<'a> {
+    generation_id: &'a str,
+    provider: &'a str,
+    model: &'a str,
+    // The history row, when it could be recorded
+    history_id: Option<i64>,
+    duration_ms: u64,
+    total_tokens: u64,
+    cached: bool,
+    seed: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GenerationFailed<'a> {
+    generation_id: &'a str,
+    provider: &'a str,
+    model: &'a str,
+    error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GenerationCancelled<'a> {
+    generation_id: &'a str,
+    provider: &'a str,
+    model: &'a str,
+}
+
+pub struct GenerationEvents<'a> {
+    app_handle: &'a AppHandle,
+    id: String,
+    provider: String,
+    model: String,
+}
+
+impl<'a> GenerationEvents<'a> {
+    // Emits generation-started
+    pub fn start(
+        app_handle: &'a AppHandle,
+        provider: &dyn LlmProvider,
+        attempt: u32,
+        fallback_from: Option<&str>,
+    ) -> Self {
+        let events = GenerationEvents {
+            app_handle,
+            id: uuid::Uuid::new_v4().to_string(),
+            provider: provider.name().to_string(),
+            model: provider.model().to_string(),
+        };
+        events.emit("generation-started", GenerationStarted {
+            generation_id: &events.id,
+            provider: &events.provider,
+            model: &events.model,
+            attempt,
+            fallback_from,
+            started_at: now_millis(),
+        });
+        events
+    }
+
+    pub fn completed(&self, generated: &Generated) {
+        self.emit("generation-completed", GenerationCompleted {
+            generation_id: &self.id,
+            provider: &generated.metrics.provider,
+            model: &generated.metrics.model,
+            history_id: generated.generation_id,
+            duration_ms: generated.metrics.duration_ms,
+            total_tokens: generated.metrics.total_tokens,
+            cached: generated.cache_hit.is_some(),
+            seed: generated.seed,
+        });
+    }
+
+    // Cancellation comes back as an error too, but gets its own event
+    pub fn failed(&self, error: &str) {
+        if error == jobs::CANCELLED {
+            self.emit("generation-cancelled", GenerationCancelled {
+                generation_id: &self.id,
+                provider: &self.provider,
+                model: &self.model,
+            });
+        } else {
+            self.emit("generation-failed", GenerationFailed {
+                generation_id: &self.id,
+                provider: &self.provider,
+                model: &self.model,
+                error: keys::redact(error),
+            });
+        }
+    }
+
+    fn emit(&self, event: &str, payload: impl Serialize + Clone) {
+        if let Err(e) = self.app_handle.emit(event, payload) {
+            tracing::warn!("Failed to emit {}: {}", event, e);
+        }
+    }
+}
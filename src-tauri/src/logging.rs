@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+const LOG_PREFIX: &str = "vibecherry";
+const MAX_LOG_FILES: usize = 7;
+const DEFAULT_LIMIT: usize = 200;
+
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+// Dropping the guard stops the background writer, so it lives for the whole process
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+// The shape tracing-subscriber's json formatter writes, one object per line
+#[derive(Deserialize)]
+struct JsonLine {
+    timestamp: String,
+    level: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+// Daily-rotated JSON logs in <app data>/logs plus human-readable stderr.
+// VIBE_CHERRY_LOG takes the usual filter syntax, e.g. "debug" or "vibe_cherry_lib=trace".
+pub fn init(log_dir: &Path) {
+    let filter = || EnvFilter::try_from_env("VIBE_CHERRY_LOG").unwrap_or_else(|_| EnvFilter::new("info"));
+    let stderr_layer = fmt::layer().with_writer(std::io::stderr).with_filter(filter());
+
+    let file_appender = std::fs::create_dir_all(log_dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            Builder::new()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_PREFIX)
+                .filename_suffix("log")
+                .max_log_files(MAX_LOG_FILES)
+                .build(log_dir)
+                .map_err(|e| e.to_string())
+        });
+
+    match file_appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = GUARD.set(guard);
+            let _ = LOG_DIR.set(log_dir.to_path_buf());
+            let file_layer = fmt::layer().json().with_writer(writer).with_filter(filter());
+            let _ = tracing_subscriber::registry().with(stderr_layer).with(file_layer).try_init();
+        }
+        Err(e) => {
+            let _ = tracing_subscriber::registry().with(stderr_layer).try_init();
+            tracing::error!("Failed to open log directory {}: {}, logging to stderr only", log_dir.display(), e);
+        }
+    }
+}
+
+fn severity(level: &str) -> u8 {
+    match level.to_uppercase().as_str() {
+        "ERROR" => 4,
+        "WARN" => 3,
+        "INFO" => 2,
+        "DEBUG" => 1,
+        _ => 0,
+    }
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let json: JsonLine = serde_json::from_str(line).ok()?;
+    let message = json.fields.get("message")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_string();
+    // Structured fields (provider, duration_ms, ...) follow the message
+    let extra: Vec<String> = json.fields.iter()
+        .filter(|(key, _)| key.as_str() != "message")
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+
+    Some(LogEntry {
+        timestamp: json.timestamp,
+        level: json.level,
+        target: json.target,
+        message: if extra.is_empty() { message } else { format!("{} {}", message, extra.join(" ")) },
+    })
+}
+
+#[tauri::command]
+pub async fn get_recent_logs(level: Option<String>, limit: Option<usize>) -> Result<Vec<LogEntry>, String> {
+    let Some(dir) = LOG_DIR.get() else { return Ok(Vec::new()) };
+    let min_severity = severity(level.as_deref().unwrap_or("INFO"));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    // Rotated names end in the date, so a reverse sort puts the newest file first
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read log dir: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(LOG_PREFIX)))
+        .collect();
+    files.sort_by(|a, b| b.cmp(a));
+
+    let mut entries = Vec::new();
+    for file in files {
+        let contents = std::fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        for entry in contents.lines().rev().filter_map(parse_line) {
+            if severity(&entry.level) >= min_severity {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
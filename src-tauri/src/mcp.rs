@@ -0,0 +1,188 @@
+use serde_json::{json, Value};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{current_provider, extract, generate_vibe_stream, healing, projects, AppState};
+
+// Answered when the client doesn't ask for a specific version
+const PROTOCOL_VERSION: &str = "2024-11-05";
+// Revisions whose tools flow is the same as ours; anything else gets PROTOCOL_VERSION back
+const SUPPORTED_VERSIONS: &[&str] = &[PROTOCOL_VERSION, "2025-03-26"];
+
+// JSON-RPC error codes
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "generate_vibe",
+            "description": "Generate a complete, self-contained single-page web app (HTML, CSS and JavaScript) from a description. Returns the HTML.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "prompt": { "type": "string", "description": "What the app should be and do" },
+                    "save": { "type": "boolean", "description": "Also save the result as a new project" },
+                    "preset": { "type": "string", "description": "Sampler preset, e.g. Chill, Wild or Precise" },
+                    "constraints": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Hard requirements, e.g. \"no external libraries\" or \"must work offline\""
+                    }
+                },
+                "required": ["prompt"]
+            }
+        },
+        {
+            "name": "validate_html",
+            "description": "Check an HTML document for structural problems and JavaScript syntax errors.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "html": { "type": "string" } },
+                "required": ["html"]
+            }
+        },
+        {
+            "name": "list_projects",
+            "description": "List the saved Vibe Cherry projects, newest first.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "load_project",
+            "description": "Get the HTML and details of a saved project.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "save_project",
+            "description": "Save HTML as a project, or update an existing one when id is given.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "html": { "type": "string" },
+                    "prompt": { "type": "string", "description": "What the app is; also used to name it" },
+                    "name": { "type": "string" },
+                    "id": { "type": "string" }
+                },
+                "required": ["html", "prompt"]
+            }
+        }
+    ])
+}
+
+fn string_arg(arguments: &Value, name: &str) -> Option<String> {
+    arguments[name].as_str().map(str::to_string)
+}
+
+fn required(arguments: &Value, name: &str) -> Result<String, String> {
+    string_arg(arguments, name).ok_or_else(|| format!("Missing argument: {}", name))
+}
+
+fn to_text(value: &impl serde::Serialize) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize result: {}", e))
+}
+
+async fn call_tool(app_handle: &AppHandle, name: &str, arguments: &Value) -> Result<String, String> {
+    match name {
+        "generate_vibe" => {
+            let prompt = required(arguments, "prompt")?;
+            let state: State<'_, AppState> = app_handle.state();
+            let silent = Channel::new(|_| Ok(()));
+            let preset = string_arg(arguments, "preset");
+            let constraints = arguments["constraints"].as_array().map(|items| {
+                items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect()
+            });
+            let response =
+                generate_vibe_stream(prompt.clone(), None, None, None, preset, constraints, silent, app_handle.clone(), state.clone())
+                    .await?;
+            let html = extract::extract(&response).html.ok_or("The model did not return an HTML document")?;
+            if arguments["save"].as_bool().unwrap_or(false) {
+                let model = current_provider(&state).model().to_string();
+                let project = projects::write_project(app_handle, None, None, prompt, html.clone(), model)?;
+                return Ok(format!("Saved as project {}\n\n{}", project.meta.id, html));
+            }
+            Ok(html)
+        }
+        "validate_html" => {
+            let html = required(arguments, "html")?;
+            let problems = healing::static_problems(&html);
+            if problems.is_empty() {
+                return Ok("No problems found.".to_string());
+            }
+            Ok(problems.join("\n"))
+        }
+        "list_projects" => to_text(&projects::list_metas(app_handle)?),
+        "load_project" => to_text(&projects::read_project(app_handle, &required(arguments, "id")?)?),
+        "save_project" => {
+            let project = projects::write_project(
+                app_handle,
+                string_arg(arguments, "id"),
+                string_arg(arguments, "name"),
+                required(arguments, "prompt")?,
+                required(arguments, "html")?,
+                // Written by an outside tool, not one of our models
+                "mcp".to_string(),
+            )?;
+            to_text(&project.meta)
+        }
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+fn reply(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+async fn handle_one(app_handle: &AppHandle, message: &Value) -> Option<Value> {
+    let method = message["method"].as_str().unwrap_or("");
+    // Notifications (no id) never get a response
+    let id = message.get("id").cloned()?;
+    let params = &message["params"];
+
+    let response = match method {
+        "initialize" => reply(id, json!({
+            "protocolVersion": params["protocolVersion"]
+                .as_str()
+                .filter(|version| SUPPORTED_VERSIONS.contains(version))
+                .unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "vibe-cherry", "version": env!("CARGO_PKG_VERSION") }
+        })),
+        "ping" => reply(id, json!({})),
+        "tools/list" => reply(id, json!({ "tools": tools() })),
+        "tools/call" => {
+            let Some(name) = params["name"].as_str() else {
+                return Some(error(id, INVALID_PARAMS, "Missing tool name"));
+            };
+            // Tool failures are results the model can read, not protocol errors
+            let (text, is_error) = match call_tool(app_handle, name, &params["arguments"]).await {
+                Ok(text) => (text, false),
+                Err(e) => (crate::keys::redact(&e), true),
+            };
+            reply(id, json!({ "content": [{ "type": "text", "text": text }], "isError": is_error }))
+        }
+        _ => error(id, METHOD_NOT_FOUND, &format!("Method not found: {}", method)),
+    };
+    Some(response)
+}
+
+// One JSON-RPC message or a batch; None when there is nothing to send back
+pub async fn handle(app_handle: &AppHandle, message: Value) -> Option<Value> {
+    match message {
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in &batch {
+                responses.extend(handle_one(app_handle, message).await);
+            }
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => handle_one(app_handle, &message).await,
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::AppState;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationMetrics {
+    pub provider: String,
+    pub model: String,
+    pub attempt: u32,
+    // None when nothing streamed (errors, empty responses)
+    pub ttft_ms: Option<u64>,
+    pub duration_ms: u64,
+    // Streamed chunks; every backend we talk to sends roughly one token per chunk
+    pub total_tokens: u64,
+    pub tokens_per_sec: f64,
+    pub cached: bool,
+    // The configured model when a fallback produced this output instead
+    pub fallback_from: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModelStats {
+    pub generations: u64,
+    pub failures: u64,
+    pub total_tokens: u64,
+    pub total_duration_ms: u64,
+    pub avg_ttft_ms: Option<f64>,
+    pub avg_tokens_per_sec: Option<f64>,
+    #[serde(skip)]
+    ttft_sum: u64,
+    #[serde(skip)]
+    ttft_count: u64,
+}
+
+impl ModelStats {
+    fn add(&mut self, metrics: &GenerationMetrics) {
+        self.generations += 1;
+        self.total_tokens += metrics.total_tokens;
+        self.total_duration_ms += metrics.duration_ms;
+        if let Some(ttft) = metrics.ttft_ms {
+            self.ttft_sum += ttft;
+            self.ttft_count += 1;
+            self.avg_ttft_ms = Some(self.ttft_sum as f64 / self.ttft_count as f64);
+        }
+        if self.total_duration_ms > 0 {
+            self.avg_tokens_per_sec = Some(self.total_tokens as f64 * 1000.0 / self.total_duration_ms as f64);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetricsSummary {
+    pub overall: ModelStats,
+    pub per_model: HashMap<String, ModelStats>,
+    pub cache_hits: u64,
+    pub last: Option<GenerationMetrics>,
+}
+
+// Session-only aggregates; per-generation numbers also live in the history db
+#[derive(Default)]
+pub struct MetricsStore {
+    summary: Mutex<MetricsSummary>,
+}
+
+impl MetricsStore {
+    pub fn record(&self, metrics: &GenerationMetrics) {
+        let mut summary = self.summary.lock().unwrap();
+        if metrics.cached {
+            summary.cache_hits += 1;
+        } else {
+            summary.overall.add(metrics);
+            summary.per_model.entry(metrics.model.clone()).or_default().add(metrics);
+        }
+        summary.last = Some(metrics.clone());
+    }
+
+    pub fn model_stats(&self, model: &str) -> Option<ModelStats> {
+        self.summary.lock().unwrap().per_model.get(model).cloned()
+    }
+
+    pub fn record_failure(&self, model: &str) {
+        let mut summary = self.summary.lock().unwrap();
+        summary.overall.failures += 1;
+        summary.per_model.entry(model.to_string()).or_default().failures += 1;
+    }
+}
+
+pub fn tokens_per_sec(tokens: u64, duration_ms: u64) -> f64 {
+    if duration_ms == 0 {
+        return 0.0;
+    }
+    tokens as f64 * 1000.0 / duration_ms as f64
+}
+
+pub fn emit(app_handle: &AppHandle, metrics: &GenerationMetrics) {
+    if let Err(e) = app_handle.emit("vibe-metrics", metrics) {
+        tracing::warn!("Failed to emit metrics: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_metrics(state: State<'_, AppState>) -> Result<MetricsSummary, String> {
+    Ok(state.metrics.summary.lock().unwrap().clone())
+}
@@ -0,0 +1,115 @@
+use base64::Engine;
+use lol_html::html_content::ContentType;
+use lol_html::{element, rewrite_str, RewriteStrSettings};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{export, html, project_files, projects, tailwind};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MinifiedExport {
+    pub path: String,
+    pub original_size: usize,
+    pub minified_size: usize,
+}
+
+// Project-relative path for a URL, or None for anything that lives elsewhere
+fn local_path(url: &str) -> Option<String> {
+    let url = url.trim();
+    if url.is_empty() || url.contains(':') || url.starts_with("//") {
+        return None;
+    }
+    let path = url.split(['?', '#']).next()?.trim_start_matches('/');
+    project_files::validate_path(path).ok()
+}
+
+fn mime_for(path: &str) -> &'static str {
+    match path.rsplit('.').next().unwrap_or_default().to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+// Pull the project's own scripts, stylesheets and images into the page so it stands alone
+fn inline_assets(app_handle: &AppHandle, project_id: &str, source: &str) -> String {
+    let read = |url: &str| -> Option<(String, Vec<u8>)> {
+        let path = local_path(url)?;
+        let bytes = std::fs::read(project_files::file_path(app_handle, project_id, &path).ok()?).ok()?;
+        Some((path, bytes))
+    };
+
+    let rewritten = rewrite_str(
+        source,
+        RewriteStrSettings {
+            element_content_handlers: vec![
+                element!("script[src]", |el| {
+                    if let Some((_, bytes)) = el.get_attribute("src").and_then(|src| read(&src)) {
+                        el.remove_attribute("src");
+                        el.set_inner_content(&String::from_utf8_lossy(&bytes), ContentType::Html);
+                    }
+                    Ok(())
+                }),
+                element!("link[rel=stylesheet][href]", |el| {
+                    if let Some((_, bytes)) = el.get_attribute("href").and_then(|href| read(&href)) {
+                        let style = format!("<style>{}</style>", String::from_utf8_lossy(&bytes));
+                        el.replace(&style, ContentType::Html);
+                    }
+                    Ok(())
+                }),
+                element!("img[src], link[rel~=icon][href]", |el| {
+                    let attribute = if el.tag_name() == "img" { "src" } else { "href" };
+                    if let Some((path, bytes)) = el.get_attribute(attribute).and_then(|url| read(&url)) {
+                        let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+                        el.set_attribute(attribute, &format!("data:{};base64,{}", mime_for(&path), data))?;
+                    }
+                    Ok(())
+                }),
+            ],
+            ..RewriteStrSettings::default()
+        },
+    );
+
+    rewritten.unwrap_or_else(|e| {
+        tracing::warn!("Failed to inline project assets: {}", e);
+        source.to_string()
+    })
+}
+
+pub fn minify(source: &str) -> String {
+    let cfg = minify_html::Cfg {
+        minify_css: true,
+        minify_js: true,
+        // Keep the output valid for strict parsers (mail clients, embeds)
+        do_not_minify_doctype: true,
+        ensure_spec_compliant_unquoted_attribute_values: true,
+        keep_spaces_between_attributes: true,
+        ..minify_html::Cfg::default()
+    };
+    String::from_utf8_lossy(&minify_html::minify(source.as_bytes(), &cfg)).to_string()
+}
+
+#[tauri::command]
+pub async fn export_minified(project_id: String, app_handle: AppHandle) -> Result<Option<MinifiedExport>, String> {
+    let project = projects::read_project(&app_handle, &project_id)?;
+    let name = html::extract_title(&project.html).unwrap_or_else(|| project.meta.name.clone());
+    let suggested = export::file_name(&format!("{}.min", name), "html");
+    let Some(path) = export::pick_save_path(&app_handle, &suggested, "HTML", &["html", "htm"]).await? else {
+        return Ok(None);
+    };
+
+    let page = inline_assets(&app_handle, &project_id, &project.html);
+    let page = tailwind::for_export(&app_handle, &page);
+    let minified = minify(&page);
+    std::fs::write(&path, &minified).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(Some(MinifiedExport {
+        path: path.to_string_lossy().to_string(),
+        original_size: project.html.len(),
+        minified_size: minified.len(),
+    }))
+}
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::html::escape_html;
+
+// Shipped inside the binary; users can override any of these from app_data_dir/mocks
+const BUNDLED_MANIFEST: &str = include_str!("../assets/mocks/manifest.json");
+const BUNDLED_FIXTURES: &[(&str, &str)] = &[
+    ("todo.html", include_str!("../assets/mocks/todo.html")),
+    ("calculator.html", include_str!("../assets/mocks/calculator.html")),
+    ("fixed.html", include_str!("../assets/mocks/fixed.html")),
+    ("custom.html", include_str!("../assets/mocks/custom.html")),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+struct Fixture {
+    id: String,
+    // Case-insensitive substrings of the prompt that select this fixture
+    #[serde(default)]
+    keywords: Vec<String>,
+    file: String,
+    #[serde(default)]
+    intro: String,
+    #[serde(default)]
+    outro: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Manifest {
+    fixtures: Vec<Fixture>,
+    // Used for every fix attempt regardless of keywords
+    fix_fixture: Option<String>,
+    default_fixture: String,
+}
+
+fn user_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    Some(app_handle.path().app_data_dir().ok()?.join("mocks"))
+}
+
+fn bundled_manifest() -> Manifest {
+    serde_json::from_str(BUNDLED_MANIFEST).expect("bundled mock manifest")
+}
+
+fn bundled_fixture(file: &str) -> Option<String> {
+    BUNDLED_FIXTURES
+        .iter()
+        .find(|(name, _)| *name == file)
+        .map(|(_, html)| html.to_string())
+}
+
+fn load_manifest(app_handle: &AppHandle) -> Manifest {
+    let user_manifest = user_dir(app_handle)
+        .and_then(|dir| std::fs::read_to_string(dir.join("manifest.json")).ok());
+    if let Some(json) = user_manifest {
+        match serde_json::from_str(&json) {
+            Ok(manifest) => return manifest,
+            Err(e) => tracing::warn!("Failed to parse mocks/manifest.json: {}, using bundled mocks", e),
+        }
+    }
+    bundled_manifest()
+}
+
+fn load_fixture(app_handle: &AppHandle, file: &str) -> Option<String> {
+    // Only plain file names; the manifest may come from the user
+    if file.contains('/') || file.contains('\\') || file.contains("..") {
+        return None;
+    }
+    if let Some(html) = user_dir(app_handle).and_then(|dir| std::fs::read_to_string(dir.join(file)).ok()) {
+        return Some(html);
+    }
+    bundled_fixture(file)
+}
+
+fn pick<'a>(manifest: &'a Manifest, prompt: &str, is_fix_attempt: bool) -> Option<&'a Fixture> {
+    let by_id = |id: &str| manifest.fixtures.iter().find(|fixture| fixture.id == id);
+    if is_fix_attempt {
+        if let Some(fixture) = manifest.fix_fixture.as_deref().and_then(by_id) {
+            return Some(fixture);
+        }
+    }
+
+    let prompt = prompt.to_lowercase();
+    manifest.fixtures
+        .iter()
+        .find(|fixture| fixture.keywords.iter().any(|keyword| prompt.contains(&keyword.to_lowercase())))
+        .or_else(|| by_id(&manifest.default_fixture))
+}
+
+// A canned reply shaped like a real model response: intro, html block, feature list
+pub fn response(app_handle: &AppHandle, prompt: &str, is_fix_attempt: bool) -> String {
+    let manifest = load_manifest(app_handle);
+    let fixture = pick(&manifest, prompt, is_fix_attempt);
+    let html = fixture.and_then(|fixture| load_fixture(app_handle, &fixture.file));
+    render(fixture, html, prompt)
+}
+
+// Same as response() but ignores user overrides, so test runs can't be skewed by local files
+pub fn bundled_response(prompt: &str, is_fix_attempt: bool) -> String {
+    let manifest = bundled_manifest();
+    let fixture = pick(&manifest, prompt, is_fix_attempt);
+    let html = fixture.and_then(|fixture| bundled_fixture(&fixture.file));
+    render(fixture, html, prompt)
+}
+
+fn render(fixture: Option<&Fixture>, html: Option<String>, prompt: &str) -> String {
+    match (fixture, html) {
+        (Some(fixture), Some(html)) => format!(
+            "{}\n\n```html\n{}\n```\n\n{}",
+            fixture.intro,
+            html.trim_end().replace("{{prompt}}", &escape_html(prompt)),
+            fixture.outro
+        ),
+        _ => format!("Mock mode has no fixture for this request: \"{}\"", prompt),
+    }
+}
@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::process::Command;
+
+use crate::providers::{ProviderConfig, DEFAULT_OLLAMA_MODEL};
+use crate::settings::{self, Settings};
+use crate::{current_settings, installer, AppState};
+
+pub const DEFAULT_VIBE_MODEL: &str = "vibecherry";
+
+// A model created with `ollama create` from one of the stock models, with our system prompt baked in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VibeModel {
+    pub name: String,
+    pub base: String,
+    // blake3 of the baked system prompt; editing the prompt in settings makes the model stale
+    pub system_hash: String,
+}
+
+impl VibeModel {
+    fn hash(system_prompt: &str) -> String {
+        blake3::hash(system_prompt.as_bytes()).to_hex().to_string()
+    }
+
+    // Only a current model can skip the per-request system prompt; a stale one is
+    // prompted like its base model
+    pub fn is_current(&self, settings: &Settings) -> bool {
+        self.system_hash == Self::hash(&settings.system_prompt())
+    }
+}
+
+// Names `ollama create` accepts, e.g. "vibecherry" or "me/vibecherry:gemma"
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':' | '/'))
+}
+
+// Triple quotes would end the SYSTEM block early
+fn quote_block(text: &str) -> String {
+    format!("\"\"\"{}\"\"\"", text.replace("\"\"\"", "\"\" \""))
+}
+
+fn render(base: &str, settings: &Settings, system_prompt: &str) -> String {
+    let mut modelfile = format!("FROM {}\n\nSYSTEM {}\n\n", base, quote_block(system_prompt));
+    modelfile.push_str(&format!("PARAMETER temperature {}\n", settings.temperature));
+    if let Some(max_tokens) = settings.max_output_tokens {
+        modelfile.push_str(&format!("PARAMETER num_predict {}\n", max_tokens));
+    }
+    for stop in &settings.stop_sequences {
+        if stop.contains('"') || stop.contains('\n') {
+            tracing::warn!("Leaving stop sequence {:?} out of the Modelfile", stop);
+            continue;
+        }
+        modelfile.push_str(&format!("PARAMETER stop \"{}\"\n", stop));
+    }
+    modelfile
+}
+
+fn modelfile_path(app_handle: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?
+        .join("modelfiles");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create modelfiles dir: {}", e))?;
+    Ok(dir.join(format!("{}.Modelfile", name.replace(['/', ':'], "_"))))
+}
+
+// The model to build on: the current Ollama model, or the base of the vibe model in use
+fn default_base(settings: &Settings) -> String {
+    let current = match &settings.provider {
+        ProviderConfig::Ollama { model } => settings.model.clone().or_else(|| model.clone()),
+        _ => None,
+    };
+    match (current, &settings.vibe_model) {
+        (Some(current), Some(vibe)) if current == vibe.name => vibe.base.clone(),
+        (Some(current), _) => current,
+        (None, _) => DEFAULT_OLLAMA_MODEL.to_string(),
+    }
+}
+
+// Bake the system prompt and sampling settings into a custom Ollama model and switch to it.
+// Requests to it no longer resend the system prompt, and Ollama applies the right chat template.
+#[tauri::command]
+pub async fn create_vibe_model(
+    base_model: Option<String>,
+    name: Option<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<VibeModel, String> {
+    let settings = current_settings(&state);
+    let name = name.map(|n| n.trim().to_string()).unwrap_or_else(|| DEFAULT_VIBE_MODEL.to_string());
+    if !valid_name(&name) {
+        return Err(format!("Invalid model name: {}", name));
+    }
+    let base = base_model
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| default_base(&settings));
+    if base == name {
+        return Err(format!("{} cannot be built from itself", name));
+    }
+
+    let system_prompt = settings.system_prompt();
+    let path = modelfile_path(&app_handle, &name)?;
+    std::fs::write(&path, render(&base, &settings, &system_prompt))
+        .map_err(|e| format!("Failed to write Modelfile: {}", e))?;
+
+    let ollama = installer::find_ollama(&app_handle, settings.ollama_path.as_deref())
+        .ok_or("Ollama not found; install it or set its location in settings")?;
+    let output = Command::new(ollama)
+        .arg("create")
+        .arg(&name)
+        .arg("-f")
+        .arg(&path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ollama create: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to create {}: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    tracing::info!(model = %name, base = %base, "Created vibe model");
+
+    let vibe_model = VibeModel { name: name.clone(), base, system_hash: VibeModel::hash(&system_prompt) };
+    let recorded = vibe_model.clone();
+    settings::modify(&app_handle, &state, |settings| {
+        if !matches!(settings.provider, ProviderConfig::Ollama { .. }) {
+            settings.provider = ProviderConfig::Ollama { model: None };
+        }
+        settings.model = Some(name);
+        settings.vibe_model = Some(recorded);
+    })?;
+    Ok(vibe_model)
+}
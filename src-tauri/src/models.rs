@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::providers::{self, InstalledModel, ProviderConfig};
+use crate::settings::{self, Settings};
+use crate::{current_provider, current_settings, test_mode, AppState};
+
+const REGISTRY_URL: &str = "https://registry.ollama.ai";
+const MANIFEST_TYPE: &str = "application/vnd.docker.distribution.manifest.v2+json";
+// Leave startup (and the first generation) alone before hitting the network
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUpdate {
+    pub name: String,
+    pub installed_digest: String,
+    pub latest_digest: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PullProgress {
+    pub model: String,
+    pub status: String,
+    pub completed: u64,
+    pub total: Option<u64>,
+}
+
+// "gemma3:4b" -> ("library/gemma3", "4b"); "me/model" -> ("me/model", "latest").
+// Models from other registries (host names contain a dot) aren't ours to check.
+fn registry_path(name: &str) -> Option<(String, String)> {
+    let (repository, tag) = name.rsplit_once(':').unwrap_or((name, "latest"));
+    let repository = repository.strip_prefix("registry.ollama.ai/").unwrap_or(repository);
+    if repository.split('/').next().is_some_and(|first| first.contains('.')) {
+        return None;
+    }
+    let repository = if repository.contains('/') {
+        repository.to_string()
+    } else {
+        format!("library/{}", repository)
+    };
+    Some((repository, tag.to_string()))
+}
+
+// The registry's manifest digest; None for models it doesn't know (e.g. ones made with ollama create)
+async fn latest_digest(client: &reqwest::Client, name: &str) -> Result<Option<String>, String> {
+    let Some((repository, tag)) = registry_path(name) else { return Ok(None) };
+    let response = client
+        .get(format!("{}/v2/{}/manifests/{}", REGISTRY_URL, repository, tag))
+        .header(reqwest::header::ACCEPT, MANIFEST_TYPE)
+        .timeout(Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the Ollama registry: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let manifest = response
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch manifest for {}: {}", name, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read manifest for {}: {}", name, e))?;
+    // Ollama stores the manifest verbatim, so its digest is the hash of these exact bytes
+    Ok(Some(Sha256::digest(manifest).iter().map(|b| format!("{:02x}", b)).collect()))
+}
+
+fn emit_update(app_handle: &AppHandle, update: &ModelUpdate) {
+    if let Err(e) = app_handle.emit("model-update-available", update) {
+        tracing::warn!("Failed to emit model update: {}", e);
+    }
+}
+
+async fn find_updates(installed: &[InstalledModel]) -> Vec<ModelUpdate> {
+    let client = reqwest::Client::new();
+    let mut updates = Vec::new();
+    for model in installed {
+        let installed_digest = model.digest.trim_start_matches("sha256:");
+        match latest_digest(&client, &model.name).await {
+            Ok(Some(latest)) if latest != installed_digest => updates.push(ModelUpdate {
+                name: model.name.clone(),
+                installed_digest: installed_digest.to_string(),
+                latest_digest: latest,
+            }),
+            Ok(_) => {}
+            // One unreachable manifest shouldn't hide updates for the rest
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+    updates
+}
+
+// Compare every installed model with the registry now; each stale one also gets an event
+#[tauri::command]
+pub async fn check_model_updates(app_handle: AppHandle) -> Result<Vec<ModelUpdate>, String> {
+    let updates = find_updates(&providers::installed_models().await?).await;
+    for update in &updates {
+        emit_update(&app_handle, update);
+    }
+    Ok(updates)
+}
+
+// Background check every settings.model_update_check_hours; each new digest is announced once
+pub fn spawn_update_checks(app_handle: AppHandle) {
+    if test_mode() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut announced = HashSet::new();
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            let hours = current_settings(&app_handle.state::<AppState>()).model_update_check_hours;
+            if hours > 0 {
+                match providers::installed_models().await {
+                    Ok(installed) => {
+                        for update in find_updates(&installed).await {
+                            if announced.insert((update.name.clone(), update.latest_digest.clone())) {
+                                emit_update(&app_handle, &update);
+                            }
+                        }
+                    }
+                    // Ollama isn't running; try again next time
+                    Err(e) => tracing::debug!("Skipping model update check: {}", e),
+                }
+            }
+            // Checked hourly while turned off, so turning it back on takes effect
+            tokio::time::sleep(Duration::from_secs(hours.max(1).saturating_mul(3600))).await;
+        }
+    });
+}
+
+// ollama pull, reporting progress to the frontend as `event`
+pub async fn pull_with_progress(app_handle: &AppHandle, name: &str, event: &str) -> Result<(), String> {
+    providers::pull(name, |status, completed, total| {
+        let progress = PullProgress { model: name.to_string(), status: status.to_string(), completed, total };
+        if let Err(e) = app_handle.emit(event, progress) {
+            tracing::warn!("Failed to emit pull progress: {}", e);
+        }
+    })
+    .await
+}
+
+// Pull the latest weights for `name`, reporting progress as model-upgrade-progress events
+#[tauri::command]
+pub async fn upgrade_model(
+    name: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    pull_with_progress(&app_handle, &name, "model-upgrade-progress").await?;
+    tracing::info!(model = %name, "Upgraded model");
+
+    // A vibe model holds a copy of its base's weights from when it was created
+    match current_settings(&state).vibe_model {
+        Some(vibe) if vibe.base == name => Ok(format!(
+            "Upgraded {}. Recreate {} to build it on the new weights.",
+            name, vibe.name
+        )),
+        _ => Ok(format!("Upgraded {}", name)),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUsage {
+    pub name: String,
+    pub size: u64,
+    pub modified_at: String,
+    // Referenced by settings (main, fallback, vision, embedding or vibe model base)
+    pub in_use: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    // Largest first
+    pub models: Vec<ModelUsage>,
+    // Sum of model sizes; models sharing layers are counted once each
+    pub total_bytes: u64,
+    // Ollama's model store and what it really occupies, when it can be found
+    pub store_path: Option<String>,
+    pub store_bytes: Option<u64>,
+}
+
+// OLLAMA_MODELS wins; otherwise the per-user default (the Linux service keeps its own under /usr/share/ollama)
+fn model_store() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let user = dirs::home_dir().map(|home| home.join(".ollama").join("models"));
+    let service = PathBuf::from("/usr/share/ollama/.ollama/models");
+    user.into_iter().chain([service]).find(|dir| dir.is_dir())
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn models_in_use(settings: &Settings) -> HashSet<String> {
+    let mut names: HashSet<String> = settings.fallback_models.iter().cloned().collect();
+    names.extend(settings.model.clone());
+    names.extend(settings.vision_model.clone());
+    names.insert(settings.embedding_model.clone());
+    if let Some(vibe) = &settings.vibe_model {
+        names.insert(vibe.name.clone());
+        names.insert(vibe.base.clone());
+    }
+    if let ProviderConfig::Ollama { model } = &settings.provider {
+        names.insert(model.clone().unwrap_or_else(|| providers::DEFAULT_OLLAMA_MODEL.to_string()));
+    }
+    names
+}
+
+// Ollama shows "nomic-embed-text:latest" for a model settings call "nomic-embed-text"
+fn same_model(installed: &str, configured: &str) -> bool {
+    installed == configured || installed.strip_suffix(":latest") == Some(configured)
+}
+
+#[tauri::command]
+pub async fn get_model_disk_usage(state: State<'_, AppState>) -> Result<DiskUsage, String> {
+    let in_use = models_in_use(&current_settings(&state));
+    let mut models: Vec<ModelUsage> = providers::installed_models()
+        .await?
+        .into_iter()
+        .map(|model| ModelUsage {
+            in_use: in_use.iter().any(|name| same_model(&model.name, name)),
+            name: model.name,
+            size: model.size,
+            modified_at: model.modified_at,
+        })
+        .collect();
+    models.sort_by(|a, b| b.size.cmp(&a.size));
+    let total_bytes = models.iter().map(|model| model.size).sum();
+
+    let store = model_store();
+    let store_bytes = match store.clone() {
+        Some(dir) => tokio::task::spawn_blocking(move || dir_size(&dir)).await.ok(),
+        None => None,
+    };
+    Ok(DiskUsage {
+        models,
+        total_bytes,
+        store_path: store.map(|dir| dir.display().to_string()),
+        store_bytes,
+    })
+}
+
+// Deletes the weights for good; the active model has to be switched away from first
+#[tauri::command]
+pub async fn remove_model(
+    name: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let active = current_provider(&state).model().to_string();
+    if same_model(&name, &active) || same_model(&active, &name) {
+        return Err(format!("{} is the active model; switch to another model before removing it", name));
+    }
+    providers::delete_model(&name).await?;
+    tracing::info!(model = %name, "Removed model");
+
+    let is_vibe_model = current_settings(&state).vibe_model.is_some_and(|vibe| same_model(&name, &vibe.name));
+    if is_vibe_model {
+        settings::modify(&app_handle, &state, |settings| settings.vibe_model = None)?;
+    }
+    Ok(format!("Removed {}", name))
+}
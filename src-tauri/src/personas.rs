@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{settings, AppState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Persona {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub system_prompt: String,
+    // Use the persona prompt on its own instead of layering it on the base prompt
+    #[serde(default)]
+    pub replaces_base: bool,
+    #[serde(default, skip_deserializing)]
+    pub builtin: bool,
+}
+
+impl Persona {
+    pub fn apply(&self, base_prompt: &str) -> String {
+        if self.replaces_base {
+            self.system_prompt.clone()
+        } else {
+            format!("{}\n\nPERSONA — {}:\n{}", base_prompt, self.name, self.system_prompt)
+        }
+    }
+}
+
+fn builtin(id: &str, name: &str, description: &str, system_prompt: &str) -> Persona {
+    Persona {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        system_prompt: system_prompt.to_string(),
+        replaces_base: false,
+        builtin: true,
+    }
+}
+
+fn builtin_personas() -> Vec<Persona> {
+    vec![
+        builtin(
+            "brutalist",
+            "Brutalist",
+            "Raw, bold, unapologetic web brutalism",
+            "Ignore the default gradient aesthetic. Use stark black and white with one loud accent color, \
+             thick borders, hard drop shadows, oversized monospace or grotesque type, visible grids and no rounded corners. \
+             Interactions should feel mechanical and immediate.",
+        ),
+        builtin(
+            "corporate-clean",
+            "Corporate clean",
+            "Polished SaaS look that would pass a design review",
+            "Use a light theme with lots of whitespace, a restrained blue/slate palette, subtle 1px borders, \
+             small radii and system fonts. Favor clear hierarchy, accessible contrast and calm, professional copy. \
+             Animations should be short and understated.",
+        ),
+        builtin(
+            "retro-terminal",
+            "Retro terminal",
+            "Green-phosphor CRT nostalgia",
+            "Style everything like an 80s terminal: black background, green or amber monospace text, \
+             blinking block cursors, scanline overlays and ASCII borders. Buttons look like bracketed commands, e.g. [ START ]. \
+             Keep it fully functional underneath the retro skin.",
+        ),
+        builtin(
+            "kids-mode",
+            "Kids mode",
+            "Friendly, big and safe for young users",
+            "Design for children aged 6-10: huge tap targets, bright primary colors, rounded playful shapes, \
+             emoji, simple words and encouraging feedback messages. Never include external links, text inputs for \
+             personal information, or anything scary.",
+        ),
+    ]
+}
+
+fn personas_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    Some(app_handle.path().app_data_dir().ok()?.join("personas"))
+}
+
+// Built-ins first, then any *.json packs the user dropped into the personas folder.
+// A user file can override a built-in by reusing its id.
+pub fn load_all(app_handle: &AppHandle) -> Vec<Persona> {
+    let mut personas = builtin_personas();
+
+    let Some(dir) = personas_dir(app_handle) else { return personas };
+    let Ok(entries) = std::fs::read_dir(&dir) else { return personas };
+
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.extension().map(|ext| ext != "json").unwrap_or(true) {
+            continue;
+        }
+        let Ok(contents) = std::fs::read_to_string(&path) else { continue };
+
+        // A file may hold one persona or a whole pack
+        let loaded: Vec<Persona> = match serde_json::from_str::<Vec<Persona>>(&contents) {
+            Ok(pack) => pack,
+            Err(_) => match serde_json::from_str::<Persona>(&contents) {
+                Ok(persona) => vec![persona],
+                Err(e) => {
+                    tracing::warn!("Skipping invalid persona file {}: {}", path.display(), e);
+                    continue;
+                }
+            },
+        };
+
+        for persona in loaded {
+            personas.retain(|p| p.id != persona.id);
+            personas.push(persona);
+        }
+    }
+
+    personas
+}
+
+pub fn find(app_handle: &AppHandle, id: &str) -> Option<Persona> {
+    load_all(app_handle).into_iter().find(|p| p.id == id)
+}
+
+#[tauri::command]
+pub async fn list_personas(app_handle: AppHandle) -> Result<Vec<Persona>, String> {
+    if let Some(dir) = personas_dir(&app_handle) {
+        // Create the folder so users can find where to put their packs
+        let _ = std::fs::create_dir_all(dir);
+    }
+    Ok(load_all(&app_handle))
+}
+
+// None switches back to the plain Vibe Cherry prompt
+#[tauri::command]
+pub async fn set_persona(
+    id: Option<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<Persona>, String> {
+    let persona = match &id {
+        Some(id) => Some(find(&app_handle, id).ok_or_else(|| format!("Unknown persona: {}", id))?),
+        None => None,
+    };
+
+    settings::modify(&app_handle, &state, |settings| settings.persona = id)?;
+    Ok(persona)
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::settings::Settings;
+use crate::{current_settings, formatter, html, preview, sanitize, tailwind, AppState};
+
+// Where a page is headed: the sandboxed preview, or a file the user takes elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Preview,
+    Export,
+}
+
+pub struct PostContext<'a> {
+    pub app_handle: &'a AppHandle,
+    pub settings: &'a Settings,
+    pub stage: Stage,
+}
+
+pub struct Processed {
+    pub html: String,
+    // Human-readable notes about what changed (removed elements, skipped steps, ...)
+    pub diagnostics: Vec<String>,
+}
+
+impl Processed {
+    // Transforms with nothing to report
+    fn new(html: String) -> Self {
+        Self { html, diagnostics: Vec::new() }
+    }
+}
+
+// One transform applied to extracted HTML before it is previewed or exported
+pub trait PostProcessor: Send + Sync {
+    // Name used in settings.post_processors
+    fn id(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn applies_to(&self, stage: Stage) -> bool;
+
+    fn process(&self, html: &str, context: &PostContext) -> Processed;
+}
+
+struct Sanitizer;
+
+impl PostProcessor for Sanitizer {
+    fn id(&self) -> &'static str {
+        "sanitize"
+    }
+
+    fn description(&self) -> &'static str {
+        "Strip unexpected external resources and add a CSP before previews run generated code"
+    }
+
+    fn applies_to(&self, stage: Stage) -> bool {
+        stage == Stage::Preview
+    }
+
+    fn process(&self, html: &str, context: &PostContext) -> Processed {
+        // sanitize_previews predates the pipeline and still switches it off
+        if !context.settings.sanitize_previews {
+            return Processed::new(html.to_string());
+        }
+        let result = sanitize::sanitize(html);
+        let diagnostics = result.removed.into_iter().map(|what| format!("Removed {}", what)).collect();
+        Processed { html: result.html, diagnostics }
+    }
+}
+
+struct TailwindRewrite;
+
+impl PostProcessor for TailwindRewrite {
+    fn id(&self) -> &'static str {
+        "tailwind"
+    }
+
+    fn description(&self) -> &'static str {
+        "Serve previews from the bundled Tailwind build; inline it into exports unless they keep the CDN link"
+    }
+
+    fn applies_to(&self, _stage: Stage) -> bool {
+        true
+    }
+
+    fn process(&self, html: &str, context: &PostContext) -> Processed {
+        let Some(script) = tailwind::bundled_script(context.app_handle) else {
+            return Processed::new(html.to_string());
+        };
+        match context.stage {
+            Stage::Preview => Processed::new(tailwind::localize(html, &preview::tailwind_url())),
+            Stage::Export if !context.settings.export_tailwind_cdn => Processed::new(tailwind::inline(html, script)),
+            Stage::Export => Processed::new(html.to_string()),
+        }
+    }
+}
+
+struct Formatter;
+
+impl PostProcessor for Formatter {
+    fn id(&self) -> &'static str {
+        "format"
+    }
+
+    fn description(&self) -> &'static str {
+        "Re-indent exported markup by nesting depth"
+    }
+
+    fn applies_to(&self, stage: Stage) -> bool {
+        stage == Stage::Export
+    }
+
+    fn process(&self, html: &str, _context: &PostContext) -> Processed {
+        Processed::new(formatter::format_html(html))
+    }
+}
+
+struct AnalyticsSnippet;
+
+impl PostProcessor for AnalyticsSnippet {
+    fn id(&self) -> &'static str {
+        "analytics"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add the analytics snippet from settings to exported pages"
+    }
+
+    // Previews would count every edit as a visit
+    fn applies_to(&self, stage: Stage) -> bool {
+        stage == Stage::Export
+    }
+
+    fn process(&self, html: &str, context: &PostContext) -> Processed {
+        match context.settings.analytics_snippet.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(snippet) => Processed::new(html::inject_into_head(html, snippet)),
+            None => Processed::new(html.to_string()),
+        }
+    }
+}
+
+// Run order; the sanitizer's allow-list knows the Tailwind CDN host, not the local rewrite
+const PROCESSORS: &[&dyn PostProcessor] = &[&Sanitizer, &TailwindRewrite, &Formatter, &AnalyticsSnippet];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub processor: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineResult {
+    pub html: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// Every enabled processor for `stage`, in PROCESSORS order
+pub fn run(app_handle: &AppHandle, stage: Stage, html: &str) -> PipelineResult {
+    let settings = current_settings(&app_handle.state::<AppState>());
+    let context = PostContext { app_handle, settings: &settings, stage };
+
+    let mut html = html.to_string();
+    let mut diagnostics = Vec::new();
+    for processor in PROCESSORS {
+        if !processor.applies_to(stage) || !settings.post_processors.iter().any(|id| id == processor.id()) {
+            continue;
+        }
+        let processed = processor.process(&html, &context);
+        html = processed.html;
+        diagnostics.extend(processed.diagnostics.into_iter().map(|message| Diagnostic {
+            processor: processor.id().to_string(),
+            message,
+        }));
+    }
+    PipelineResult { html, diagnostics }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostProcessorInfo {
+    pub id: String,
+    pub description: String,
+    pub preview: bool,
+    pub export: bool,
+    pub enabled: bool,
+}
+
+#[tauri::command]
+pub async fn list_post_processors(state: State<'_, AppState>) -> Result<Vec<PostProcessorInfo>, String> {
+    let enabled = current_settings(&state).post_processors;
+    Ok(PROCESSORS
+        .iter()
+        .map(|processor| PostProcessorInfo {
+            id: processor.id().to_string(),
+            description: processor.description().to_string(),
+            preview: processor.applies_to(Stage::Preview),
+            export: processor.applies_to(Stage::Export),
+            enabled: enabled.iter().any(|id| id == processor.id()),
+        })
+        .collect())
+}
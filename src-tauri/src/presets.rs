@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::{current_settings, settings, AppState};
+
+// Named sampler settings, so "Wild" can be picked instead of remembering temperature 1.2 / top_p 0.98
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationPreset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub temperature: f32,
+    // None leaves the backend's default
+    #[serde(default)]
+    pub top_p: Option<f32>,
+}
+
+fn preset(name: &str, description: &str, temperature: f32, top_p: Option<f32>) -> GenerationPreset {
+    GenerationPreset {
+        name: name.to_string(),
+        description: description.to_string(),
+        temperature,
+        top_p,
+    }
+}
+
+// Seeded into settings; users can edit or delete them like their own
+pub fn builtin_presets() -> Vec<GenerationPreset> {
+    vec![
+        preset("Chill", "Safe, conventional layouts with few surprises", 0.4, None),
+        preset("Wild", "Unexpected ideas and styling; expect the odd broken app", 1.2, Some(0.98)),
+        // Temperature 0 always takes the most likely token
+        preset("Precise", "Greedy decoding: the same prompt gives the same app", 0.0, None),
+    ]
+}
+
+pub fn find<'a>(presets: &'a [GenerationPreset], name: &str) -> Option<&'a GenerationPreset> {
+    presets.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+// The app state with one request's preset applied to its sampler settings
+pub fn scoped(state: &AppState, preset: Option<&str>) -> Result<AppState, String> {
+    let Some(name) = preset else { return Ok(state.clone()) };
+    let mut settings = current_settings(state);
+    let preset = find(&settings.presets, name).ok_or_else(|| format!("Unknown preset: {}", name))?.clone();
+    settings.temperature = preset.temperature;
+    settings.top_p = preset.top_p;
+
+    Ok(AppState {
+        settings: Arc::new(Mutex::new(settings)),
+        ..state.clone()
+    })
+}
+
+fn validate(preset: &GenerationPreset) -> Result<(), String> {
+    if preset.name.trim().is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    if !(0.0..=2.0).contains(&preset.temperature) {
+        return Err("Temperature must be between 0 and 2".to_string());
+    }
+    if let Some(top_p) = preset.top_p {
+        if !(top_p > 0.0 && top_p <= 1.0) {
+            return Err("top_p must be greater than 0 and at most 1".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_presets(state: State<'_, AppState>) -> Result<Vec<GenerationPreset>, String> {
+    Ok(current_settings(&state).presets)
+}
+
+// Adds the preset, or replaces the one with the same name
+#[tauri::command]
+pub async fn save_preset(
+    preset: GenerationPreset,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<GenerationPreset>, String> {
+    let preset = GenerationPreset { name: preset.name.trim().to_string(), ..preset };
+    validate(&preset)?;
+    let settings = settings::modify(&app_handle, &state, |settings| {
+        match settings.presets.iter_mut().find(|p| p.name.eq_ignore_ascii_case(&preset.name)) {
+            Some(existing) => *existing = preset,
+            None => settings.presets.push(preset),
+        }
+    })?;
+    Ok(settings.presets)
+}
+
+#[tauri::command]
+pub async fn delete_preset(
+    name: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<GenerationPreset>, String> {
+    if find(&current_settings(&state).presets, &name).is_none() {
+        return Err(format!("Unknown preset: {}", name));
+    }
+    let settings = settings::modify(&app_handle, &state, |settings| {
+        settings.presets.retain(|p| !p.name.eq_ignore_ascii_case(&name));
+    })?;
+    Ok(settings.presets)
+}
@@ -0,0 +1,346 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::http::{Request, Response};
+use tauri::{AppHandle, Emitter, Manager, UriSchemeContext, WebviewUrl, WebviewWindowBuilder};
+use tokio::process::Command;
+use tokio::sync::oneshot;
+
+use crate::postprocess::{self, Stage};
+use crate::{project_files, projects, tailwind};
+
+pub const PREVIEW_SCHEME: &str = "vibe-preview";
+const SMOKE_DURATION_MS: u64 = 3000;
+
+// Injected before any page script runs; reports back to the scheme handler below
+const SMOKE_SCRIPT: &str = r#"(() => {
+  const issues = [];
+  const push = (kind, message, source, line, column) =>
+    issues.push({ kind, message: String(message), source: source || null, line: line || null, column: column || null });
+  window.addEventListener('error', (e) => push('exception', e.message, e.filename, e.lineno, e.colno));
+  window.addEventListener('unhandledrejection', (e) => push('unhandled_rejection', e.reason && e.reason.stack || e.reason));
+  const originalError = console.error;
+  console.error = (...args) => { push('console_error', args.map(String).join(' ')); originalError.apply(console, args); };
+  setTimeout(() => {
+    fetch('/__smoke/__TOKEN__', { method: 'POST', body: JSON.stringify(issues) }).catch(() => {});
+  }, __DURATION__);
+})();"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeIssue {
+    // exception, unhandled_rejection or console_error
+    pub kind: String,
+    pub message: String,
+    pub source: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeTestResult {
+    pub passed: bool,
+    pub issues: Vec<RuntimeIssue>,
+    // The page never reported back, which usually means it hung or crashed
+    pub timed_out: bool,
+}
+
+// Unsaved HTML (e.g. a healing attempt) served under /__scratch/<token>
+fn scratch_pages() -> &'static Mutex<HashMap<String, String>> {
+    static SCRATCH: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    SCRATCH.get_or_init(Default::default)
+}
+
+fn pending_smoke_tests() -> &'static Mutex<HashMap<String, oneshot::Sender<Vec<RuntimeIssue>>>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, oneshot::Sender<Vec<RuntimeIssue>>>>> = OnceLock::new();
+    PENDING.get_or_init(Default::default)
+}
+
+// Windows and Android expose custom schemes as http://<scheme>.localhost
+const TAILWIND_PATH: &str = "__vendor/tailwind.js";
+
+pub fn preview_url(path: &str) -> String {
+    if cfg!(any(windows, target_os = "android")) {
+        format!("http://{}.localhost/{}", PREVIEW_SCHEME, path.trim_start_matches('/'))
+    } else {
+        format!("{}://localhost/{}", PREVIEW_SCHEME, path.trim_start_matches('/'))
+    }
+}
+
+fn respond(status: u16, content_type: &str, body: Vec<u8>) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", content_type)
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Cow::Owned(body))
+        .unwrap()
+}
+
+// Serves /<project_id>/index.html and collects smoke test reports
+pub fn handle_protocol(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+) -> Response<Cow<'static, [u8]>> {
+    let path = request.uri().path().trim_start_matches('/').to_string();
+
+    if let Some(token) = path.strip_prefix("__smoke/") {
+        let issues: Vec<RuntimeIssue> = serde_json::from_slice(request.body()).unwrap_or_default();
+        if let Some(sender) = pending_smoke_tests().lock().unwrap().remove(token) {
+            let _ = sender.send(issues);
+        }
+        return respond(204, "text/plain", Vec::new());
+    }
+
+    if path == TAILWIND_PATH {
+        return match tailwind::bundled_script(ctx.app_handle()) {
+            Some(script) => respond(200, "text/javascript", script.to_vec()),
+            None => respond(404, "text/plain", b"Tailwind is not bundled".to_vec()),
+        };
+    }
+
+    if let Some(token) = path.strip_prefix("__scratch/") {
+        return match scratch_pages().lock().unwrap().get(token) {
+            Some(html) => serve_html(ctx.app_handle(), html),
+            None => respond(404, "text/plain", b"Scratch page expired".to_vec()),
+        };
+    }
+
+    // /<project_id>/ is the page itself; anything after it is one of a multi-file project's files
+    let (project_id, file) = path.split_once('/').unwrap_or((&path, ""));
+    if !file.is_empty() && file != projects::HTML_FILE {
+        return match project_files::file_path(ctx.app_handle(), project_id, file).and_then(|p| {
+            std::fs::read(&p).map_err(|e| format!("Failed to read {}: {}", p.display(), e))
+        }) {
+            Ok(contents) => respond(200, project_files::content_type_for(file), contents),
+            Err(e) => respond(404, "text/plain", e.into_bytes()),
+        };
+    }
+
+    match projects::html_path(ctx.app_handle(), project_id).and_then(|p| {
+        std::fs::read_to_string(&p).map_err(|e| format!("Failed to read {}: {}", p.display(), e))
+    }) {
+        Ok(html) => serve_html(ctx.app_handle(), &html),
+        Err(e) => respond(404, "text/plain", e.into_bytes()),
+    }
+}
+
+// Model-written pages go through the preview post-processors (sanitizer + CSP, bundled
+// Tailwind so previews work offline) before they run
+fn serve_html(app_handle: &AppHandle, html: &str) -> Response<Cow<'static, [u8]>> {
+    let html = postprocess::run(app_handle, Stage::Preview, html).html;
+    respond(200, "text/html; charset=utf-8", html.into_bytes())
+}
+
+// Where previews load the bundled Tailwind build from
+pub fn tailwind_url() -> String {
+    preview_url(TAILWIND_PATH)
+}
+
+const CAPTURE_WIDTH: u32 = 1280;
+const CAPTURE_HEIGHT: u32 = 800;
+const CAPTURE_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewReload {
+    pub project_id: String,
+    pub updated_at: u64,
+}
+
+pub fn preview_window_label(project_id: &str) -> String {
+    format!("preview-{}", project_id)
+}
+
+const WINDOW_WIDTH: f64 = 1024.0;
+const WINDOW_HEIGHT: f64 = 768.0;
+
+// One window per project; opening it again just brings the existing one forward
+pub fn open_window(app_handle: &AppHandle, project: &projects::ProjectMeta) -> Result<(), String> {
+    let label = preview_window_label(&project.id);
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        return window.set_focus().map_err(|e| format!("Failed to focus preview window: {}", e));
+    }
+    let url = preview_url(&format!("{}/{}", project.id, projects::HTML_FILE));
+    WebviewWindowBuilder::new(
+        app_handle,
+        label,
+        WebviewUrl::External(url.parse().map_err(|e| format!("Invalid preview URL: {}", e))?),
+    )
+    .title(match &project.emoji {
+        Some(emoji) => format!("{} {} · Preview", emoji, project.name),
+        None => format!("{} · Preview", project.name),
+    })
+    .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+    .resizable(true)
+    .build()
+    .map_err(|e| format!("Failed to open preview window: {}", e))?;
+    Ok(())
+}
+
+// Called whenever a project's HTML changes (regeneration, healing, edits) so open
+// previews refresh themselves instead of showing stale output
+pub fn notify_reload(app_handle: &AppHandle, project_id: &str, updated_at: u64) {
+    if let Some(window) = app_handle.get_webview_window(&preview_window_label(project_id)) {
+        if let Err(e) = window.eval("window.location.reload()") {
+            tracing::warn!("Failed to reload preview window: {}", e);
+        }
+    }
+
+    let payload = PreviewReload {
+        project_id: project_id.to_string(),
+        updated_at,
+    };
+    if let Err(e) = app_handle.emit("preview-reload", payload) {
+        tracing::warn!("Failed to emit preview reload: {}", e);
+    }
+}
+
+// Tauri can't snapshot a webview on every platform, so screenshots go through
+// whichever Chromium-based browser is installed (Edge ships with Windows).
+const BROWSER_COMMANDS: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "microsoft-edge",
+    "msedge",
+];
+
+const BROWSER_PATHS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+    r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+];
+
+pub fn find_headless_browser() -> Option<PathBuf> {
+    let from_path = std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths).find_map(|dir| {
+            BROWSER_COMMANDS.iter().find_map(|name| {
+                let candidate = dir.join(if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() });
+                candidate.is_file().then_some(candidate)
+            })
+        })
+    });
+
+    from_path.or_else(|| {
+        BROWSER_PATHS.iter().map(PathBuf::from).find(|path| path.is_file())
+    })
+}
+
+pub fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+pub async fn screenshot(html_path: &Path, output: &Path) -> Result<(), String> {
+    let browser = find_headless_browser()
+        .ok_or("No Chrome, Chromium or Edge installation found for capturing previews")?;
+
+    let mut cmd = Command::new(browser);
+    cmd.args(["--headless=new", "--disable-gpu", "--hide-scrollbars", "--virtual-time-budget=2000"])
+        .arg(format!("--window-size={},{}", CAPTURE_WIDTH, CAPTURE_HEIGHT))
+        .arg(format!("--screenshot={}", output.display()))
+        .arg(file_url(html_path))
+        .kill_on_drop(true);
+
+    let output_result = tokio::time::timeout(Duration::from_secs(CAPTURE_TIMEOUT_SECS), cmd.output())
+        .await
+        .map_err(|_| "Preview capture timed out".to_string())?
+        .map_err(|e| format!("Failed to launch headless browser: {}", e))?;
+
+    if !output.exists() {
+        let stderr = String::from_utf8_lossy(&output_result.stderr);
+        return Err(format!("Headless browser did not produce a screenshot: {}", stderr.trim()));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn open_preview_window(project_id: String, app_handle: AppHandle) -> Result<String, String> {
+    let meta = projects::read_meta(&app_handle, &project_id)
+        .map_err(|_| format!("Project {} not found", project_id))?;
+    open_window(&app_handle, &meta)?;
+    Ok(preview_window_label(&meta.id))
+}
+
+#[tauri::command]
+pub async fn capture_preview(project_id: String, app_handle: AppHandle) -> Result<String, String> {
+    let html_path = projects::html_path(&app_handle, &project_id)?;
+    if !html_path.exists() {
+        return Err(format!("Project {} not found", project_id));
+    }
+
+    let thumbnail = projects::thumbnail_path(&app_handle, &project_id)?;
+    screenshot(&html_path, &thumbnail).await?;
+    Ok(thumbnail.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn smoke_test(project_id: String, app_handle: AppHandle) -> Result<SmokeTestResult, String> {
+    run_smoke_test(&app_handle, &project_id).await
+}
+
+pub async fn run_smoke_test(app_handle: &AppHandle, project_id: &str) -> Result<SmokeTestResult, String> {
+    if !projects::html_path(app_handle, project_id)?.exists() {
+        return Err(format!("Project {} not found", project_id));
+    }
+    smoke_test_page(app_handle, &format!("{}/index.html", project_id)).await
+}
+
+pub async fn smoke_test_html(app_handle: &AppHandle, html: &str) -> Result<SmokeTestResult, String> {
+    let token = uuid::Uuid::new_v4().to_string();
+    scratch_pages().lock().unwrap().insert(token.clone(), html.to_string());
+    let result = smoke_test_page(app_handle, &format!("__scratch/{}", token)).await;
+    scratch_pages().lock().unwrap().remove(&token);
+    result
+}
+
+async fn smoke_test_page(app_handle: &AppHandle, page_path: &str) -> Result<SmokeTestResult, String> {
+    let token = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    pending_smoke_tests().lock().unwrap().insert(token.clone(), tx);
+
+    let url = preview_url(page_path);
+    let script = SMOKE_SCRIPT
+        .replace("__TOKEN__", &token)
+        .replace("__DURATION__", &SMOKE_DURATION_MS.to_string());
+
+    let window = WebviewWindowBuilder::new(
+        app_handle,
+        format!("smoke-{}", token),
+        WebviewUrl::External(url.parse().map_err(|e| format!("Invalid preview URL: {}", e))?),
+    )
+    .visible(false)
+    .initialization_script(&script)
+    .build()
+    .map_err(|e| {
+        pending_smoke_tests().lock().unwrap().remove(&token);
+        format!("Failed to open smoke test window: {}", e)
+    })?;
+
+    let wait = Duration::from_millis(SMOKE_DURATION_MS + 5000);
+    let outcome = tokio::time::timeout(wait, rx).await;
+    pending_smoke_tests().lock().unwrap().remove(&token);
+    let _ = window.destroy();
+
+    let (issues, timed_out) = match outcome {
+        Ok(Ok(issues)) => (issues, false),
+        _ => (Vec::new(), true),
+    };
+
+    Ok(SmokeTestResult {
+        passed: issues.is_empty() && !timed_out,
+        issues,
+        timed_out,
+    })
+}
@@ -0,0 +1,93 @@
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{current_settings, AppState};
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+// Used until this session has finished a generation with the model
+const FALLBACK_TOKENS_PER_SEC: f64 = 15.0;
+const FALLBACK_TTFT_MS: f64 = 3000.0;
+const FALLBACK_RESPONSE_TOKENS: f64 = 1500.0;
+// Fewer streamed tokens than this make the live rate too noisy to trust
+const MIN_TOKENS_FOR_LIVE_RATE: u64 = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationProgress {
+    // waiting (no tokens yet) or streaming
+    pub phase: &'static str,
+    pub elapsed_ms: u64,
+    pub tokens: u64,
+    pub tokens_per_sec: f64,
+    pub expected_tokens: u64,
+    pub eta_ms: u64,
+    // Capped below 100 since the estimate can't know when the model will stop
+    pub percent: f64,
+}
+
+struct Typical {
+    tokens_per_sec: f64,
+    ttft_ms: f64,
+    response_tokens: f64,
+}
+
+fn typical(state: &AppState, model: &str) -> Typical {
+    let stats = state.metrics.model_stats(model).filter(|stats| stats.generations > 0);
+    let max_tokens = current_settings(state).max_output_tokens.map(|max| max as f64);
+    Typical {
+        tokens_per_sec: stats.as_ref().and_then(|s| s.avg_tokens_per_sec).filter(|tps| *tps > 0.0).unwrap_or(FALLBACK_TOKENS_PER_SEC),
+        ttft_ms: stats.as_ref().and_then(|s| s.avg_ttft_ms).unwrap_or(FALLBACK_TTFT_MS),
+        response_tokens: stats
+            .map(|s| s.total_tokens as f64 / s.generations as f64)
+            .or(max_tokens)
+            .unwrap_or(FALLBACK_RESPONSE_TOKENS),
+    }
+}
+
+// Emits vibe-progress every second until the caller drops it (select it against the generation)
+pub async fn heartbeat(app_handle: &AppHandle, state: &AppState, model: &str, tokens: &AtomicU64) -> Infallible {
+    let typical = typical(state, model);
+    let started = Instant::now();
+    let mut first_token: Option<Instant> = None;
+    let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+    // The first tick fires immediately; skip it so short generations send nothing
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        let elapsed_ms = started.elapsed().as_millis() as f64;
+        let count = tokens.load(Ordering::Relaxed);
+        if count > 0 && first_token.is_none() {
+            first_token = Some(Instant::now());
+        }
+
+        let streaming_ms = first_token.map(|at| at.elapsed().as_millis() as f64).unwrap_or(0.0);
+        let rate = if count >= MIN_TOKENS_FOR_LIVE_RATE && streaming_ms > 0.0 {
+            count as f64 * 1000.0 / streaming_ms
+        } else {
+            typical.tokens_per_sec
+        };
+        let expected = typical.response_tokens.max(count as f64 * 1.1);
+        let remaining_ms = (expected - count as f64) / rate * 1000.0;
+        let eta_ms = if count == 0 {
+            (typical.ttft_ms - elapsed_ms).max(0.0) + remaining_ms
+        } else {
+            remaining_ms
+        };
+
+        let progress = GenerationProgress {
+            phase: if count == 0 { "waiting" } else { "streaming" },
+            elapsed_ms: elapsed_ms as u64,
+            tokens: count,
+            tokens_per_sec: rate,
+            expected_tokens: expected as u64,
+            eta_ms: eta_ms as u64,
+            percent: (elapsed_ms / (elapsed_ms + eta_ms) * 100.0).min(99.0),
+        };
+        if let Err(e) = app_handle.emit("vibe-progress", progress) {
+            tracing::warn!("Failed to emit progress: {}", e);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::path::{Component, Path, PathBuf};
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+
+use crate::projects::{self, HTML_FILE, META_FILE, THUMBNAIL_FILE};
+use crate::{build_full_prompt, current_provider, extract, git, keys, now_millis, preview, prompts, stream_from_provider, AppState, StreamEvent};
+
+// Things the app keeps inside a project dir that must never be treated as source
+const RESERVED: &[&str] = &[META_FILE, THUMBNAIL_FILE, "pwa", "versions"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectFile {
+    pub path: String,
+    pub language: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectFiles {
+    pub project: projects::ProjectMeta,
+    pub files: Vec<ProjectFile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub language: String,
+    pub size: u64,
+}
+
+// Relative, forward-slash paths of plain segments only; the result is safe to join onto the project dir
+pub fn validate_path(path: &str) -> Result<String, String> {
+    let normalized = path.trim().trim_start_matches("./").replace('\\', "/");
+    let valid_segment = |segment: &str| {
+        !segment.is_empty()
+            && !segment.starts_with('.')
+            && segment.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if normalized.is_empty() || !normalized.split('/').all(valid_segment) {
+        return Err(format!("Invalid file path: {}", path));
+    }
+    let first = normalized.split('/').next().unwrap_or_default();
+    if RESERVED.contains(&first) {
+        return Err(format!("{} is reserved", first));
+    }
+    if Path::new(&normalized).components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(format!("Invalid file path: {}", path));
+    }
+    Ok(normalized)
+}
+
+pub fn language_for(path: &str) -> &'static str {
+    match path.rsplit('.').next().unwrap_or_default().to_ascii_lowercase().as_str() {
+        "html" | "htm" => "html",
+        "css" => "css",
+        "js" | "mjs" => "javascript",
+        "json" => "json",
+        "svg" => "svg",
+        "md" => "markdown",
+        _ => "text",
+    }
+}
+
+pub fn content_type_for(path: &str) -> &'static str {
+    match language_for(path) {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "javascript" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "svg" => "image/svg+xml",
+        _ => "text/plain; charset=utf-8",
+    }
+}
+
+pub fn file_path(app_handle: &AppHandle, project_id: &str, path: &str) -> Result<PathBuf, String> {
+    Ok(projects::project_dir(app_handle, project_id)?.join(validate_path(path)?))
+}
+
+// Blocks without a file name fall back to the conventional one for their language
+fn default_name(language: &str) -> Option<&'static str> {
+    match language {
+        "html" | "htm" => Some(HTML_FILE),
+        "css" => Some("styles.css"),
+        "js" | "javascript" => Some("app.js"),
+        _ => None,
+    }
+}
+
+pub fn parse_files(response: &str) -> Vec<ProjectFile> {
+    let (blocks, _) = extract::parse_blocks(response);
+    let mut files: Vec<ProjectFile> = Vec::new();
+    for block in blocks {
+        let name = block.filename.clone().or_else(|| default_name(&block.language).map(str::to_string));
+        let Some(path) = name.and_then(|name| validate_path(&name).ok()) else { continue };
+        // A later block for the same file wins, matching how a person would read it
+        files.retain(|file| file.path != path);
+        files.push(ProjectFile {
+            language: language_for(&path).to_string(),
+            path,
+            content: block.code,
+        });
+    }
+    files
+}
+
+fn write_file(app_handle: &AppHandle, project_id: &str, path: &str, content: &str) -> Result<(), String> {
+    let target = file_path(app_handle, project_id, path)?;
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&target, content).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+fn collect(dir: &Path, root: &Path, entries: &mut Vec<FileEntry>) -> Result<(), String> {
+    let items = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for item in items.flatten() {
+        let path = item.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        if validate_path(&relative).is_err() {
+            continue;
+        }
+        if path.is_dir() {
+            collect(&path, root, entries)?;
+        } else {
+            entries.push(FileEntry {
+                language: language_for(&relative).to_string(),
+                size: item.metadata().map(|m| m.len()).unwrap_or(0),
+                path: relative,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn generate_project_files(
+    prompt: String,
+    project_id: Option<String>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ProjectFiles, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+
+    let user_turn = format!("{}\n\nREQUEST: {}", prompts::MULTI_FILE_INSTRUCTIONS, prompt);
+    let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
+    let response = stream_from_provider(&state, &app_handle, &on_token, &prompt, &full_prompt, 1)
+        .await
+        .map_err(|e| keys::redact(&e))?;
+
+    let files = parse_files(&response);
+    let Some(index) = files.iter().find(|file| file.path == HTML_FILE) else {
+        return Err("The model did not return an index.html".to_string());
+    };
+
+    let model = current_provider(&state).model().to_string();
+    let project = projects::write_project(&app_handle, project_id, None, prompt, index.content.clone(), model)?;
+    for file in files.iter().filter(|file| file.path != HTML_FILE) {
+        write_file(&app_handle, &project.meta.id, &file.path, &file.content)?;
+    }
+
+    let mut meta = project.meta;
+    meta.files = files.iter().map(|file| file.path.clone()).collect();
+    projects::write_meta(&app_handle, &meta)?;
+    // write_project committed index.html alone; this picks up the other files
+    git::commit_quietly(&app_handle, &meta.id, &meta.prompt);
+
+    Ok(ProjectFiles { project: meta, files })
+}
+
+#[tauri::command]
+pub async fn list_project_files(project_id: String, app_handle: AppHandle) -> Result<Vec<FileEntry>, String> {
+    let root = projects::project_dir(&app_handle, &project_id)?;
+    let mut entries = Vec::new();
+    collect(&root, &root, &mut entries)?;
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn read_project_file(project_id: String, path: String, app_handle: AppHandle) -> Result<String, String> {
+    let target = file_path(&app_handle, &project_id, &path)?;
+    std::fs::read_to_string(&target).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+#[tauri::command]
+pub async fn write_project_file(
+    project_id: String,
+    path: String,
+    content: String,
+    app_handle: AppHandle,
+) -> Result<projects::ProjectMeta, String> {
+    let path = validate_path(&path)?;
+    let mut meta = projects::read_meta(&app_handle, &project_id)?;
+    write_file(&app_handle, &project_id, &path, &content)?;
+    let message = format!("Edit {}", path);
+
+    if !meta.files.contains(&path) {
+        meta.files.push(path);
+    }
+    meta.updated_at = now_millis();
+    projects::write_meta(&app_handle, &meta)?;
+    git::commit_quietly(&app_handle, &project_id, &message);
+    preview::notify_reload(&app_handle, &project_id, meta.updated_at);
+    Ok(meta)
+}
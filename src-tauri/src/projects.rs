@@ -0,0 +1,253 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{git, now_millis, preview, shutdown, titles, versions};
+
+pub const META_FILE: &str = "project.json";
+pub const HTML_FILE: &str = "index.html";
+pub const THUMBNAIL_FILE: &str = "thumbnail.png";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMeta {
+    pub id: String,
+    pub name: String,
+    // Written by titles.rs for new projects; shown next to the name in the gallery
+    #[serde(default)]
+    pub emoji: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub prompt: String,
+    pub model: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    // Paths of every source file for multi-file projects; empty for single-file ones
+    #[serde(default)]
+    pub files: Vec<String>,
+    // The project this one was remixed from
+    #[serde(default)]
+    pub remixed_from: Option<String>,
+    // The two projects merge_projects combined into this one
+    #[serde(default)]
+    pub merged_from: Vec<String>,
+    // Lowercase, trimmed and unique; see normalize_tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectFilter {
+    // Projects carrying every one of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub favorite: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    #[serde(flatten)]
+    pub meta: ProjectMeta,
+    pub html: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GalleryItem {
+    #[serde(flatten)]
+    pub meta: ProjectMeta,
+    // Absolute path for convertFileSrc; None until a preview has been captured
+    pub thumbnail_path: Option<String>,
+}
+
+pub fn projects_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?
+        .join("projects");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create projects dir: {}", e))?;
+    Ok(dir)
+}
+
+// Ids end up in paths, so only accept what we generate ourselves
+pub fn project_dir(app_handle: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid project id: {}", id));
+    }
+    Ok(projects_dir(app_handle)?.join(id))
+}
+
+pub fn read_meta(app_handle: &AppHandle, id: &str) -> Result<ProjectMeta, String> {
+    let path = project_dir(app_handle, id)?.join(META_FILE);
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|_| format!("Project {} not found", id))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Corrupt project {}: {}", id, e))
+}
+
+pub fn write_meta(app_handle: &AppHandle, meta: &ProjectMeta) -> Result<(), String> {
+    let dir = project_dir(app_handle, &meta.id)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create project dir: {}", e))?;
+    let json = serde_json::to_string_pretty(meta)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    std::fs::write(dir.join(META_FILE), json).map_err(|e| format!("Failed to write project: {}", e))
+}
+
+pub fn read_project(app_handle: &AppHandle, id: &str) -> Result<Project, String> {
+    let meta = read_meta(app_handle, id)?;
+    let html = std::fs::read_to_string(html_path(app_handle, id)?)
+        .map_err(|e| format!("Failed to read project HTML: {}", e))?;
+    Ok(Project { meta, html })
+}
+
+pub fn write_project(
+    app_handle: &AppHandle,
+    id: Option<String>,
+    name: Option<String>,
+    prompt: String,
+    html: String,
+    model: String,
+) -> Result<Project, String> {
+    let _saving = shutdown::saving();
+    let now = now_millis();
+
+    // Saving over an existing id keeps its creation time
+    let existing = id.as_deref().and_then(|id| read_meta(app_handle, id).ok());
+    // New projects nobody named get a quick title now and a model-written one shortly after
+    let untitled = existing.is_none() && name.is_none();
+    let guess = titles::heuristic(&prompt, &html);
+    let meta = ProjectMeta {
+        id: id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name: name
+            .or_else(|| existing.as_ref().map(|m| m.name.clone()))
+            .unwrap_or_else(|| guess.title.clone()),
+        emoji: existing.as_ref().map_or(Some(guess.emoji), |m| m.emoji.clone()),
+        description: existing.as_ref().map_or(Some(guess.description), |m| m.description.clone()),
+        prompt,
+        model,
+        created_at: existing.as_ref().map(|m| m.created_at).unwrap_or(now),
+        updated_at: now,
+        files: existing.as_ref().map(|m| m.files.clone()).unwrap_or_default(),
+        remixed_from: existing.as_ref().and_then(|m| m.remixed_from.clone()),
+        merged_from: existing.as_ref().map(|m| m.merged_from.clone()).unwrap_or_default(),
+        tags: existing.as_ref().map(|m| m.tags.clone()).unwrap_or_default(),
+        favorite: existing.as_ref().is_some_and(|m| m.favorite),
+    };
+
+    write_meta(app_handle, &meta)?;
+    std::fs::write(html_path(app_handle, &meta.id)?, &html)
+        .map_err(|e| format!("Failed to write project HTML: {}", e))?;
+    versions::record_quietly(app_handle, &meta.id, &html, "save", None);
+    git::commit_quietly(app_handle, &meta.id, &meta.prompt);
+
+    if existing.is_some() {
+        preview::notify_reload(app_handle, &meta.id, meta.updated_at);
+    }
+    if untitled {
+        titles::refine_quietly(app_handle, &meta);
+    }
+
+    Ok(Project { meta, html })
+}
+
+pub fn list_metas(app_handle: &AppHandle) -> Result<Vec<ProjectMeta>, String> {
+    let entries = std::fs::read_dir(projects_dir(app_handle)?)
+        .map_err(|e| format!("Failed to read projects dir: {}", e))?;
+
+    let mut projects: Vec<ProjectMeta> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let contents = std::fs::read_to_string(entry.path().join(META_FILE)).ok()?;
+            serde_json::from_str(&contents).ok()
+        })
+        .collect();
+
+    projects.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(projects)
+}
+
+// "Games", " games " and "games" are the same tag
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+fn matches_filter(meta: &ProjectMeta, filter: &ProjectFilter) -> bool {
+    (filter.favorite.is_none() || filter.favorite == Some(meta.favorite))
+        && normalize_tags(filter.tags.clone()).iter().all(|tag| meta.tags.contains(tag))
+}
+
+pub fn html_path(app_handle: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    Ok(project_dir(app_handle, id)?.join(HTML_FILE))
+}
+
+pub fn thumbnail_path(app_handle: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    Ok(project_dir(app_handle, id)?.join(THUMBNAIL_FILE))
+}
+
+#[tauri::command]
+pub async fn save_project(
+    id: Option<String>,
+    name: Option<String>,
+    prompt: String,
+    html: String,
+    model: String,
+    app_handle: AppHandle,
+) -> Result<Project, String> {
+    write_project(&app_handle, id, name, prompt, html, model)
+}
+
+#[tauri::command]
+pub async fn load_project(id: String, app_handle: AppHandle) -> Result<Project, String> {
+    read_project(&app_handle, &id)
+}
+
+#[tauri::command]
+pub async fn list_projects(filter: Option<ProjectFilter>, app_handle: AppHandle) -> Result<Vec<ProjectMeta>, String> {
+    let filter = filter.unwrap_or_default();
+    Ok(list_metas(&app_handle)?.into_iter().filter(|meta| matches_filter(meta, &filter)).collect())
+}
+
+// Replaces the project's tags; an empty list clears them
+#[tauri::command]
+pub async fn tag_project(id: String, tags: Vec<String>, app_handle: AppHandle) -> Result<ProjectMeta, String> {
+    let mut meta = read_meta(&app_handle, &id)?;
+    meta.tags = normalize_tags(tags);
+    write_meta(&app_handle, &meta)?;
+    Ok(meta)
+}
+
+#[tauri::command]
+pub async fn set_favorite(id: String, favorite: bool, app_handle: AppHandle) -> Result<ProjectMeta, String> {
+    let mut meta = read_meta(&app_handle, &id)?;
+    meta.favorite = favorite;
+    write_meta(&app_handle, &meta)?;
+    Ok(meta)
+}
+
+#[tauri::command]
+pub async fn delete_project(id: String, app_handle: AppHandle) -> Result<String, String> {
+    let dir = project_dir(&app_handle, &id)?;
+    std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete project {}: {}", id, e))?;
+    Ok(format!("Project {} deleted", id))
+}
+
+#[tauri::command]
+pub async fn get_gallery(app_handle: AppHandle) -> Result<Vec<GalleryItem>, String> {
+    let items = list_metas(&app_handle)?
+        .into_iter()
+        .map(|meta| {
+            let thumbnail_path = thumbnail_path(&app_handle, &meta.id)
+                .ok()
+                .filter(|path| path.exists())
+                .map(|path| path.to_string_lossy().to_string());
+            GalleryItem { meta, thumbnail_path }
+        })
+        .collect();
+
+    Ok(items)
+}
@@ -1,5 +1,6 @@
-pub const VIBE_CODING_SYSTEM_PROMPT: &str = r#"<|im_start|>system
-You are Vibe Cherry, an expert at creating beautiful, functional web applications in a single response.
+use serde::{Deserialize, Serialize};
+
+pub const VIBE_CODING_SYSTEM_PROMPT: &str = r#"You are Vibe Cherry, an expert at creating beautiful, functional web applications in a single response.
 
 CORE RULES:
 1. Always output complete, self-contained HTML that includes CSS and JavaScript
@@ -41,5 +42,413 @@ EXAMPLES OF GOOD VIBES:
 ✓ Input fields with focus glow effects
 ✓ Satisfying click feedback
 
-When the user asks for an app, think about the core functionality and create something they can immediately use and enjoy.
-<|im_end|>"#;
\ No newline at end of file
+When the user asks for an app, think about the core functionality and create something they can immediately use and enjoy."#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VibeTheme {
+    Dark,
+    Light,
+    Glassmorphism,
+    NeoBrutalism,
+    Minimal,
+}
+
+impl VibeTheme {
+    pub fn guidelines(&self) -> &'static str {
+        match self {
+            VibeTheme::Dark => "Dark mode: near-black backgrounds (slate-900/950), light text, vivid accent \
+                gradients used sparingly, soft glows on focus and hover.",
+            VibeTheme::Light => "Light mode: white or very light gray backgrounds, dark slate text, one saturated \
+                accent color, gentle shadows instead of glows. Do NOT default to dark mode.",
+            VibeTheme::Glassmorphism => "Glassmorphism: colorful blurred gradient backdrop, translucent cards \
+                (bg-white/10, backdrop-blur-lg), thin light borders (border-white/20), rounded-2xl corners.",
+            VibeTheme::NeoBrutalism => "Neo-brutalism: flat bright colors, thick black borders (border-4), hard \
+                offset shadows (shadow-[6px_6px_0_0_#000]), chunky bold type, no gradients or blur.",
+            VibeTheme::Minimal => "Minimal: monochrome palette with a single accent, generous whitespace, no \
+                gradients, hairline borders, small radii and only subtle opacity/transform transitions.",
+        }
+    }
+}
+
+// Languages written right to left; pages in them need dir="rtl"
+const RTL_LANGUAGES: &[&str] = &["ar", "he", "fa", "ur", "ps", "yi", "dv", "ckb"];
+
+// English names for the common tags; models follow "German" more reliably than "de"
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("bn", "Bengali"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fa", "Persian"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("ur", "Urdu"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+// "pt_br" -> "pt-BR"; None for anything that isn't shaped like a BCP 47 tag
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let parts: Vec<&str> = locale.trim().split(['-', '_']).collect();
+    let language = parts.first()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    if parts[1..].iter().any(|part| part.is_empty() || part.len() > 8 || !part.chars().all(|c| c.is_ascii_alphanumeric())) {
+        return None;
+    }
+    let mut normalized = vec![language.to_ascii_lowercase()];
+    for part in &parts[1..] {
+        normalized.push(match part.len() {
+            2 => part.to_ascii_uppercase(),
+            4 => format!("{}{}", part[..1].to_ascii_uppercase(), part[1..].to_ascii_lowercase()),
+            _ => part.to_ascii_lowercase(),
+        });
+    }
+    Some(normalized.join("-"))
+}
+
+fn language_instructions(locale: &str) -> String {
+    let language = locale.split('-').next().unwrap_or(locale);
+    let name = LANGUAGE_NAMES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, name)| format!("{} ({})", name, locale))
+        .unwrap_or_else(|| locale.to_string());
+    let direction = if RTL_LANGUAGES.contains(&language) {
+        " dir=\"rtl\" and mirror the layout (use start/end rather than left/right)"
+    } else {
+        ""
+    };
+    format!(
+        "Write ALL user-facing text in {name}: headings, labels, buttons, placeholders, messages, \
+         sample data and the <title>. Use <html lang=\"{locale}\">{direction}. Format dates, numbers and \
+         currencies the way {locale} readers expect. Keep code, identifiers and comments in English."
+    )
+}
+
+// The base (or user-customised) system prompt with theme guidance and extra style notes appended
+pub fn system_prompt(
+    custom_prompt: Option<&str>,
+    vibe_theme: Option<VibeTheme>,
+    theme_prompt: Option<&str>,
+    output_language: Option<&str>,
+) -> String {
+    let mut prompt = custom_prompt
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or(VIBE_CODING_SYSTEM_PROMPT)
+        .to_string();
+
+    if let Some(theme) = vibe_theme {
+        prompt.push_str("\n\nTHEME (overrides any default color guidance above):\n");
+        prompt.push_str(theme.guidelines());
+    }
+    if let Some(extra) = theme_prompt.map(str::trim).filter(|t| !t.is_empty()) {
+        prompt.push_str("\n\nSTYLE PREFERENCES:\n");
+        prompt.push_str(extra);
+    }
+    if let Some(locale) = output_language.and_then(normalize_locale) {
+        prompt.push_str("\n\nLANGUAGE:\n");
+        prompt.push_str(&language_instructions(&locale));
+    }
+
+    prompt
+}
+
+// Rough token count for budgeting, no tokenizer needed: ~4 ASCII characters per token, but
+// CJK and other non-Latin scripts often take a token (or more) per character
+pub fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.chars().filter(char::is_ascii).count();
+    let other = text.chars().count() - ascii;
+    ascii.div_ceil(4) + other
+}
+
+// Chat templates by model family. ChatMl doubles as the message format chat APIs are
+// converted from; the others are only for prompts sent raw, where the markers must match
+// what the model was trained on (Gemma has no system role, Llama 3 uses headers, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptTemplate {
+    ChatMl,
+    Gemma,
+    Llama3,
+    Mistral,
+    Phi3,
+    // Ollama applies the model's own template and baked-in SYSTEM (see modelfile.rs);
+    // the prompt is just the user turn, prefixed with any per-request context
+    Native,
+}
+
+// Control tokens of every template above. Text typed by the user (or pasted code)
+// containing them could close the user turn early and inject its own system/assistant turns.
+const SPECIAL_TOKENS: &[&str] = &[
+    "<|im_start|>",
+    "<|im_end|>",
+    "<|endoftext|>",
+    "<start_of_turn>",
+    "<end_of_turn>",
+    "<|begin_of_text|>",
+    "<|start_header_id|>",
+    "<|end_header_id|>",
+    "<|eot_id|>",
+    "[INST]",
+    "[/INST]",
+    "<|system|>",
+    "<|user|>",
+    "<|assistant|>",
+    "<|end|>",
+];
+
+// Pasted text brings along \r\n, byte order marks and stray control characters that some
+// tokenizers choke on; everything else (any script, emoji, RTL text) passes through untouched
+fn clean_text(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .chars()
+        .filter(|c| *c == '\n' || *c == '\t' || !(c.is_control() || *c == '\u{feff}'))
+        .collect()
+}
+
+// Defuse control tokens with a space after their first character ("< |im_end|>"),
+// which no tokenizer maps back to the special token but the model can still read
+pub fn escape_special_tokens(text: &str) -> String {
+    let mut escaped = clean_text(text);
+    for token in SPECIAL_TOKENS {
+        if escaped.contains(token) {
+            escaped = escaped.replace(token, &format!("{} {}", &token[..1], &token[1..]));
+        }
+    }
+    escaped
+}
+
+impl PromptTemplate {
+    // Accepts an Ollama family ("gemma3", "llama", "qwen2") or a bare model name
+    pub fn for_family(family: &str) -> Self {
+        let family = family.to_lowercase();
+        if family.contains("gemma") {
+            PromptTemplate::Gemma
+        } else if family.contains("llama3") || family.contains("llama-3") || family == "llama" {
+            PromptTemplate::Llama3
+        } else if family.contains("mistral") || family.contains("mixtral") {
+            PromptTemplate::Mistral
+        } else if family.contains("phi3") || family.contains("phi-3") || family.contains("phi4") {
+            PromptTemplate::Phi3
+        } else {
+            PromptTemplate::ChatMl
+        }
+    }
+
+    pub fn assemble(&self, system: &str, user: &str) -> String {
+        let system = escape_special_tokens(system);
+        let user = escape_special_tokens(user);
+        match self {
+            PromptTemplate::ChatMl => format!(
+                "<|im_start|>system\n{system}\n<|im_end|>\n<|im_start|>user\n{user}\n<|im_end|>\n<|im_start|>assistant\n"
+            ),
+            PromptTemplate::Gemma => format!(
+                "<start_of_turn>user\n{system}\n\n{user}<end_of_turn>\n<start_of_turn>model\n"
+            ),
+            PromptTemplate::Llama3 => format!(
+                "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\n{system}<|eot_id|><|start_header_id|>user<|end_header_id|>\n\n{user}<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n"
+            ),
+            PromptTemplate::Mistral => format!("[INST] {system}\n\n{user} [/INST]"),
+            PromptTemplate::Phi3 => format!(
+                "<|system|>\n{system}<|end|>\n<|user|>\n{user}<|end|>\n<|assistant|>\n"
+            ),
+            PromptTemplate::Native if system.trim().is_empty() => user,
+            PromptTemplate::Native => format!("{}\n\n{user}", system.trim()),
+        }
+    }
+
+    // Inverse of assemble, for re-templating a prompt built for another model.
+    // Gemma folds the system prompt into the user turn, so it comes back as one user turn.
+    pub fn turns(&self, prompt: &str) -> Option<(String, String)> {
+        let split = |body: &str, separator: &str| {
+            body.split_once(separator).map(|(system, user)| (system.to_string(), user.to_string()))
+        };
+        match self {
+            PromptTemplate::ChatMl => {
+                let body = prompt.strip_prefix("<|im_start|>system\n")?
+                    .strip_suffix("\n<|im_end|>\n<|im_start|>assistant\n")?;
+                split(body, "\n<|im_end|>\n<|im_start|>user\n")
+            }
+            PromptTemplate::Gemma => {
+                let body = prompt.strip_prefix("<start_of_turn>user\n")?
+                    .strip_suffix("<end_of_turn>\n<start_of_turn>model\n")?;
+                Some((String::new(), body.to_string()))
+            }
+            PromptTemplate::Llama3 => {
+                let body = prompt.strip_prefix("<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\n")?
+                    .strip_suffix("<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n")?;
+                split(body, "<|eot_id|><|start_header_id|>user<|end_header_id|>\n\n")
+            }
+            PromptTemplate::Mistral => {
+                let body = prompt.strip_prefix("[INST] ")?.strip_suffix(" [/INST]")?;
+                Some((String::new(), body.to_string()))
+            }
+            PromptTemplate::Phi3 => {
+                let body = prompt.strip_prefix("<|system|>\n")?.strip_suffix("<|end|>\n<|assistant|>\n")?;
+                split(body, "<|end|>\n<|user|>\n")
+            }
+            PromptTemplate::Native => Some((String::new(), prompt.to_string())),
+        }
+    }
+
+    // The same conversation laid out for `target`; prompts in an unknown shape pass through unchanged
+    pub fn convert(&self, prompt: &str, target: PromptTemplate) -> String {
+        if *self == target {
+            return prompt.to_string();
+        }
+        match self.turns(prompt) {
+            Some((system, user)) => target.assemble(&system, &user),
+            None => prompt.to_string(),
+        }
+    }
+}
+
+pub const MULTI_FILE_INSTRUCTIONS: &str = r#"Build this as a small multi-file project instead of a single HTML document.
+
+Output each file in its own code block, with the file name after the language on the opening fence:
+
+```html index.html
+(markup only, linking the other files)
+```
+
+```css styles.css
+(all styles)
+```
+
+```js app.js
+(all scripts)
+```
+
+Rules:
+- index.html must load the others with <link rel="stylesheet" href="styles.css"> and <script src="app.js" defer></script>
+- Split larger apps into more files (e.g. js/storage.js) and reference them with relative paths
+- Use only relative paths made of letters, numbers, dashes, dots and slashes
+- Output every file completely, never "rest unchanged""#;
+
+pub const IMAGE_INSTRUCTIONS: &str = r#"The attached image is a sketch, wireframe or screenshot of the app to build.
+
+- Reproduce its layout, sections and controls faithfully, in the same positions
+- Read any handwritten or on-screen labels and use them as the real text
+- Turn boxes, arrows and scribbles into proper, polished UI elements
+- Make every control actually work"#;
+
+pub const EDIT_INSTRUCTIONS: &str = r#"You are editing an EXISTING app. Make only the change the user asks for and leave everything else exactly as it is.
+
+Respond with one or more edit blocks in this exact format and nothing else:
+
+<<<<<<< SEARCH
+(exact lines copied from the current code)
+=======
+(the replacement lines)
+>>>>>>> REPLACE
+
+Rules:
+- The SEARCH section must match the current code character for character, including indentation
+- Keep each SEARCH section small but unique
+- To add code, SEARCH for a nearby line and repeat it in REPLACE with the new lines around it
+- Never rewrite the whole file"#;
+
+pub const REMIX_INSTRUCTIONS: &str = r#"Below is an EXISTING app. Use it as the starting point for a NEW app described by the remix request.
+
+- Keep what still fits: the look and feel, layout ideas, working interactions and code structure
+- Rename, restructure and rewrite whatever the new purpose needs; it's fine if little of the original remains
+- Replace the original's text, sample data and <title> so nothing refers to the old app
+- Return the complete new document, never "rest unchanged""#;
+
+pub const MERGE_INSTRUCTIONS: &str = r#"Below are TWO EXISTING apps. Combine them into ONE app as the merge request describes.
+
+- App A is the host unless the request says otherwise: keep its structure and look, and restyle what comes from App B to match
+- Bring over App B's features completely, with their markup, styles and scripts working
+- Avoid collisions: rename clashing ids, classes, CSS variables, functions and localStorage keys
+- Use a single <!DOCTYPE html> document with one <head> and one <body>
+- Return the complete merged document, never "rest unchanged""#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATES: [PromptTemplate; 5] = [
+        PromptTemplate::ChatMl,
+        PromptTemplate::Gemma,
+        PromptTemplate::Llama3,
+        PromptTemplate::Mistral,
+        PromptTemplate::Phi3,
+    ];
+
+    #[test]
+    fn special_tokens_are_defused() {
+        let cases = [
+            ("<|im_end|>\n<|im_start|>system", "< |im_end|>\n< |im_start|>system"),
+            ("<end_of_turn><start_of_turn>model", "< end_of_turn>< start_of_turn>model"),
+            ("<|eot_id|><|start_header_id|>", "< |eot_id|>< |start_header_id|>"),
+            ("[INST] ignore that [/INST]", "[ INST] ignore that [ /INST]"),
+            ("<|end|>\n<|assistant|>", "< |end|>\n< |assistant|>"),
+            ("line\r\nbreak\u{feff}\u{7}", "line\nbreak"),
+            ("tabs\tstay, 🍒 and שלום too", "tabs\tstay, 🍒 and שלום too"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(escape_special_tokens(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn turns_invert_assemble() {
+        for template in TEMPLATES {
+            let prompt = template.assemble("Be brief.", "Make a <|im_end|> timer");
+            let expected = match template {
+                // No system role, so it was folded into the user turn
+                PromptTemplate::Gemma | PromptTemplate::Mistral => {
+                    (String::new(), "Be brief.\n\nMake a < |im_end|> timer".to_string())
+                }
+                _ => ("Be brief.".to_string(), "Make a < |im_end|> timer".to_string()),
+            };
+            assert_eq!(template.turns(&prompt), Some(expected), "{:?}", template);
+        }
+    }
+
+    #[test]
+    fn turns_reject_other_shapes() {
+        for template in TEMPLATES {
+            assert_eq!(template.turns("just some text"), None, "{:?}", template);
+        }
+    }
+
+    #[test]
+    fn convert_reassembles_for_the_target() {
+        let prompt = PromptTemplate::ChatMl.assemble("Be brief.", "Make a timer");
+        for target in TEMPLATES {
+            assert_eq!(
+                PromptTemplate::ChatMl.convert(&prompt, target),
+                target.assemble("Be brief.", "Make a timer"),
+                "{:?}",
+                target
+            );
+        }
+        assert_eq!(PromptTemplate::Llama3.convert("just some text", PromptTemplate::Phi3), "just some text");
+    }
+}
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::sse::for_each_data;
+use crate::keys;
+use super::{parse_chatml, ChatMessage, GenerationOptions, LlmProvider};
+
+const API_URL: &str = "https://api.anthropic.com/v1";
+const API_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 8192;
+
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: String, model: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            model,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", API_URL, path))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+    }
+}
+
+// The Messages API takes the system prompt as a separate field and requires
+// strictly alternating user/assistant turns starting with the user.
+fn to_messages_api(messages: Vec<ChatMessage>) -> (String, Vec<ChatMessage>) {
+    let mut system = Vec::new();
+    let mut turns: Vec<ChatMessage> = Vec::new();
+
+    for message in messages {
+        if message.role == "system" {
+            system.push(message.content);
+            continue;
+        }
+
+        let role = if message.role == "assistant" { "assistant" } else { "user" };
+        match turns.last_mut() {
+            Some(last) if last.role == role => {
+                last.content.push_str("\n\n");
+                last.content.push_str(&message.content);
+            }
+            _ => turns.push(ChatMessage {
+                role: role.to_string(),
+                content: message.content,
+            }),
+        }
+    }
+
+    if turns.first().map(|m| m.role != "user").unwrap_or(true) {
+        turns.insert(0, ChatMessage {
+            role: "user".to_string(),
+            content: "Continue.".to_string(),
+        });
+    }
+
+    (system.join("\n\n"), turns)
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        on_token: &(dyn Fn(String) + Send + Sync),
+    ) -> Result<String, String> {
+        let (system, messages) = to_messages_api(parse_chatml(prompt));
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": options.max_tokens.unwrap_or(MAX_TOKENS).min(MAX_TOKENS),
+            "system": system,
+            "messages": messages,
+            "stream": true,
+        });
+        // top_p is left out: recent Claude models reject it alongside temperature
+        if let Some(temperature) = options.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if !options.stop.is_empty() {
+            body["stop_sequences"] = json!(options.stop);
+        }
+
+        let response = self.request(reqwest::Method::POST, "/messages")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Anthropic: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Anthropic API error ({}): {}", status, keys::redact(&text)));
+        }
+
+        let mut result = String::new();
+        let mut stream_error = None;
+        for_each_data(response, |data| {
+            let Ok(event) = serde_json::from_str::<Value>(data) else { return true };
+            match event["type"].as_str() {
+                Some("content_block_delta") => {
+                    if let Some(token) = event["delta"]["text"].as_str() {
+                        result.push_str(token);
+                        on_token(token.to_string());
+                    }
+                    true
+                }
+                Some("error") => {
+                    stream_error = Some(event["error"]["message"].as_str().unwrap_or("unknown error").to_string());
+                    false
+                }
+                Some("message_stop") => false,
+                _ => true,
+            }
+        })
+        .await?;
+
+        match stream_error {
+            Some(e) => Err(format!("Anthropic stream error: {}", e)),
+            None => Ok(result),
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, String> {
+        let response = self.request(reqwest::Method::GET, "/models")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Anthropic: {}", e))?;
+
+        let body: Value = response.json().await
+            .map_err(|e| format!("Invalid models response: {}", e))?;
+
+        let models = body["data"]
+            .as_array()
+            .map(|items| {
+                items.iter()
+                    .filter_map(|item| item["id"].as_str().map(|id| id.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(models)
+    }
+}
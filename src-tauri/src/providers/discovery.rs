@@ -0,0 +1,42 @@
+use std::time::Duration;
+use serde_json::Value;
+
+// LM Studio defaults to 1234, llama.cpp server / LocalAI commonly use 8080
+const LOCAL_SERVER_PORTS: &[u16] = &[1234, 8080];
+
+pub struct LocalServer {
+    pub base_url: String,
+    pub models: Vec<String>,
+}
+
+// Probe the common ports for something answering /v1/models with at least one model
+pub async fn discover_local_server() -> Option<LocalServer> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(800))
+        .build()
+        .ok()?;
+
+    for port in LOCAL_SERVER_PORTS {
+        let base_url = format!("http://localhost:{}/v1", port);
+        let Ok(response) = client.get(format!("{}/models", base_url)).send().await else { continue };
+        if !response.status().is_success() {
+            continue;
+        }
+        let Ok(body) = response.json::<Value>().await else { continue };
+
+        let models: Vec<String> = body["data"]
+            .as_array()
+            .map(|items| {
+                items.iter()
+                    .filter_map(|item| item["id"].as_str().map(|id| id.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if !models.is_empty() {
+            return Some(LocalServer { base_url, models });
+        }
+    }
+
+    None
+}
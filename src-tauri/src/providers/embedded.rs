@@ -0,0 +1,216 @@
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::{AddBos, LlamaModel, Special};
+use llama_cpp_2::sampling::LlamaSampler;
+use tokio::sync::mpsc;
+
+use super::{clip_at_stop, GenerationOptions, LlmProvider};
+use crate::prompts::PromptTemplate;
+
+const CONTEXT_SIZE: u32 = 8192;
+const MAX_NEW_TOKENS: i32 = 4096;
+
+// llama.cpp may only be initialized once per process
+static BACKEND: OnceLock<Result<LlamaBackend, String>> = OnceLock::new();
+
+fn backend() -> Result<&'static LlamaBackend, String> {
+    BACKEND
+        .get_or_init(|| LlamaBackend::init().map_err(|e| format!("Failed to init llama.cpp: {}", e)))
+        .as_ref()
+        .map_err(|e| e.clone())
+}
+
+// First *.gguf file in the given directory, used when nothing else is available
+pub fn find_gguf(dir: &Path) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map(|ext| ext == "gguf").unwrap_or(false))
+        .collect();
+    files.sort();
+    files.into_iter().next()
+}
+
+pub struct EmbeddedProvider {
+    model_path: PathBuf,
+    model_name: String,
+    loaded: Arc<Mutex<Option<Arc<LlamaModel>>>>,
+}
+
+impl EmbeddedProvider {
+    pub fn new(model_path: PathBuf) -> Self {
+        let model_name = model_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "embedded".to_string());
+
+        Self {
+            model_path,
+            model_name,
+            loaded: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Reading a multi-GB GGUF takes seconds, so it happens on a blocking thread like inference
+    async fn load(&self) -> Result<Arc<LlamaModel>, String> {
+        let loaded = self.loaded.clone();
+        let model_path = self.model_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut loaded = loaded.lock().unwrap();
+            if let Some(model) = loaded.as_ref() {
+                return Ok(model.clone());
+            }
+
+            let model = LlamaModel::load_from_file(backend()?, &model_path, &LlamaModelParams::default())
+                .map_err(|e| format!("Failed to load {}: {}", model_path.display(), e))?;
+            let model = Arc::new(model);
+            *loaded = Some(model.clone());
+            Ok(model)
+        })
+        .await
+        .map_err(|e| format!("Model loading thread panicked: {}", e))?
+    }
+}
+
+// Runs on a blocking thread; pieces of text are sent back as soon as they decode to valid UTF-8
+fn run_inference(
+    model: &LlamaModel,
+    prompt: &str,
+    temperature: f32,
+    seed: u32,
+    max_tokens: i32,
+    tx: mpsc::UnboundedSender<String>,
+) -> Result<(), String> {
+    let backend = backend()?;
+    let ctx_params = LlamaContextParams::default().with_n_ctx(NonZeroU32::new(CONTEXT_SIZE));
+    let mut ctx = model.new_context(backend, ctx_params)
+        .map_err(|e| format!("Failed to create context: {}", e))?;
+
+    let tokens = model.str_to_token(prompt, AddBos::Always)
+        .map_err(|e| format!("Failed to tokenize prompt: {}", e))?;
+
+    let mut batch = LlamaBatch::new(CONTEXT_SIZE as usize, 1);
+    let last_index = tokens.len() as i32 - 1;
+    for (i, token) in (0_i32..).zip(tokens.into_iter()) {
+        batch.add(token, i, &[0], i == last_index)
+            .map_err(|e| format!("Failed to build batch: {}", e))?;
+    }
+    ctx.decode(&mut batch).map_err(|e| format!("Decode failed: {}", e))?;
+
+    let mut sampler = LlamaSampler::chain_simple([
+        LlamaSampler::temp(temperature),
+        LlamaSampler::dist(seed),
+    ]);
+    let mut position = batch.n_tokens();
+    let limit = position + max_tokens;
+    let mut pending: Vec<u8> = Vec::new();
+
+    while position < limit {
+        let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+        sampler.accept(token);
+        if model.is_eog_token(token) {
+            break;
+        }
+
+        let bytes = model.token_to_bytes(token, Special::Tokenize)
+            .map_err(|e| format!("Failed to detokenize: {}", e))?;
+        pending.extend_from_slice(&bytes);
+
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(text) => text.len(),
+            Err(e) => e.valid_up_to(),
+        };
+        if valid > 0 {
+            let piece = String::from_utf8_lossy(&pending[..valid]).to_string();
+            pending.drain(..valid);
+            if tx.send(piece).is_err() {
+                break;
+            }
+        }
+
+        batch.clear();
+        batch.add(token, position, &[0], true)
+            .map_err(|e| format!("Failed to build batch: {}", e))?;
+        position += 1;
+        ctx.decode(&mut batch).map_err(|e| format!("Decode failed: {}", e))?;
+    }
+
+    Ok(())
+}
+
+fn rand_seed() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0)
+}
+
+#[async_trait]
+impl LlmProvider for EmbeddedProvider {
+    fn name(&self) -> &str {
+        "embedded"
+    }
+
+    fn model(&self) -> &str {
+        &self.model_name
+    }
+
+    async fn template(&self) -> PromptTemplate {
+        PromptTemplate::for_family(&self.model_name)
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        on_token: &(dyn Fn(String) + Send + Sync),
+    ) -> Result<String, String> {
+        let model = self.load().await?;
+        let prompt = prompt.to_string();
+        let temperature = options.temperature.unwrap_or(0.7);
+        let seed = options.seed.unwrap_or_else(rand_seed);
+        let max_tokens = options.max_tokens.map_or(MAX_NEW_TOKENS, |n| (n as i32).min(MAX_NEW_TOKENS));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let worker = tokio::task::spawn_blocking(move || run_inference(&model, &prompt, temperature, seed, max_tokens, tx));
+
+        let mut result = String::new();
+        while let Some(piece) = rx.recv().await {
+            let emitted = result.len();
+            result.push_str(&piece);
+            // Dropping the receiver makes the worker stop at its next token
+            if let Some(clipped) = clip_at_stop(&result, &options.stop) {
+                let end = clipped.len();
+                if end > emitted {
+                    on_token(result[emitted..end].to_string());
+                }
+                result.truncate(end);
+                break;
+            }
+            on_token(piece);
+        }
+        drop(rx);
+
+        worker.await.map_err(|e| format!("Inference thread panicked: {}", e))??;
+        Ok(result)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, String> {
+        Ok(vec![self.model_name.clone()])
+    }
+
+    // The weights are freed once any in-flight generation lets go of its handle; the next one reloads them
+    async fn unload(&self) -> Result<Vec<String>, String> {
+        match self.loaded.lock().unwrap().take() {
+            Some(_) => Ok(vec![self.model_name.clone()]),
+            None => Ok(Vec::new()),
+        }
+    }
+}
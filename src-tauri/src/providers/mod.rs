@@ -0,0 +1,244 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::keys::{self, Secret};
+use crate::prompts::PromptTemplate;
+
+mod anthropic;
+mod discovery;
+#[cfg(feature = "embedded")]
+mod embedded;
+mod ollama;
+mod openai;
+mod sse;
+mod test;
+
+pub use anthropic::AnthropicProvider;
+pub use discovery::{discover_local_server, LocalServer};
+#[cfg(feature = "embedded")]
+pub use embedded::{find_gguf, EmbeddedProvider};
+pub use ollama::{delete_model, embed, installed_models, loaded_models, pull, InstalledModel, OllamaProvider, OLLAMA_URL};
+pub use openai::OpenAiProvider;
+pub use test::TestProvider;
+
+pub const DEFAULT_OLLAMA_MODEL: &str = "gemma3:4b";
+pub const DEFAULT_ANTHROPIC_MODEL: &str = "claude-sonnet-4-5";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationOptions {
+    pub temperature: Option<f32>,
+    // Nucleus sampling cutoff; skipped when unset so existing cache keys stay valid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    // Fixed sampling seed; None lets the backend pick one
+    pub seed: Option<u32>,
+    // Generation ends as soon as any of these is produced (not included in the output).
+    // Both skipped when unset so existing cache keys stay valid
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    // Cap on generated tokens (Ollama's num_predict)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    // How long Ollama keeps the model resident afterwards ("30m", "-1" forever, "0" unload).
+    // It doesn't change the output, so it stays out of cache keys
+    #[serde(default, skip_serializing)]
+    pub keep_alive: Option<String>,
+    // Base64-encoded images for vision models; skipped when empty so cache keys stay stable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+}
+
+// Every backend implements this so the commands never care where tokens come from
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    // Cloud/OpenAI-style providers get ChatML and convert it to messages themselves
+    async fn template(&self) -> PromptTemplate {
+        PromptTemplate::ChatMl
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        on_token: &(dyn Fn(String) + Send + Sync),
+    ) -> Result<String, String>;
+
+    async fn generate(&self, prompt: &str, options: &GenerationOptions) -> Result<String, String> {
+        self.stream(prompt, options, &|_| {}).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, String>;
+
+    // Load the model ahead of the first real request; a no-op for remote APIs
+    async fn warm_up(&self, _options: &GenerationOptions) -> Result<(), String> {
+        Ok(())
+    }
+
+    // Free the memory held by loaded models and return their names; remote APIs hold none
+    async fn unload(&self) -> Result<Vec<String>, String> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProviderConfig {
+    Ollama {
+        model: Option<String>,
+    },
+    // Works with OpenAI, Groq, Together, vLLM and anything else speaking /v1/chat/completions
+    OpenAi {
+        base_url: String,
+        api_key: Option<Secret>,
+        model: String,
+    },
+    // The key falls back to the one stored with set_api_key("anthropic", ...)
+    Anthropic {
+        api_key: Option<Secret>,
+        model: Option<String>,
+    },
+    // LM Studio, llama.cpp server and friends running on this machine
+    LocalServer {
+        base_url: String,
+        model: String,
+    },
+    // A GGUF loaded in-process through llama.cpp (requires the `embedded` feature)
+    #[cfg(feature = "embedded")]
+    Embedded {
+        model_path: String,
+    },
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        ProviderConfig::Ollama { model: None }
+    }
+}
+
+impl ProviderConfig {
+    // Point the config at a different model, used when settings override the default
+    pub fn set_model(&mut self, name: &str) {
+        match self {
+            ProviderConfig::Ollama { model } | ProviderConfig::Anthropic { model, .. } => {
+                *model = Some(name.to_string())
+            }
+            ProviderConfig::OpenAi { model, .. } | ProviderConfig::LocalServer { model, .. } => {
+                *model = name.to_string()
+            }
+            #[cfg(feature = "embedded")]
+            ProviderConfig::Embedded { .. } => {}
+        }
+    }
+
+    // Keychain account holding this backend's key. Each OpenAI-compatible endpoint gets its
+    // own so configuring Groq doesn't overwrite the OpenAI key
+    pub fn key_account(&self) -> Option<String> {
+        match self {
+            ProviderConfig::OpenAi { base_url, .. } => Some(format!("openai:{}", base_url.trim().trim_end_matches('/'))),
+            ProviderConfig::Anthropic { .. } => Some("anthropic".to_string()),
+            _ => None,
+        }
+    }
+
+    // Settings are saved without keys, so an inline one goes to the keychain to survive a restart.
+    // Returns the config with the key removed
+    pub fn store_inline_key(&self) -> Result<Self, String> {
+        let inline = match self {
+            ProviderConfig::OpenAi { api_key, .. } | ProviderConfig::Anthropic { api_key, .. } => api_key.as_ref(),
+            _ => None,
+        };
+        if let (Some(key), Some(account)) = (inline.filter(|key| !key.expose().is_empty()), self.key_account()) {
+            keys::store_api_key(&account, key.expose())?;
+        }
+        Ok(self.without_secrets())
+    }
+
+    // Drop inline keys so a config can be written to disk; keys live in the keychain
+    pub fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        match &mut config {
+            ProviderConfig::OpenAi { api_key, .. } | ProviderConfig::Anthropic { api_key, .. } => {
+                *api_key = None
+            }
+            _ => {}
+        }
+        config
+    }
+
+    pub fn build(&self) -> Arc<dyn LlmProvider> {
+        match self {
+            ProviderConfig::Ollama { model } => Arc::new(OllamaProvider::new(
+                model.clone().unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string()),
+            )),
+            ProviderConfig::OpenAi { base_url, api_key, model } => Arc::new(OpenAiProvider::new(
+                base_url.clone(),
+                keys::resolve(&self.key_account().unwrap_or_default(), api_key)
+                    // Keys saved before accounts were per endpoint
+                    .or_else(|| keys::get_api_key("openai")),
+                model.clone(),
+            )),
+            ProviderConfig::Anthropic { api_key, model } => Arc::new(AnthropicProvider::new(
+                keys::resolve("anthropic", api_key).unwrap_or_default(),
+                model.clone().unwrap_or_else(|| DEFAULT_ANTHROPIC_MODEL.to_string()),
+            )),
+            ProviderConfig::LocalServer { base_url, model } => Arc::new(
+                OpenAiProvider::new(base_url.clone(), None, model.clone()).with_name("local"),
+            ),
+            #[cfg(feature = "embedded")]
+            ProviderConfig::Embedded { model_path } => Arc::new(EmbeddedProvider::new(model_path.into())),
+        }
+    }
+}
+
+// For backends without native stop support: the text before the earliest stop sequence
+pub fn clip_at_stop<'a>(text: &'a str, stop: &[String]) -> Option<&'a str> {
+    stop.iter()
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| text.find(s.as_str()))
+        .min()
+        .map(|end| &text[..end])
+}
+
+// Split a ChatML prompt (<|im_start|>role\n...<|im_end|>) into role-based messages.
+// The trailing empty assistant turn is dropped since chat APIs add it themselves.
+pub fn parse_chatml(prompt: &str) -> Vec<ChatMessage> {
+    let mut messages = Vec::new();
+
+    for block in prompt.split("<|im_start|>").skip(1) {
+        let block = block.split("<|im_end|>").next().unwrap_or("");
+        let (role, content) = match block.split_once('\n') {
+            Some((role, content)) => (role.trim(), content.trim()),
+            None => (block.trim(), ""),
+        };
+
+        if role.is_empty() || (role == "assistant" && content.is_empty()) {
+            continue;
+        }
+
+        messages.push(ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        });
+    }
+
+    // Plain prompts without any markers become a single user turn
+    if messages.is_empty() && !prompt.trim().is_empty() {
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: prompt.trim().to_string(),
+        });
+    }
+
+    messages
+}
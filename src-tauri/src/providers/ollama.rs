@@ -0,0 +1,380 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+
+use super::sse::for_each_line;
+use super::{parse_chatml, GenerationOptions, LlmProvider};
+use crate::prompts::PromptTemplate;
+
+pub const OLLAMA_URL: &str = "http://localhost:11434";
+
+// One vector per text from an embedding model such as nomic-embed-text
+pub async fn embed(model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let client = reqwest::Client::new();
+    let mut vectors = Vec::with_capacity(texts.len());
+    for text in texts {
+        let response = client
+            .post(format!("{}/api/embeddings", OLLAMA_URL))
+            .json(&json!({ "model": model, "prompt": text }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Ollama error ({}): {}", status, text));
+        }
+
+        let body: Value = response.json().await.map_err(|e| format!("Invalid Ollama response: {}", e))?;
+        let vector: Vec<f32> = body["embedding"]
+            .as_array()
+            .ok_or_else(|| "Ollama returned no embedding".to_string())?
+            .iter()
+            .filter_map(|value| value.as_f64().map(|value| value as f32))
+            .collect();
+        vectors.push(vector);
+    }
+    Ok(vectors)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledModel {
+    pub name: String,
+    // sha256 of the model's manifest, comparable with the registry's
+    pub digest: String,
+    pub size: u64,
+    pub modified_at: String,
+}
+
+// Everything `ollama list` shows, with digests and sizes
+pub async fn installed_models() -> Result<Vec<InstalledModel>, String> {
+    let body: Value = reqwest::Client::new()
+        .get(format!("{}/api/tags", OLLAMA_URL))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Ollama response: {}", e))?;
+
+    Ok(body["models"]
+        .as_array()
+        .map(|items| {
+            items.iter()
+                .filter_map(|item| {
+                    Some(InstalledModel {
+                        name: item["name"].as_str()?.to_string(),
+                        digest: item["digest"].as_str().unwrap_or_default().to_string(),
+                        size: item["size"].as_u64().unwrap_or(0),
+                        modified_at: item["modified_at"].as_str().unwrap_or_default().to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Equivalent of `ollama pull`; reports (status, completed bytes, total bytes) as layers download
+pub async fn pull(model: &str, mut on_progress: impl FnMut(&str, u64, Option<u64>) + Send) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/pull", OLLAMA_URL))
+        .json(&json!({ "model": model, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama error ({}): {}", status, text));
+    }
+
+    // Newline-delimited JSON: {"status": "pulling <digest>", "completed": 123, "total": 456}
+    let mut pull_error = None;
+    for_each_line(response, |line| {
+        let Ok(chunk) = serde_json::from_str::<Value>(line) else { return true };
+        if let Some(error) = chunk["error"].as_str() {
+            pull_error = Some(error.to_string());
+            return false;
+        }
+        let status = chunk["status"].as_str().unwrap_or_default();
+        on_progress(status, chunk["completed"].as_u64().unwrap_or(0), chunk["total"].as_u64());
+        status != "success"
+    })
+    .await?;
+
+    match pull_error {
+        Some(e) => Err(format!("Failed to pull {}: {}", model, e)),
+        None => Ok(()),
+    }
+}
+
+// Models currently in memory, as `ollama ps` shows them
+pub async fn loaded_models() -> Result<Vec<String>, String> {
+    let body: Value = reqwest::Client::new()
+        .get(format!("{}/api/ps", OLLAMA_URL))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Ollama response: {}", e))?;
+    Ok(body["models"]
+        .as_array()
+        .map(|items| items.iter().filter_map(|item| item["name"].as_str().map(|name| name.to_string())).collect())
+        .unwrap_or_default())
+}
+
+// Equivalent of `ollama rm`
+pub async fn delete_model(model: &str) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .delete(format!("{}/api/delete", OLLAMA_URL))
+        .json(&json!({ "model": model }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Failed to remove {} ({}): {}", model, status, text));
+    }
+    Ok(())
+}
+
+pub struct OllamaProvider {
+    client: reqwest::Client,
+    model: String,
+    template: OnceCell<PromptTemplate>,
+    // The model's Modelfile carries the system prompt, so only the user turn is sent
+    native: bool,
+    // Hand-templated prompts to /api/generate instead of messages to /api/chat
+    raw: bool,
+}
+
+impl OllamaProvider {
+    pub fn new(model: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            model,
+            template: OnceCell::new(),
+            native: false,
+            raw: false,
+        }
+    }
+
+    // Advanced override (settings.raw_prompt_template) for models whose Ollama template is
+    // broken: prompts are laid out in our template for the model family and sent raw
+    pub fn with_raw_template(mut self) -> Self {
+        self.raw = true;
+        self
+    }
+
+    // For models created by create_vibe_model, whose Modelfile already carries the system prompt
+    pub fn with_native_template(mut self) -> Self {
+        self.native = true;
+        self
+    }
+
+    // Equivalent of `ollama show`
+    async fn show(&self) -> Option<Value> {
+        self.client
+            .post(format!("{}/api/show", OLLAMA_URL))
+            .json(&json!({ "model": self.model }))
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()
+    }
+
+    // The details block names the model family
+    async fn detect_family(&self) -> Option<String> {
+        let body = self.show().await?;
+        body["details"]["family"].as_str().map(|family| family.to_string())
+    }
+
+    // Newer Ollama lists capabilities; older ones only reveal a vision tower via the clip family
+    pub async fn supports_vision(&self) -> bool {
+        let Some(body) = self.show().await else { return false };
+        let lists = |items: &Value, wanted: &str| {
+            items.as_array().is_some_and(|items| items.iter().any(|item| item.as_str() == Some(wanted)))
+        };
+        lists(&body["capabilities"], "vision") || lists(&body["details"]["families"], "clip")
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn template(&self) -> PromptTemplate {
+        if self.native {
+            return PromptTemplate::Native;
+        }
+        // /api/chat takes messages and applies the model's own template, like the cloud APIs
+        if !self.raw {
+            return PromptTemplate::ChatMl;
+        }
+        *self.template
+            .get_or_init(|| async {
+                let family = self.detect_family().await.unwrap_or_else(|| self.model.clone());
+                PromptTemplate::for_family(&family)
+            })
+            .await
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        on_token: &(dyn Fn(String) + Send + Sync),
+    ) -> Result<String, String> {
+        let mut body = json!({ "model": self.model, "stream": true, "options": {} });
+        if self.raw && !self.native {
+            // Already in the model's template, so skip Ollama's
+            body["prompt"] = json!(prompt);
+            body["raw"] = json!(true);
+        } else {
+            body["messages"] = json!(parse_chatml(prompt));
+        }
+        if let Some(temperature) = options.temperature {
+            body["options"]["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = options.top_p {
+            body["options"]["top_p"] = json!(top_p);
+        }
+        if let Some(seed) = options.seed {
+            body["options"]["seed"] = json!(seed);
+        }
+        if !options.stop.is_empty() {
+            body["options"]["stop"] = json!(options.stop);
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            body["options"]["num_predict"] = json!(max_tokens);
+        }
+        if let Some(keep_alive) = &options.keep_alive {
+            body["keep_alive"] = json!(keep_alive);
+        }
+        if !options.images.is_empty() {
+            match body.get_mut("messages").and_then(Value::as_array_mut).and_then(|messages| messages.last_mut()) {
+                Some(last) => last["images"] = json!(options.images),
+                // In raw mode the prompt itself must reference them as [img-0], [img-1], ...
+                None => body["images"] = json!(options.images),
+            }
+        }
+
+        let endpoint = if body.get("messages").is_some() { "chat" } else { "generate" };
+        let response = self.client
+            .post(format!("{}/api/{}", OLLAMA_URL, endpoint))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Ollama error ({}): {}", status, text));
+        }
+
+        // Newline-delimited JSON chunks: {"response": "...", "done": false} from generate,
+        // {"message": {"role": "assistant", "content": "..."}, "done": false} from chat
+        let mut result = String::new();
+        let mut stream_error = None;
+        for_each_line(response, |line| {
+            let Ok(chunk) = serde_json::from_str::<Value>(line) else { return true };
+            if let Some(error) = chunk["error"].as_str() {
+                stream_error = Some(error.to_string());
+                return false;
+            }
+            if let Some(token) = chunk["response"].as_str().or(chunk["message"]["content"].as_str()) {
+                if !token.is_empty() {
+                    result.push_str(token);
+                    on_token(token.to_string());
+                }
+            }
+            !chunk["done"].as_bool().unwrap_or(false)
+        })
+        .await?;
+
+        match stream_error {
+            Some(e) => Err(format!("Ollama error: {}", e)),
+            None => Ok(result),
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, String> {
+        let body: Value = self.client
+            .get(format!("{}/api/tags", OLLAMA_URL))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Ollama response: {}", e))?;
+
+        let models = body["models"]
+            .as_array()
+            .map(|items| {
+                items.iter()
+                    .filter_map(|item| item["name"].as_str().map(|name| name.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(models)
+    }
+
+    // Everything Ollama has in memory (vision and embedding models included), not just ours;
+    // keep_alive 0 evicts a model immediately, the same as `ollama stop`
+    async fn unload(&self) -> Result<Vec<String>, String> {
+        let loaded = loaded_models().await?;
+
+        for model in &loaded {
+            let response = self.client
+                .post(format!("{}/api/generate", OLLAMA_URL))
+                .json(&json!({ "model": model, "keep_alive": 0 }))
+                .send()
+                .await
+                .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format!("Failed to unload {} ({}): {}", model, status, text));
+            }
+        }
+        Ok(loaded)
+    }
+
+    // A generate request without a prompt just loads the model into memory
+    async fn warm_up(&self, options: &GenerationOptions) -> Result<(), String> {
+        let mut body = json!({ "model": self.model });
+        if let Some(keep_alive) = &options.keep_alive {
+            body["keep_alive"] = json!(keep_alive);
+        }
+
+        let response = self.client
+            .post(format!("{}/api/generate", OLLAMA_URL))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Ollama error ({}): {}", status, text));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::sse::for_each_data;
+use crate::keys;
+use super::{parse_chatml, GenerationOptions, LlmProvider};
+
+pub struct OpenAiProvider {
+    name: &'static str,
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(base_url: String, api_key: Option<String>, model: String) -> Self {
+        Self {
+            name: "openai",
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+        }
+    }
+
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self.client.request(method, format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        on_token: &(dyn Fn(String) + Send + Sync),
+    ) -> Result<String, String> {
+        let mut body = json!({
+            "model": self.model,
+            "messages": parse_chatml(prompt),
+            "stream": true,
+        });
+        if let Some(temperature) = options.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = options.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if let Some(seed) = options.seed {
+            body["seed"] = json!(seed);
+        }
+        // OpenAI accepts at most four stop sequences
+        if !options.stop.is_empty() {
+            body["stop"] = json!(options.stop.iter().take(4).collect::<Vec<_>>());
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+
+        let response = self.request(reqwest::Method::POST, "/chat/completions")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", self.base_url, e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("OpenAI-compatible API error ({}): {}", status, keys::redact(&text)));
+        }
+
+        // Server-sent events: one `data: {json}` per line, terminated by `data: [DONE]`
+        let mut result = String::new();
+        for_each_data(response, |data| {
+            if data == "[DONE]" {
+                return false;
+            }
+            if let Ok(event) = serde_json::from_str::<Value>(data) {
+                if let Some(token) = event["choices"][0]["delta"]["content"].as_str() {
+                    result.push_str(token);
+                    on_token(token.to_string());
+                }
+            }
+            true
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, String> {
+        let response = self.request(reqwest::Method::GET, "/models")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", self.base_url, e))?;
+
+        let body: Value = response.json().await
+            .map_err(|e| format!("Invalid models response: {}", e))?;
+
+        let models = body["data"]
+            .as_array()
+            .map(|items| {
+                items.iter()
+                    .filter_map(|item| item["id"].as_str().map(|id| id.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(models)
+    }
+}
@@ -0,0 +1,44 @@
+use futures_util::StreamExt;
+
+// Feed each complete line of a streamed HTTP body to `on_line`.
+// Returning false from the callback stops reading early.
+pub async fn for_each_line(
+    response: reqwest::Response,
+    mut on_line: impl FnMut(&str) -> bool + Send,
+) -> Result<(), String> {
+    let mut stream = response.bytes_stream();
+    let mut buffer: Vec<u8> = Vec::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Stream interrupted: {}", e))?;
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if !line.is_empty() && !on_line(line) {
+                return Ok(());
+            }
+        }
+    }
+
+    let rest = String::from_utf8_lossy(&buffer);
+    if !rest.trim().is_empty() {
+        on_line(rest.trim());
+    }
+
+    Ok(())
+}
+
+// Server-sent events: only the `data:` payloads matter to us
+pub async fn for_each_data(
+    response: reqwest::Response,
+    mut on_data: impl FnMut(&str) -> bool + Send,
+) -> Result<(), String> {
+    for_each_line(response, |line| match line.strip_prefix("data:") {
+        Some(data) => on_data(data.trim()),
+        None => true,
+    })
+    .await
+}
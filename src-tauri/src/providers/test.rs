@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+
+use super::{parse_chatml, GenerationOptions, LlmProvider};
+use crate::mock;
+
+// Chunk size for the fake stream; small enough that the UI sees several tokens
+const CHUNK_CHARS: usize = 64;
+
+// Backs --test-mode: bundled fixtures, no network, no delays
+pub struct TestProvider;
+
+#[async_trait]
+impl LlmProvider for TestProvider {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    fn model(&self) -> &str {
+        "test-fixtures"
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        _options: &GenerationOptions,
+        on_token: &(dyn Fn(String) + Send + Sync),
+    ) -> Result<String, String> {
+        // Match on what the user asked for, not on the system prompt around it
+        let user_turn = parse_chatml(prompt)
+            .into_iter()
+            .rev()
+            .find(|message| message.role == "user")
+            .map(|message| message.content)
+            .unwrap_or_else(|| prompt.to_string());
+        let is_fix_attempt = user_turn.starts_with("FIX ATTEMPT");
+        let response = mock::bundled_response(&user_turn, is_fix_attempt);
+
+        let chars: Vec<char> = response.chars().collect();
+        for chunk in chars.chunks(CHUNK_CHARS) {
+            on_token(chunk.iter().collect());
+        }
+        Ok(response)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, String> {
+        Ok(vec![self.model().to_string()])
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::postprocess::{self, Stage};
+use crate::{keys, projects};
+
+const GITHUB_API: &str = "https://api.github.com";
+const USER_AGENT: &str = concat!("vibe-cherry/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GistResult {
+    pub id: String,
+    pub url: String,
+    pub raw_url: String,
+    // Renders the HTML directly in the browser
+    pub preview_url: String,
+}
+
+#[tauri::command]
+pub async fn publish_gist(
+    project_id: String,
+    token: Option<String>,
+    public: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<GistResult, String> {
+    let token = token
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| keys::get_api_key("github"))
+        .ok_or("A GitHub token is required to publish gists")?;
+
+    let project = projects::read_project(&app_handle, &project_id)?;
+    let page = postprocess::run(&app_handle, Stage::Export, &project.html).html;
+    let body = json!({
+        "description": format!("{} — made with Vibe Cherry 🍒", project.meta.name),
+        "public": public.unwrap_or(false),
+        "files": {
+            "index.html": { "content": page },
+        },
+    });
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/gists", GITHUB_API))
+        .bearer_auth(&token)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("GitHub API error ({}): {}", status, keys::redact(&text)));
+    }
+
+    let gist: Value = response.json().await
+        .map_err(|e| format!("Invalid GitHub response: {}", e))?;
+
+    let id = gist["id"].as_str().unwrap_or_default().to_string();
+    let raw_url = gist["files"]["index.html"]["raw_url"].as_str().unwrap_or_default().to_string();
+
+    Ok(GistResult {
+        url: gist["html_url"].as_str().unwrap_or_default().to_string(),
+        preview_url: format!("https://gistpreview.github.io/?{}", id),
+        raw_url,
+        id,
+    })
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{current_settings, extract, validate, AppState};
+
+// Below this a page is technically fine but almost certainly not what was asked for
+const MIN_LENGTH: usize = 400;
+// Anything this large is usually a model stuck repeating itself
+const MAX_LENGTH: usize = 300_000;
+
+// Text models write instead of the code they were supposed to produce
+const PLACEHOLDERS: &[&str] = &[
+    "lorem ipsum",
+    "rest of the code",
+    "existing code",
+    "your code here",
+    "your content here",
+    "add more items here",
+];
+
+// Markup that only does something with a script behind it
+const CONTROLS: &[&str] = &["<button", "<input", "<select", "<textarea", "<form"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityScore {
+    // 0-100
+    pub score: u8,
+    // One entry per deduction, worded so it can go straight into a fix prompt
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct QualityEvent {
+    generation_id: Option<i64>,
+    #[serde(flatten)]
+    quality: QualityScore,
+    // Below settings.quality_threshold; the healing loop would retry this one
+    needs_healing: bool,
+}
+
+// Cheap heuristics only, so it can run on every generation
+pub fn score(html: &str) -> QualityScore {
+    let lower = html.to_lowercase();
+    let mut penalty: u32 = 0;
+    let mut reasons = Vec::new();
+    let mut deduct = |points: u32, reason: String| {
+        penalty += points;
+        reasons.push(reason);
+    };
+
+    let validation = validate::validate(html);
+    if !validation.has_doctype {
+        deduct(15, "The document has no <!DOCTYPE html> declaration".to_string());
+    }
+    if validation.truncated {
+        deduct(30, "The document is cut off before </html>".to_string());
+    }
+
+    let scripts = validate::validate_scripts(html);
+    if !scripts.errors.is_empty() {
+        deduct(25, format!("{} JavaScript syntax error(s) stop the scripts from running", scripts.errors.len()));
+    }
+    let has_behaviour = scripts.script_count > 0 || lower.contains(" onclick=") || lower.contains(" oninput=");
+    if !has_behaviour {
+        let reason = if CONTROLS.iter().any(|control| lower.contains(control)) {
+            "The page has controls but no script, so nothing happens when they are used"
+        } else {
+            "The page is static: no script and no interactive controls"
+        };
+        deduct(20, reason.to_string());
+    }
+
+    let length = html.trim().len();
+    if length < MIN_LENGTH {
+        deduct(20, format!("The document is suspiciously short ({} characters)", length));
+    } else if length > MAX_LENGTH {
+        deduct(10, format!("The document is unusually long ({} characters), possibly repeating itself", length));
+    }
+
+    let placeholders: Vec<&str> = PLACEHOLDERS.iter().copied().filter(|p| lower.contains(p)).collect();
+    if !placeholders.is_empty() {
+        deduct(15, format!("Placeholder text instead of real content: \"{}\"", placeholders.join("\", \"")));
+    }
+
+    QualityScore {
+        score: 100u32.saturating_sub(penalty) as u8,
+        reasons,
+    }
+}
+
+pub fn below_threshold(state: &AppState, quality: &QualityScore) -> bool {
+    quality.score < current_settings(state).quality_threshold
+}
+
+// Bullets in the shape healing::format_problems expects
+pub fn format_reasons(quality: &QualityScore) -> Vec<String> {
+    quality.reasons.iter().map(|reason| format!("- {}", reason)).collect()
+}
+
+// Scores the HTML in a finished response, if there is any; edit blocks and chat answers are skipped
+pub fn emit_for_response(app_handle: &AppHandle, state: &AppState, generation_id: Option<i64>, response: &str) {
+    let Some(html) = extract::extract(response).html else { return };
+    let quality = score(&html);
+    let needs_healing = below_threshold(state, &quality);
+    let event = QualityEvent { generation_id, quality, needs_healing };
+    if let Err(e) = app_handle.emit("vibe-quality", event) {
+        tracing::warn!("Failed to emit quality score: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn score_output(html: String) -> Result<QualityScore, String> {
+    Ok(score(&html))
+}
@@ -0,0 +1,84 @@
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::projects::{self, ProjectMeta};
+use crate::{current_provider, extract, generate_vibe_stream, preview, AppState};
+
+pub const WINDOW_LABEL: &str = "quick-vibe";
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+// Replaces whatever was registered before; None just clears it
+pub fn register_shortcut(app_handle: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let shortcuts = app_handle.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| format!("Failed to clear global shortcut: {}", e))?;
+    let Some(shortcut) = shortcut.map(str::trim).filter(|s| !s.is_empty()) else { return Ok(()) };
+
+    shortcuts
+        .on_shortcut(shortcut, |app_handle, _, event| {
+            if event.state() == ShortcutState::Pressed {
+                if let Err(e) = toggle_window(app_handle) {
+                    tracing::warn!("{}", e);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to register global shortcut {}: {}", shortcut, e))
+}
+
+fn toggle_window(app_handle: &AppHandle) -> Result<(), String> {
+    match app_handle.get_webview_window(WINDOW_LABEL) {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            window.hide().map_err(|e| format!("Failed to hide quick vibe window: {}", e))
+        }
+        _ => show_window(app_handle),
+    }
+}
+
+// Small always-on-top prompt; the frontend renders the quick input for this label
+pub fn show_window(app_handle: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app_handle.get_webview_window(WINDOW_LABEL) {
+        window.show().map_err(|e| format!("Failed to show quick vibe window: {}", e))?;
+        return window.set_focus().map_err(|e| format!("Failed to focus quick vibe window: {}", e));
+    }
+
+    WebviewWindowBuilder::new(app_handle, WINDOW_LABEL, WebviewUrl::App("index.html?window=quick-vibe".into()))
+        .title("Quick vibe")
+        .inner_size(560.0, 96.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build()
+        .map_err(|e| format!("Failed to open quick vibe window: {}", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn submit_quick_vibe(
+    prompt: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ProjectMeta, String> {
+    if prompt.trim().is_empty() {
+        return Err("Describe the app you want".to_string());
+    }
+    if let Some(window) = app_handle.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+
+    // Nothing shows tokens here; the result opens in its own preview window
+    let silent = Channel::new(|_| Ok(()));
+    let response = generate_vibe_stream(prompt.clone(), None, None, None, None, None, silent, app_handle.clone(), state.clone()).await?;
+    let html = extract::extract(&response)
+        .html
+        .ok_or_else(|| "The model didn't return any HTML".to_string())?;
+
+    let model = current_provider(&state).model().to_string();
+    let project = projects::write_project(&app_handle, None, None, prompt, html, model)?;
+    preview::open_window(&app_handle, &project.meta)?;
+    Ok(project.meta)
+}
@@ -0,0 +1,143 @@
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+
+use crate::projects::{self, Project};
+use crate::validate::{self, HtmlValidation, JsValidation};
+use crate::{context, extract, healing, keys, prompts, sessions};
+use crate::{build_full_prompt, current_provider, stream_from_provider, AppState, StreamEvent};
+
+// Turn a saved project into a different app ("make this todo app a habit tracker").
+// The original stays untouched; the result is saved as a new project linked back to it.
+#[tauri::command]
+pub async fn remix_project(
+    project_id: String,
+    instruction: String,
+    name: Option<String>,
+    session_id: Option<String>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Project, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    if instruction.trim().is_empty() {
+        return Err("Describe what to turn the project into".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+    let source = projects::read_project(&app_handle, &project_id)?;
+
+    let user_turn = format!(
+        "{}\n\nEXISTING APP ({}):\n```html\n{}\n```\n\nREMIX REQUEST: {}",
+        prompts::REMIX_INSTRUCTIONS,
+        source.meta.name,
+        source.html,
+        instruction.trim()
+    );
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let user_turn = context::with_history(&state, &app_handle, &history, &user_turn).await;
+    let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
+
+    let response = stream_from_provider(&state, &app_handle, &on_token, &instruction, &full_prompt, 1)
+        .await
+        .map_err(|e| keys::redact(&e))?;
+    let html = extract::extract(&response).html.ok_or("The model did not return an HTML document")?;
+
+    let model = current_provider(&state).model().to_string();
+    let name = name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| format!("{} (remix)", source.meta.name));
+    let mut remix = projects::write_project(&app_handle, None, Some(name), instruction, html, model)?;
+    remix.meta.remixed_from = Some(source.meta.id);
+    projects::write_meta(&app_handle, &remix.meta)?;
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &remix.meta.prompt, &response);
+    tracing::info!(from = %project_id, to = %remix.meta.id, "Remixed project");
+    Ok(remix)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeResult {
+    pub project: Project,
+    // Checks on the saved document; problems left after the repair attempt show up here
+    pub html_validation: HtmlValidation,
+    pub js_validation: JsValidation,
+    // A second generation was needed to repair the first merge
+    pub repaired: bool,
+}
+
+// Combine two saved projects into a new one ("put the calculator in the todo app's sidebar").
+// Merges are long and easy to get wrong, so a result that fails validation gets one repair
+// pass before it is saved.
+#[tauri::command]
+pub async fn merge_projects(
+    id_a: String,
+    id_b: String,
+    instruction: String,
+    name: Option<String>,
+    session_id: Option<String>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<MergeResult, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    if id_a == id_b {
+        return Err("Pick two different projects to merge".to_string());
+    }
+    if instruction.trim().is_empty() {
+        return Err("Describe how to combine the projects".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+    let a = projects::read_project(&app_handle, &id_a)?;
+    let b = projects::read_project(&app_handle, &id_b)?;
+
+    let request = format!(
+        "{}\n\nAPP A ({}):\n```html\n{}\n```\n\nAPP B ({}):\n```html\n{}\n```\n\nMERGE REQUEST: {}",
+        prompts::MERGE_INSTRUCTIONS,
+        a.meta.name,
+        a.html,
+        b.meta.name,
+        b.html,
+        instruction.trim()
+    );
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let request = context::with_history(&state, &app_handle, &history, &request).await;
+    let full_prompt = build_full_prompt(&state, &app_handle, &request).await;
+    let mut response = stream_from_provider(&state, &app_handle, &on_token, &instruction, &full_prompt, 1)
+        .await
+        .map_err(|e| keys::redact(&e))?;
+    let mut html = extract::extract(&response).html.ok_or("The model did not return an HTML document")?;
+
+    let problems = healing::static_problems(&html);
+    let repaired = !problems.is_empty();
+    if repaired {
+        let user_turn = format!(
+            "FIX ATTEMPT #1\nThe merged document has these problems:\n{}\nFix every one of them and return the complete document.\n\nMerge request: {}\n\nMerged code:\n```html\n{}\n```",
+            problems.join("\n"),
+            instruction.trim(),
+            html
+        );
+        let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
+        let repair = stream_from_provider(&state, &app_handle, &on_token, &instruction, &full_prompt, 2)
+            .await
+            .map_err(|e| keys::redact(&e))?;
+        // A repair that lost the document is worse than the flawed merge
+        if let Some(fixed) = extract::extract(&repair).html {
+            html = fixed;
+            response = repair;
+        }
+    }
+
+    let html_validation = validate::validate(&html);
+    let js_validation = validate::validate_scripts(&html);
+    let model = current_provider(&state).model().to_string();
+    let name = name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| format!("{} + {}", a.meta.name, b.meta.name));
+    let mut project = projects::write_project(&app_handle, None, Some(name), instruction, html, model)?;
+    project.meta.merged_from = vec![a.meta.id, b.meta.id];
+    projects::write_meta(&app_handle, &project.meta)?;
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &project.meta.prompt, &response);
+    tracing::info!(a = %id_a, b = %id_b, to = %project.meta.id, repaired, "Merged projects");
+    Ok(MergeResult { project, html_validation, js_validation, repaired })
+}
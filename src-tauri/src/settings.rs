@@ -0,0 +1,379 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::guardrails::GuardrailMode;
+use crate::modelfile::VibeModel;
+use crate::presets::{self, GenerationPreset};
+use crate::prompts::{self, PromptTemplate, VibeTheme, VIBE_CODING_SYSTEM_PROMPT};
+use crate::providers::{GenerationOptions, LlmProvider, OllamaProvider, ProviderConfig, TestProvider, DEFAULT_OLLAMA_MODEL};
+use crate::AppState;
+
+const SETTINGS_FILE: &str = "settings.json";
+// Matches tauri.conf.json; app_config_dir() is the platform config dir joined with it
+const APP_IDENTIFIER: &str = "com.vibecherry.app";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub provider: ProviderConfig,
+    // Overrides the model configured on the provider when set
+    pub model: Option<String>,
+    // Tried in order when the model above errors or isn't installed, before mock mode
+    pub fallback_models: Vec<String>,
+    // Set by create_vibe_model
+    pub vibe_model: Option<VibeModel>,
+    // The ollama CLI; None searches PATH and the standard install locations
+    pub ollama_path: Option<String>,
+    // Send Ollama raw prompts in our per-family templates instead of using /api/chat
+    pub raw_prompt_template: bool,
+    pub temperature: f32,
+    pub top_p: Option<f32>,
+    // Named temperature/top_p pairs a request can pick instead of the two values above
+    pub presets: Vec<GenerationPreset>,
+    // Replaces VIBE_CODING_SYSTEM_PROMPT entirely when set
+    pub custom_system_prompt: Option<String>,
+    // Preset look (dark, light, glassmorphism, ...) injected into the system prompt
+    pub vibe_theme: Option<VibeTheme>,
+    // Extra style guidance appended to the system prompt
+    pub theme_prompt: Option<String>,
+    // BCP 47 locale (e.g. "de-DE") generated UI copy is written in; None leaves it to the prompt
+    pub output_language: Option<String>,
+    // Id of the active persona pack entry
+    pub persona: Option<String>,
+    // Upper bound for few-shot examples appended to the system prompt
+    pub example_token_budget: usize,
+    // Chat history beyond this gets summarized
+    pub context_token_budget: usize,
+    // Replay identical prompts from the response cache instead of regenerating
+    pub response_cache: bool,
+    // Ollama keep_alive: longer keeps generations fast, shorter frees RAM/VRAM sooner
+    pub keep_alive: String,
+    // e.g. "\n```\n" to cut off narration after the closing code fence
+    pub stop_sequences: Vec<String>,
+    // Maximum tokens per generation; None leaves it to the backend
+    pub max_output_tokens: Option<u32>,
+    // Use canned responses when no backend is available at all
+    pub mock_mode: bool,
+    // Exports link Tailwind's CDN; false inlines the bundled build for fully offline files
+    pub export_tailwind_cdn: bool,
+    // Strip unexpected external resources and add a CSP before previews run generated code
+    pub sanitize_previews: bool,
+    // Let the model name new projects after saving; off keeps the instant heuristic title
+    pub auto_titles: bool,
+    // Ids of the enabled post-processors (sanitize, tailwind, format, analytics)
+    pub post_processors: Vec<String>,
+    // Injected into the <head> of exported pages by the analytics post-processor
+    pub analytics_snippet: Option<String>,
+    // What happens to generations containing miners, cookie exfiltration, known-bad CDNs, ...
+    pub guardrails: GuardrailMode,
+    // Extra case-insensitive substrings treated like the built-in guardrail rules
+    pub blocked_patterns: Vec<String>,
+    // Ollama model used for image-to-app; None uses the main model if it can see images
+    pub vision_model: Option<String>,
+    // OpenAI-compatible /v1/audio/transcriptions URL; None uses the built-in whisper model
+    pub transcription_url: Option<String>,
+    // Pull the closest saved snippets and past generations into the prompt
+    pub snippet_retrieval: bool,
+    // Ollama embedding model used for snippet retrieval
+    pub embedding_model: String,
+    pub retrieval_top_k: usize,
+    // How often installed models are compared against the Ollama registry; 0 turns it off
+    pub model_update_check_hours: u64,
+    // Seconds between backend-health pings; 0 pauses them
+    pub health_check_secs: u64,
+    // Global shortcut that summons the quick vibe window; None disables it
+    pub quick_vibe_shortcut: Option<String>,
+    // Generations beyond this wait in a queue instead of competing for RAM
+    pub max_concurrent_generations: usize,
+    // Heuristic quality score (0-100) below which the healing loop retries a page
+    pub quality_threshold: u8,
+    pub generation_timeout_secs: u64,
+    pub init_timeout_secs: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            provider: ProviderConfig::default(),
+            model: None,
+            fallback_models: Vec::new(),
+            vibe_model: None,
+            ollama_path: None,
+            raw_prompt_template: false,
+            temperature: 0.7,
+            top_p: None,
+            presets: presets::builtin_presets(),
+            custom_system_prompt: None,
+            vibe_theme: None,
+            theme_prompt: None,
+            output_language: None,
+            persona: None,
+            example_token_budget: 2000,
+            context_token_budget: 6000,
+            response_cache: true,
+            keep_alive: "30m".to_string(),
+            stop_sequences: Vec::new(),
+            max_output_tokens: None,
+            mock_mode: false,
+            export_tailwind_cdn: true,
+            sanitize_previews: true,
+            auto_titles: true,
+            post_processors: vec!["sanitize".to_string(), "tailwind".to_string(), "analytics".to_string()],
+            analytics_snippet: None,
+            guardrails: GuardrailMode::Strip,
+            blocked_patterns: Vec::new(),
+            vision_model: None,
+            transcription_url: None,
+            snippet_retrieval: true,
+            embedding_model: "nomic-embed-text".to_string(),
+            retrieval_top_k: 3,
+            model_update_check_hours: 24,
+            health_check_secs: 5,
+            quick_vibe_shortcut: Some(crate::quick_vibe::DEFAULT_SHORTCUT.to_string()),
+            max_concurrent_generations: 1,
+            quality_threshold: 60,
+            generation_timeout_secs: 300,
+            init_timeout_secs: 10,
+        }
+    }
+}
+
+impl Settings {
+    pub fn build_provider(&self) -> std::sync::Arc<dyn LlmProvider> {
+        if crate::test_mode() {
+            return std::sync::Arc::new(TestProvider);
+        }
+        if let (ProviderConfig::Ollama { .. }, Some(vibe)) = (&self.provider, &self.vibe_model) {
+            if self.model.as_ref() == Some(&vibe.name) && vibe.is_current(self) {
+                return std::sync::Arc::new(OllamaProvider::new(vibe.name.clone()).with_native_template());
+            }
+        }
+        let mut config = self.provider.clone();
+        if let Some(model) = &self.model {
+            config.set_model(model);
+        }
+        if let (ProviderConfig::Ollama { model }, true) = (&config, self.raw_prompt_template) {
+            let model = model.clone().unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string());
+            return std::sync::Arc::new(OllamaProvider::new(model).with_raw_template());
+        }
+        config.build()
+    }
+
+    pub fn system_prompt(&self) -> String {
+        prompts::system_prompt(
+            self.custom_system_prompt.as_deref(),
+            self.vibe_theme,
+            self.theme_prompt.as_deref(),
+            self.output_language.as_deref(),
+        )
+    }
+
+    // Vibe models already carry it in their Modelfile
+    pub fn system_prompt_for(&self, template: PromptTemplate) -> String {
+        match template {
+            PromptTemplate::Native => String::new(),
+            _ => self.system_prompt(),
+        }
+    }
+
+    pub fn generation_options(&self) -> GenerationOptions {
+        GenerationOptions {
+            temperature: Some(self.temperature),
+            top_p: self.top_p,
+            seed: None,
+            stop: self.stop_sequences.clone(),
+            max_tokens: self.max_output_tokens,
+            keep_alive: Some(self.keep_alive.clone()),
+            images: Vec::new(),
+        }
+    }
+}
+
+fn settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_config_dir()
+        .map_err(|e| format!("Failed to resolve config dir: {}", e))?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+// Missing or corrupt settings fall back to defaults rather than blocking startup
+pub fn load(app_handle: &AppHandle) -> Settings {
+    // Test runs never see (or write) the developer's real settings
+    if crate::test_mode() {
+        return Settings::default();
+    }
+    let Ok(path) = settings_path(app_handle) else { return Settings::default() };
+    read(&path)
+}
+
+// The GUI's settings for tools running without a Tauri app (the CLI)
+pub fn load_standalone() -> Settings {
+    if crate::test_mode() {
+        return Settings::default();
+    }
+    match dirs::config_dir() {
+        Some(dir) => read(&dir.join(APP_IDENTIFIER).join(SETTINGS_FILE)),
+        None => Settings::default(),
+    }
+}
+
+fn read(path: &Path) -> Settings {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid settings file {}: {}", path.display(), e);
+            Settings::default()
+        }),
+        Err(_) => Settings::default(),
+    }
+}
+
+pub fn save(app_handle: &AppHandle, settings: &Settings) -> Result<(), String> {
+    if crate::test_mode() {
+        return Ok(());
+    }
+    let path = settings_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+
+    // API keys belong in the keychain, never in the settings file
+    let mut on_disk = settings.clone();
+    on_disk.provider = settings.provider.without_secrets();
+
+    let json = serde_json::to_string_pretty(&on_disk)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<Settings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+// Apply a change, persist it and tell the frontend; the provider is rebuilt in case it changed
+pub fn modify(
+    app_handle: &AppHandle,
+    state: &AppState,
+    change: impl FnOnce(&mut Settings),
+) -> Result<Settings, String> {
+    let previous = state.settings.lock().unwrap().clone();
+    let mut settings = previous.clone();
+    change(&mut settings);
+    save(app_handle, &settings)?;
+
+    if settings.quick_vibe_shortcut != previous.quick_vibe_shortcut {
+        if let Err(e) = crate::quick_vibe::register_shortcut(app_handle, settings.quick_vibe_shortcut.as_deref()) {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    *state.provider.lock().unwrap() = settings.build_provider();
+    *state.settings.lock().unwrap() = settings.clone();
+
+    if let Err(e) = app_handle.emit("settings-changed", settings.clone()) {
+        tracing::warn!("Failed to emit settings change: {}", e);
+    }
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn update_settings(
+    settings: Settings,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    let mut settings = settings;
+    settings.provider = settings.provider.store_inline_key()?;
+    modify(&app_handle, &state, |current| *current = settings)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemPromptInfo {
+    pub prompt: String,
+    pub is_custom: bool,
+    pub default_prompt: String,
+}
+
+fn system_prompt_info(settings: &Settings) -> SystemPromptInfo {
+    SystemPromptInfo {
+        prompt: settings
+            .custom_system_prompt
+            .clone()
+            .unwrap_or_else(|| VIBE_CODING_SYSTEM_PROMPT.to_string()),
+        is_custom: settings.custom_system_prompt.is_some(),
+        default_prompt: VIBE_CODING_SYSTEM_PROMPT.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_prompt(state: State<'_, AppState>) -> Result<SystemPromptInfo, String> {
+    Ok(system_prompt_info(&state.settings.lock().unwrap()))
+}
+
+#[tauri::command]
+pub async fn set_system_prompt(
+    prompt: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SystemPromptInfo, String> {
+    if prompt.trim().is_empty() {
+        return Err("System prompt cannot be empty".to_string());
+    }
+    let settings = modify(&app_handle, &state, |settings| {
+        settings.custom_system_prompt = Some(prompt);
+    })?;
+    Ok(system_prompt_info(&settings))
+}
+
+#[tauri::command]
+pub async fn reset_system_prompt(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SystemPromptInfo, String> {
+    let settings = modify(&app_handle, &state, |settings| {
+        settings.custom_system_prompt = None;
+    })?;
+    Ok(system_prompt_info(&settings))
+}
+
+// None goes back to the default dark-with-pops-of-color look
+#[tauri::command]
+pub async fn set_vibe_theme(
+    theme: Option<VibeTheme>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    modify(&app_handle, &state, |settings| settings.vibe_theme = theme)
+}
+
+// None (or an empty string) stops asking for a specific language
+#[tauri::command]
+pub async fn set_output_language(
+    locale: Option<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    let locale = locale
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| prompts::normalize_locale(l).ok_or_else(|| format!("Invalid locale: {}", l)))
+        .transpose()?;
+    modify(&app_handle, &state, |settings| settings.output_language = locale)
+}
+
+// For Ollama installs discovery can't find; None goes back to searching
+#[tauri::command]
+pub async fn set_ollama_path(
+    path: Option<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(path) = &path {
+        if !Path::new(path).is_file() {
+            return Err(format!("No file at {}", path));
+        }
+    }
+    modify(&app_handle, &state, |settings| settings.ollama_path = path)
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, ExitRequestApi, Manager};
+use tokio::process::{Child, Command};
+
+use crate::AppState;
+
+// How long exit waits for project saves that are already writing
+const SAVE_GRACE: Duration = Duration::from_secs(5);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static SAVES_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+// Held while a project is being written; exit waits for every guard to drop
+pub struct SaveGuard;
+
+pub fn saving() -> SaveGuard {
+    SAVES_IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+    SaveGuard
+}
+
+impl Drop for SaveGuard {
+    fn drop(&mut self) {
+        SAVES_IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Child processes we started, by pid. npm and friends start their own children, so each
+// is killed with its whole tree: a process group on Unix, a job object on Windows.
+fn children() -> &'static Mutex<HashMap<u32, Tree>> {
+    static CHILDREN: OnceLock<Mutex<HashMap<u32, Tree>>> = OnceLock::new();
+    CHILDREN.get_or_init(Default::default)
+}
+
+#[cfg(unix)]
+struct Tree(libc::pid_t);
+
+// The job handle as an integer so the map stays Send
+#[cfg(windows)]
+struct Tree(isize);
+
+#[cfg(unix)]
+impl Tree {
+    fn kill(&self) {
+        // SAFETY: plain syscall; a group that already exited just returns ESRCH
+        unsafe {
+            libc::killpg(self.0, libc::SIGTERM);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Tree {
+    fn kill(&self) {
+        windows::terminate(self.0);
+    }
+}
+
+// Closing the job kills whatever is still in it
+#[cfg(windows)]
+impl Drop for Tree {
+    fn drop(&mut self) {
+        windows::close(self.0);
+    }
+}
+
+// Call before spawning a child that should die with the app
+pub fn own(cmd: &mut Command) -> &mut Command {
+    // Its own process group, so the group id is the child's pid
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd
+}
+
+#[cfg(unix)]
+fn tree_for(child: &Child) -> Option<Tree> {
+    child.id().map(|pid| Tree(pid as libc::pid_t))
+}
+
+#[cfg(windows)]
+fn tree_for(child: &Child) -> Option<Tree> {
+    windows::job_for(child).map(Tree)
+}
+
+// Call right after spawning a command prepared with own()
+pub fn track(child: &Child) {
+    let Some(pid) = child.id() else { return };
+    match tree_for(child) {
+        Some(tree) => {
+            children().lock().unwrap().insert(pid, tree);
+        }
+        None => tracing::warn!("Failed to track process {}; its children may outlive the app", pid),
+    }
+}
+
+// Kill a tracked child and everything it started; does nothing for untracked pids
+pub fn kill_tree(pid: u32) {
+    if let Some(tree) = children().lock().unwrap().remove(&pid) {
+        tree.kill();
+    }
+}
+
+// For children that exited on their own
+pub fn untrack(pid: u32) {
+    children().lock().unwrap().remove(&pid);
+}
+
+fn kill_all() -> usize {
+    let pids: Vec<u32> = children().lock().unwrap().keys().copied().collect();
+    for pid in &pids {
+        kill_tree(*pid);
+    }
+    pids.len()
+}
+
+#[cfg(windows)]
+mod windows {
+    use tokio::process::Child;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    // Processes the child starts join its job, and closing the last handle (even when we
+    // crash) kills them all. Anything started before the assignment escapes it.
+    pub fn job_for(child: &Child) -> Option<isize> {
+        let process = child.raw_handle()?;
+        // SAFETY: the handles are valid for these calls and the limits struct outlives them
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return None;
+            }
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let assigned = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const std::ffi::c_void,
+                std::mem::size_of_val(&limits) as u32,
+            ) != 0
+                && AssignProcessToJobObject(job, process as HANDLE) != 0;
+            if !assigned {
+                CloseHandle(job);
+                return None;
+            }
+            Some(job as isize)
+        }
+    }
+
+    pub fn terminate(job: isize) {
+        // SAFETY: the handle came from job_for and stays open until its Tree is dropped
+        unsafe {
+            TerminateJobObject(job as HANDLE, 1);
+        }
+    }
+
+    pub fn close(job: isize) {
+        // SAFETY: called once, from Tree's Drop
+        unsafe {
+            CloseHandle(job as HANDLE);
+        }
+    }
+}
+
+// Exit is held back once: generations are cancelled, child processes killed, the API
+// server stopped and in-progress saves given a moment to finish, then the app exits for real
+pub fn on_exit_requested(app_handle: &AppHandle, api: &ExitRequestApi) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    api.prevent_exit();
+    if let Err(e) = app_handle.emit("app-exiting", ()) {
+        tracing::warn!("Failed to emit app exiting: {}", e);
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let cancelled = state.jobs.cancel_all();
+        let server = state.api_server.lock().unwrap().take();
+        if let Some(server) = server {
+            // Its requests were just cancelled, so this is quick
+            server.stop().await;
+        }
+        let killed = kill_all();
+
+        let started = Instant::now();
+        while SAVES_IN_FLIGHT.load(Ordering::SeqCst) > 0 && started.elapsed() < SAVE_GRACE {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let unfinished = SAVES_IN_FLIGHT.load(Ordering::SeqCst);
+        if unfinished > 0 {
+            tracing::warn!("Exiting with {} project save(s) still in progress", unfinished);
+        }
+        tracing::info!(cancelled, killed, "Shut down");
+        app_handle.exit(0);
+    });
+}
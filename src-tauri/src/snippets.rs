@@ -0,0 +1,296 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::prompts::estimate_tokens;
+use crate::providers::embed;
+use crate::{current_settings, extract, keys, now_millis, test_mode, AppState};
+
+const INDEX_FILE: &str = "index.json";
+// Past generations are indexed automatically, so keep only the most recent ones
+const MAX_GENERATIONS: usize = 200;
+// Keeps one call from tying Ollama up for minutes
+const MAX_EMBED_BATCH: usize = 256;
+// Below this the "match" is usually just another page with buttons in it
+const MIN_SIMILARITY: f32 = 0.5;
+// Embedding models have small context windows; the start of a document says most about it
+const EMBED_CHARS: usize = 2000;
+// Each retrieved snippet is clipped so a few of them fit beside the request
+const SNIPPET_TOKENS: usize = 1200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub id: String,
+    // snippet (saved by the user) or generation (indexed automatically)
+    pub kind: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedSnippet {
+    #[serde(flatten)]
+    snippet: Snippet,
+    // Vectors from different models can't be compared, so each remembers its own
+    model: String,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnippetMatch {
+    #[serde(flatten)]
+    pub snippet: Snippet,
+    pub score: f32,
+}
+
+// A flat JSON file is plenty for a few hundred vectors; search is a linear scan
+pub struct SnippetIndex {
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<IndexedSnippet>>,
+}
+
+impl SnippetIndex {
+    pub fn open(dir: &Path) -> Self {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            tracing::warn!("Failed to create snippet dir: {}, keeping snippets in memory", e);
+            return Self::in_memory();
+        }
+        let path = dir.join(INDEX_FILE);
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { path: Some(path), entries: Mutex::new(entries) }
+    }
+
+    pub fn in_memory() -> Self {
+        Self { path: None, entries: Mutex::new(Vec::new()) }
+    }
+
+    fn save(&self, entries: &[IndexedSnippet]) {
+        let Some(path) = &self.path else { return };
+        let result = serde_json::to_string(entries)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to save snippet index: {}", e);
+        }
+    }
+
+    fn insert(&self, entry: IndexedSnippet) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|existing| existing.snippet.id != entry.snippet.id);
+        entries.push(entry);
+
+        let generations = entries.iter().filter(|e| e.snippet.kind == "generation").count();
+        if generations > MAX_GENERATIONS {
+            let mut excess = generations - MAX_GENERATIONS;
+            entries.retain(|e| {
+                let drop = excess > 0 && e.snippet.kind == "generation";
+                if drop {
+                    excess -= 1;
+                }
+                !drop
+            });
+        }
+        self.save(&entries);
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|entry| entry.snippet.id != id);
+        let removed = entries.len() != before;
+        if removed {
+            self.save(&entries);
+        }
+        removed
+    }
+
+    fn list(&self, kind: Option<&str>) -> Vec<Snippet> {
+        let entries = self.entries.lock().unwrap();
+        let mut snippets: Vec<Snippet> = entries
+            .iter()
+            .filter(|entry| kind.is_none() || kind == Some(entry.snippet.kind.as_str()))
+            .map(|entry| entry.snippet.clone())
+            .collect();
+        snippets.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        snippets
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+
+    fn search(&self, model: &str, query: &[f32], limit: usize) -> Vec<SnippetMatch> {
+        let entries = self.entries.lock().unwrap();
+        let mut matches: Vec<SnippetMatch> = entries
+            .iter()
+            .filter(|entry| entry.model == model)
+            .map(|entry| SnippetMatch {
+                snippet: entry.snippet.clone(),
+                score: cosine_similarity(query, &entry.embedding),
+            })
+            .filter(|found| found.score >= MIN_SIMILARITY)
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        matches
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+fn clip(text: &str, chars: usize) -> String {
+    text.chars().take(chars).collect()
+}
+
+async fn embed_one(model: &str, text: &str) -> Result<Vec<f32>, String> {
+    embed(model, &[clip(text, EMBED_CHARS)])
+        .await?
+        .pop()
+        .ok_or_else(|| "Ollama returned no embedding".to_string())
+}
+
+// Index a finished generation in the background so it can inform later ones
+pub fn index_generation(state: AppState, generation_id: i64, prompt: &str, response: &str) {
+    let settings = current_settings(&state);
+    if test_mode() || !settings.snippet_retrieval {
+        return;
+    }
+    let Some(html) = extract::extract(response).html else { return };
+    let prompt = prompt.to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let text = format!("{}\n\n{}", prompt, html);
+        match embed_one(&settings.embedding_model, &text).await {
+            Ok(embedding) => state.snippets.insert(IndexedSnippet {
+                snippet: Snippet {
+                    id: format!("generation-{}", generation_id),
+                    kind: "generation".to_string(),
+                    title: clip(prompt.trim(), 60),
+                    content: html,
+                    created_at: now_millis(),
+                },
+                model: settings.embedding_model,
+                embedding,
+            }),
+            Err(e) => tracing::debug!("Skipped indexing generation {}: {}", generation_id, keys::redact(&e)),
+        }
+    });
+}
+
+// System prompt section with the closest snippets to this request, if any are close enough
+pub async fn retrieve(state: &AppState, query: &str) -> Option<String> {
+    let settings = current_settings(state);
+    if test_mode() || !settings.snippet_retrieval || settings.retrieval_top_k == 0 || state.snippets.is_empty() {
+        return None;
+    }
+
+    let embedding = match embed_one(&settings.embedding_model, query).await {
+        Ok(embedding) => embedding,
+        Err(e) => {
+            // Usually just means the embedding model isn't pulled; generation goes on without it
+            tracing::debug!("Snippet retrieval unavailable: {}", keys::redact(&e));
+            return None;
+        }
+    };
+    let matches = state.snippets.search(&settings.embedding_model, &embedding, settings.retrieval_top_k);
+    if matches.is_empty() {
+        return None;
+    }
+
+    let sections: Vec<String> = matches
+        .iter()
+        .map(|found| {
+            let content = &found.snippet.content;
+            let content = if estimate_tokens(content) > SNIPPET_TOKENS {
+                format!("{}\n...", clip(content, SNIPPET_TOKENS * 4))
+            } else {
+                content.clone()
+            };
+            format!("--- {} ---\n```\n{}\n```", found.snippet.title, content.trim())
+        })
+        .collect();
+    Some(format!(
+        "THE USER'S OWN PATTERNS (reuse their components, naming and style where they fit this request; never copy unrelated features):\n\n{}",
+        sections.join("\n\n")
+    ))
+}
+
+// Deterministic bag-of-words vectors so test runs can exercise semantic search without a model
+fn test_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; 64];
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        let hash = blake3::hash(word.to_lowercase().as_bytes());
+        vector[hash.as_bytes()[0] as usize % 64] += 1.0;
+    }
+    vector
+}
+
+#[tauri::command]
+pub async fn embed_text(
+    texts: Vec<String>,
+    model: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Vec<f32>>, String> {
+    if texts.len() > MAX_EMBED_BATCH {
+        return Err(format!("Too many texts ({}, max {} per call)", texts.len(), MAX_EMBED_BATCH));
+    }
+    if test_mode() {
+        return Ok(texts.iter().map(|text| test_embedding(text)).collect());
+    }
+    let model = model.unwrap_or_else(|| current_settings(&state).embedding_model);
+    let texts: Vec<String> = texts.iter().map(|text| clip(text, EMBED_CHARS)).collect();
+    embed(&model, &texts)
+        .await
+        .map_err(|e| format!("Failed to embed text with {}: {}", model, e))
+}
+
+#[tauri::command]
+pub async fn save_snippet(title: String, content: String, state: State<'_, AppState>) -> Result<Snippet, String> {
+    if content.trim().is_empty() {
+        return Err("Snippet is empty".to_string());
+    }
+    let model = current_settings(&state).embedding_model;
+    let embedding = embed_one(&model, &format!("{}\n\n{}", title, content))
+        .await
+        .map_err(|e| format!("Failed to embed snippet (is {} pulled?): {}", model, e))?;
+
+    let snippet = Snippet {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: "snippet".to_string(),
+        title: title.trim().to_string(),
+        content,
+        created_at: now_millis(),
+    };
+    state.snippets.insert(IndexedSnippet { snippet: snippet.clone(), model, embedding });
+    Ok(snippet)
+}
+
+#[tauri::command]
+pub async fn list_snippets(kind: Option<String>, state: State<'_, AppState>) -> Result<Vec<Snippet>, String> {
+    Ok(state.snippets.list(kind.as_deref()))
+}
+
+#[tauri::command]
+pub async fn delete_snippet(id: String, state: State<'_, AppState>) -> Result<String, String> {
+    if state.snippets.remove(&id) {
+        Ok(format!("Snippet {} deleted", id))
+    } else {
+        Err(format!("Snippet {} not found", id))
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+// Keep in sync with scripts/fetch-tailwind.mjs
+pub const TAILWIND_VERSION: &str = "3.4.16";
+const CDN_HOST: &str = "cdn.tailwindcss.com";
+
+fn bundled_file() -> String {
+    format!("tailwindcss-{}.js", TAILWIND_VERSION)
+}
+
+// The pinned Play CDN build shipped in the app's resources, if it was fetched at build time
+pub fn bundled_script(app_handle: &AppHandle) -> Option<&'static [u8]> {
+    static SCRIPT: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+    SCRIPT
+        .get_or_init(|| {
+            let path = app_handle.path().resource_dir().ok()?.join("vendor").join(bundled_file());
+            std::fs::read(path).ok()
+        })
+        .as_deref()
+}
+
+// Byte range of the quoted src value and of the whole <script>...</script> element
+// for the first Tailwind CDN script at or after `from`
+fn find_cdn_script(html: &str, from: usize) -> Option<((usize, usize), (usize, usize))> {
+    let lower = html.to_ascii_lowercase();
+    let mut search = from;
+    loop {
+        let host = search + lower[search..].find(CDN_HOST)?;
+        search = host + CDN_HOST.len();
+
+        // Only a src inside an opening <script ...> tag counts (not e.g. a CSP meta)
+        let tag_start = match lower[..host].rfind("<script") {
+            Some(start) if !lower[start..host].contains('>') => start,
+            _ => continue,
+        };
+        let Some(quote_start) = html[..host].rfind(['"', '\'']).map(|pos| pos + 1) else { continue };
+        let quote = html.as_bytes()[quote_start - 1] as char;
+        let Some(quote_end) = html[host..].find(quote).map(|pos| host + pos) else { continue };
+        let Some(tag_end) = lower[host..].find("</script>").map(|pos| host + pos + "</script>".len()) else { continue };
+
+        return Some(((quote_start, quote_end), (tag_start, tag_end)));
+    }
+}
+
+// Point CDN script tags at `local_src` (the preview server path of the bundled copy)
+pub fn localize(html: &str, local_src: &str) -> String {
+    let mut result = html.to_string();
+    let mut from = 0;
+    while let Some(((start, end), _)) = find_cdn_script(&result, from) {
+        result.replace_range(start..end, local_src);
+        from = start + local_src.len();
+    }
+    result
+}
+
+// For standalone exports: replace CDN script tags with the bundled script inline
+pub fn inline(html: &str, script: &[u8]) -> String {
+    let inline_tag = format!("<script>{}</script>", String::from_utf8_lossy(script));
+    let mut result = html.to_string();
+    let mut from = 0;
+    while let Some((_, (start, end))) = find_cdn_script(&result, from) {
+        result.replace_range(start..end, &inline_tag);
+        from = start + inline_tag.len();
+    }
+    result
+}
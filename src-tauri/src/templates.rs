@@ -0,0 +1,133 @@
+use base64::Engine;
+use serde::Serialize;
+
+// Curated starting points for the new-app screen. Each prompt is already expanded into the
+// detailed request a one-liner like "todo app" would need; mock mode's keyword matching
+// (mocks/manifest.json) picks its fixtures from the same words.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StarterTemplate {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub prompt: &'static str,
+    // SVG data URI sketching the layout, small enough to inline in the list
+    pub thumbnail: String,
+}
+
+// 320x200 card: gradient backdrop plus the archetype's shapes in white
+fn thumbnail(from: &str, to: &str, shapes: &str) -> String {
+    let svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 320 200\">\
+         <defs><linearGradient id=\"g\" x1=\"0\" y1=\"0\" x2=\"1\" y2=\"1\">\
+         <stop offset=\"0\" stop-color=\"{from}\"/><stop offset=\"1\" stop-color=\"{to}\"/></linearGradient></defs>\
+         <rect width=\"320\" height=\"200\" rx=\"16\" fill=\"url(#g)\"/>\
+         <g fill=\"#fff\">{shapes}</g></svg>"
+    );
+    format!("data:image/svg+xml;base64,{}", base64::engine::general_purpose::STANDARD.encode(svg))
+}
+
+fn template(id: &'static str, name: &'static str, description: &'static str, prompt: &'static str, thumbnail: String) -> StarterTemplate {
+    StarterTemplate { id, name, description, prompt, thumbnail }
+}
+
+fn builtin_templates() -> Vec<StarterTemplate> {
+    vec![
+        template(
+            "todo",
+            "Todo list",
+            "Tasks with filters, persisted in the browser",
+            "Build a todo list app. Add tasks with Enter or an Add button, tick them off, edit a task by \
+             double-clicking it and delete it with a button that appears on hover. Filter between All, Active and \
+             Completed, show how many tasks are left, and offer 'Clear completed'. Save everything to localStorage \
+             so the list survives a reload. Animate tasks in and out and show a friendly empty state.",
+            thumbnail(
+                "#7c3aed",
+                "#db2777",
+                "<rect x=\"60\" y=\"36\" width=\"200\" height=\"22\" rx=\"6\" opacity=\".9\"/>\
+                 <rect x=\"60\" y=\"74\" width=\"16\" height=\"16\" rx=\"4\"/><rect x=\"86\" y=\"77\" width=\"140\" height=\"10\" rx=\"5\" opacity=\".7\"/>\
+                 <rect x=\"60\" y=\"104\" width=\"16\" height=\"16\" rx=\"4\"/><rect x=\"86\" y=\"107\" width=\"110\" height=\"10\" rx=\"5\" opacity=\".7\"/>\
+                 <rect x=\"60\" y=\"134\" width=\"16\" height=\"16\" rx=\"4\" opacity=\".5\"/><rect x=\"86\" y=\"137\" width=\"124\" height=\"10\" rx=\"5\" opacity=\".4\"/>",
+            ),
+        ),
+        template(
+            "dashboard",
+            "Analytics dashboard",
+            "KPI cards, charts and a sortable table",
+            "Build an analytics dashboard with a collapsible sidebar and a top bar with a date range picker. \
+             Show four KPI cards (revenue, users, conversion rate, churn) with trend arrows, a line chart of revenue \
+             over the last 30 days and a bar chart of signups per channel, both drawn with canvas or inline SVG \
+             from realistic sample data, and a recent orders table that sorts when a column header is clicked. \
+             Changing the date range should regenerate the data and redraw everything.",
+            thumbnail(
+                "#0f172a",
+                "#2563eb",
+                "<rect x=\"16\" y=\"16\" width=\"48\" height=\"168\" rx=\"8\" opacity=\".25\"/>\
+                 <rect x=\"78\" y=\"20\" width=\"52\" height=\"34\" rx=\"6\" opacity=\".8\"/><rect x=\"138\" y=\"20\" width=\"52\" height=\"34\" rx=\"6\" opacity=\".8\"/>\
+                 <rect x=\"198\" y=\"20\" width=\"52\" height=\"34\" rx=\"6\" opacity=\".8\"/><rect x=\"258\" y=\"20\" width=\"46\" height=\"34\" rx=\"6\" opacity=\".8\"/>\
+                 <polyline points=\"84,140 120,112 156,124 192,84 228,96 264,70 298,80\" fill=\"none\" stroke=\"#fff\" stroke-width=\"4\"/>\
+                 <rect x=\"78\" y=\"156\" width=\"226\" height=\"24\" rx=\"6\" opacity=\".3\"/>",
+            ),
+        ),
+        template(
+            "landing",
+            "Landing page",
+            "Product launch page with pricing and FAQ",
+            "Build a landing page for a fictional productivity app. Include a sticky navigation bar that gains a \
+             shadow on scroll, a hero with a bold headline, subheading, email signup form with validation and a \
+             product mockup, a three-column feature grid with icons, a testimonial carousel, a pricing section with \
+             a monthly/yearly toggle that updates the prices, an FAQ accordion and a footer. Sections fade in as \
+             they scroll into view and the layout works down to phone width.",
+            thumbnail(
+                "#f97316",
+                "#e11d48",
+                "<rect x=\"20\" y=\"16\" width=\"280\" height=\"14\" rx=\"7\" opacity=\".35\"/>\
+                 <rect x=\"40\" y=\"52\" width=\"150\" height=\"18\" rx=\"6\"/><rect x=\"40\" y=\"78\" width=\"120\" height=\"10\" rx=\"5\" opacity=\".7\"/>\
+                 <rect x=\"40\" y=\"100\" width=\"70\" height=\"22\" rx=\"11\"/><rect x=\"206\" y=\"48\" width=\"78\" height=\"78\" rx=\"12\" opacity=\".5\"/>\
+                 <rect x=\"40\" y=\"146\" width=\"72\" height=\"38\" rx=\"8\" opacity=\".35\"/><rect x=\"124\" y=\"146\" width=\"72\" height=\"38\" rx=\"8\" opacity=\".35\"/>\
+                 <rect x=\"208\" y=\"146\" width=\"72\" height=\"38\" rx=\"8\" opacity=\".35\"/>",
+            ),
+        ),
+        template(
+            "quiz",
+            "Quiz",
+            "Timed multiple-choice quiz with a results screen",
+            "Build a multiple-choice quiz app with ten general-knowledge questions. Show one question at a time \
+             with four answer buttons, a progress bar and a 15-second countdown per question. Reveal the right \
+             answer in green and a wrong pick in red before moving on, keep a running score, and finish on a \
+             results screen with the score, a short verdict, a review of every question and a Play again button \
+             that shuffles the questions and answers.",
+            thumbnail(
+                "#059669",
+                "#0ea5e9",
+                "<rect x=\"40\" y=\"24\" width=\"240\" height=\"8\" rx=\"4\" opacity=\".35\"/><rect x=\"40\" y=\"24\" width=\"140\" height=\"8\" rx=\"4\"/>\
+                 <rect x=\"40\" y=\"48\" width=\"240\" height=\"20\" rx=\"6\" opacity=\".9\"/>\
+                 <rect x=\"40\" y=\"86\" width=\"114\" height=\"36\" rx=\"10\" opacity=\".6\"/><rect x=\"166\" y=\"86\" width=\"114\" height=\"36\" rx=\"10\" opacity=\".6\"/>\
+                 <rect x=\"40\" y=\"132\" width=\"114\" height=\"36\" rx=\"10\" opacity=\".6\"/><rect x=\"166\" y=\"132\" width=\"114\" height=\"36\" rx=\"10\"/>",
+            ),
+        ),
+        template(
+            "pomodoro",
+            "Pomodoro timer",
+            "Focus timer with breaks, sounds and a session log",
+            "Build a pomodoro timer. A large circular progress ring counts down 25-minute focus sessions, \
+             5-minute short breaks and a 15-minute long break after every fourth session. Add start, pause and \
+             reset controls, keyboard shortcuts (space to start or pause), a chime made with the Web Audio API when \
+             a phase ends, the remaining time in the page title, editable durations in a settings panel and a log \
+             of today's completed sessions saved in localStorage.",
+            thumbnail(
+                "#dc2626",
+                "#9333ea",
+                "<circle cx=\"160\" cy=\"92\" r=\"58\" fill=\"none\" stroke=\"#fff\" stroke-opacity=\".3\" stroke-width=\"10\"/>\
+                 <path d=\"M160 34 A58 58 0 1 1 102 92\" fill=\"none\" stroke=\"#fff\" stroke-width=\"10\" stroke-linecap=\"round\"/>\
+                 <rect x=\"134\" y=\"84\" width=\"52\" height=\"16\" rx=\"6\"/>\
+                 <rect x=\"112\" y=\"164\" width=\"44\" height=\"20\" rx=\"10\"/><rect x=\"164\" y=\"164\" width=\"44\" height=\"20\" rx=\"10\" opacity=\".5\"/>",
+            ),
+        ),
+    ]
+}
+
+#[tauri::command]
+pub async fn list_templates() -> Result<Vec<StarterTemplate>, String> {
+    Ok(builtin_templates())
+}
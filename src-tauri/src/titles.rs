@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::projects::{self, ProjectMeta};
+use crate::{current_provider, current_settings, test_mode, AppState};
+
+const TITLE_SYSTEM_PROMPT: &str = "You name small web apps for a gallery. Reply with exactly three lines and nothing else:\n\
+TITLE: a catchy name of at most 4 words\n\
+EMOJI: one emoji that fits the app\n\
+DESCRIPTION: one plain sentence of at most 15 words saying what the app does";
+
+// Titles models and people leave in place of a real one
+const GENERIC_TITLES: &[&str] = &["app name", "custom app", "document", "untitled", "my app", "app", "web app"];
+
+// Words that start a request rather than name the app ("build me a ...")
+const REQUEST_PREFIXES: &[&str] = &[
+    "please ", "can you ", "could you ", "i want ", "i need ", "give me ", "build ", "create ", "make ",
+    "generate ", "design ", "write ", "code ", "me ", "a ", "an ", "the ", "simple ", "basic ",
+];
+
+const EMOJI_KEYWORDS: &[(&str, &str)] = &[
+    ("todo", "✅"),
+    ("task", "✅"),
+    ("habit", "🔁"),
+    ("calculator", "🧮"),
+    ("pomodoro", "🍅"),
+    ("timer", "⏱️"),
+    ("clock", "⏰"),
+    ("quiz", "❓"),
+    ("dashboard", "📊"),
+    ("analytics", "📊"),
+    ("chart", "📈"),
+    ("landing", "🚀"),
+    ("portfolio", "💼"),
+    ("weather", "🌤️"),
+    ("game", "🎮"),
+    ("music", "🎵"),
+    ("chat", "💬"),
+    ("note", "📝"),
+    ("calendar", "📅"),
+    ("recipe", "🍳"),
+    ("budget", "💰"),
+    ("expense", "💰"),
+    ("workout", "💪"),
+    ("fitness", "💪"),
+    ("photo", "🖼️"),
+    ("gallery", "🖼️"),
+    ("map", "🗺️"),
+    ("shop", "🛍️"),
+    ("store", "🛍️"),
+    ("blog", "📰"),
+];
+
+const DEFAULT_EMOJI: &str = "🍒";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppTitle {
+    pub title: String,
+    pub emoji: String,
+    pub description: String,
+}
+
+// Text of the first <tag ...>...</tag> in the document
+fn element_text(html: &str, tag: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find(&format!("<{}", tag))?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find(&format!("</{}", tag))?;
+    let text: String = html[start..end].split('<').next().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+fn meta_description(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let at = lower.find("name=\"description\"")?;
+    let tag_start = lower[..at].rfind('<')?;
+    let tag_end = at + lower[at..].find('>')?;
+    let content = lower[tag_start..tag_end].find("content=\"")? + tag_start + "content=\"".len();
+    let end = content + html[content..].find('"')?;
+    let text = html[content..end].trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn is_generic(title: &str) -> bool {
+    GENERIC_TITLES.contains(&title.trim().to_lowercase().as_str())
+}
+
+// "build me a simple pomodoro timer with stats" -> "Pomodoro Timer"
+fn title_from_prompt(prompt: &str) -> Option<String> {
+    let mut rest = prompt.trim().to_lowercase();
+    while let Some(stripped) = REQUEST_PREFIXES.iter().find_map(|prefix| rest.strip_prefix(prefix)) {
+        rest = stripped.trim_start().to_string();
+    }
+    let subject = [" with ", " that ", " which ", " for ", " where ", ",", ".", ":", ";", "!", "?", "\n"]
+        .iter()
+        .filter_map(|stop| rest.find(stop))
+        .min()
+        .map_or(rest.as_str(), |end| &rest[..end]);
+    let words: Vec<String> = subject
+        .split_whitespace()
+        .take(5)
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+        })
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+fn emoji_for(text: &str) -> &'static str {
+    let text = text.to_lowercase();
+    EMOJI_KEYWORDS
+        .iter()
+        .find(|(keyword, _)| text.contains(keyword))
+        .map_or(DEFAULT_EMOJI, |(_, emoji)| emoji)
+}
+
+fn first_sentence(prompt: &str) -> String {
+    let sentence = prompt.trim().split(['.', '!', '?', '\n']).next().unwrap_or_default().trim();
+    let mut description: String = sentence.chars().take(120).collect();
+    if sentence.chars().count() > 120 {
+        description.push('…');
+    }
+    match description.chars().next() {
+        Some(first) => first.to_uppercase().chain(description.chars().skip(1)).collect(),
+        None => description,
+    }
+}
+
+// Instant guess from the page's own <title>/<h1>/meta description and the prompt
+pub fn heuristic(prompt: &str, html: &str) -> AppTitle {
+    let title = element_text(html, "title")
+        .filter(|title| !is_generic(title))
+        .or_else(|| element_text(html, "h1").filter(|h1| !is_generic(h1) && h1.chars().count() <= 40))
+        .or_else(|| title_from_prompt(prompt))
+        .unwrap_or_else(|| "Untitled vibe".to_string());
+    AppTitle {
+        emoji: emoji_for(&format!("{} {}", title, prompt)).to_string(),
+        description: meta_description(html).unwrap_or_else(|| first_sentence(prompt)),
+        title,
+    }
+}
+
+fn parse_reply(reply: &str) -> Option<AppTitle> {
+    let field = |name: &str| {
+        reply.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim().trim_matches(['"', '*']).trim();
+            (key.trim().trim_matches('*').eq_ignore_ascii_case(name) && !value.is_empty()).then(|| value.to_string())
+        })
+    };
+    let title = field("title").filter(|title| title.chars().count() <= 40 && !is_generic(title))?;
+    let emoji = field("emoji").filter(|emoji| emoji.chars().count() <= 4 && !emoji.chars().any(char::is_alphanumeric));
+    Some(AppTitle {
+        emoji: emoji.unwrap_or_else(|| emoji_for(&title).to_string()),
+        description: field("description").unwrap_or_default(),
+        title,
+    })
+}
+
+// A short secondary generation; None when the model is unavailable or answers off-format
+async fn from_model(state: &AppState, prompt: &str, guess: &AppTitle) -> Option<AppTitle> {
+    let provider = current_provider(state);
+    let request = format!("App request: {}\nWorking title: {}", prompt.trim(), guess.title);
+    let full_prompt = provider.template().await.assemble(TITLE_SYSTEM_PROMPT, &request);
+    let mut options = current_settings(state).generation_options();
+    options.max_tokens = Some(80);
+    options.temperature = Some(0.3);
+    options.stop.clear();
+    match provider.generate(&full_prompt, &options).await {
+        Ok(reply) => parse_reply(&reply),
+        Err(e) => {
+            tracing::debug!("Skipping model-written title: {}", e);
+            None
+        }
+    }
+}
+
+// Sent as vibe-title after each generation so the window title can follow along
+pub fn emit_for_response(app_handle: &AppHandle, prompt: &str, html: &str) {
+    if let Err(e) = app_handle.emit("vibe-title", heuristic(prompt, html)) {
+        tracing::warn!("Failed to emit title: {}", e);
+    }
+}
+
+// Replace a new project's heuristic title with a model-written one in the background,
+// then emit project-titled with the updated metadata
+pub fn refine_quietly(app_handle: &AppHandle, meta: &ProjectMeta) {
+    if test_mode() || !current_settings(&app_handle.state::<AppState>()).auto_titles {
+        return;
+    }
+    let app_handle = app_handle.clone();
+    let (id, prompt) = (meta.id.clone(), meta.prompt.clone());
+    let guess = AppTitle {
+        title: meta.name.clone(),
+        emoji: meta.emoji.clone().unwrap_or_default(),
+        description: meta.description.clone().unwrap_or_default(),
+    };
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let Some(title) = from_model(&state, &prompt, &guess).await else { return };
+        match projects::read_meta(&app_handle, &id) {
+            // Unless the user renamed it while the model was thinking
+            Ok(mut meta) if meta.name == guess.title => {
+                meta.name = title.title;
+                meta.emoji = Some(title.emoji);
+                if !title.description.is_empty() {
+                    meta.description = Some(title.description);
+                }
+                if let Err(e) = projects::write_meta(&app_handle, &meta) {
+                    tracing::warn!("{}", e);
+                } else if let Err(e) = app_handle.emit("project-titled", &meta) {
+                    tracing::warn!("Failed to emit project title: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("{}", e),
+        }
+    });
+}
@@ -0,0 +1,181 @@
+use std::time::Instant;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use crate::{current_settings, AppState};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcription {
+    pub text: String,
+    // server or whisper
+    pub backend: String,
+    pub duration_ms: u64,
+}
+
+// First whisper.cpp model (ggml-*.bin) in the models dir, next to any GGUF chat model
+#[cfg(feature = "whisper")]
+fn find_whisper_model(app_handle: &AppHandle) -> Option<std::path::PathBuf> {
+    use tauri::Manager;
+
+    let dir = app_handle.path().app_data_dir().ok()?.join("models");
+    let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            name.starts_with("ggml-") && name.ends_with(".bin")
+        })
+        .collect();
+    files.sort();
+    files.into_iter().next()
+}
+
+// Any OpenAI-compatible /v1/audio/transcriptions endpoint (whisper.cpp server, faster-whisper-server, LocalAI, ...)
+async fn transcribe_with_server(url: &str, wav: Vec<u8>) -> Result<String, String> {
+    let part = reqwest::multipart::Part::bytes(wav)
+        .file_name("speech.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| format!("Failed to build transcription request: {}", e))?;
+    let form = reqwest::multipart::Form::new()
+        .part("file", part)
+        .text("model", "whisper-1")
+        .text("response_format", "json");
+
+    let response = reqwest::Client::new()
+        .post(url)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach transcription server: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Transcription server error ({}): {}", status, text));
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid transcription response: {}", e))?;
+    body["text"]
+        .as_str()
+        .map(|text| text.to_string())
+        .ok_or_else(|| "Transcription response has no text".to_string())
+}
+
+// whisper wants 16 kHz mono f32; browsers usually record 44.1/48 kHz
+#[cfg(feature = "whisper")]
+fn decode_wav(wav: &[u8]) -> Result<Vec<f32>, String> {
+    const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+    let mut reader = hound::WavReader::new(std::io::Cursor::new(wav))
+        .map_err(|e| format!("Failed to read WAV: {}", e))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().filter_map(Result::ok).collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>().filter_map(Result::ok).map(|s| s as f32 / scale).collect()
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    if spec.sample_rate == WHISPER_SAMPLE_RATE || mono.is_empty() {
+        return Ok(mono);
+    }
+
+    // Linear interpolation is plenty for speech
+    let ratio = spec.sample_rate as f64 / WHISPER_SAMPLE_RATE as f64;
+    let len = (mono.len() as f64 / ratio) as usize;
+    Ok((0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = mono[index.min(mono.len() - 1)];
+            let next = mono[(index + 1).min(mono.len() - 1)];
+            current + (next - current) * fraction
+        })
+        .collect())
+}
+
+#[cfg(feature = "whisper")]
+fn run_whisper(model_path: &std::path::Path, wav: &[u8]) -> Result<String, String> {
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    let samples = decode_wav(wav)?;
+    let context = WhisperContext::new_with_params(&model_path.to_string_lossy(), WhisperContextParameters::default())
+        .map_err(|e| format!("Failed to load whisper model: {}", e))?;
+    let mut state = context
+        .create_state()
+        .map_err(|e| format!("Failed to start whisper: {}", e))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("auto"));
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_print_special(false);
+
+    state
+        .full(params, &samples)
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+    let segments = state
+        .full_n_segments()
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+    let mut text = String::new();
+    for segment in 0..segments {
+        let piece = state
+            .full_get_segment_text(segment)
+            .map_err(|e| format!("Transcription failed: {}", e))?;
+        text.push_str(&piece);
+    }
+    Ok(text)
+}
+
+#[cfg(feature = "whisper")]
+async fn transcribe_locally(app_handle: &AppHandle, wav: Vec<u8>) -> Result<String, String> {
+    let model_path = find_whisper_model(app_handle).ok_or_else(|| {
+        "No speech model found. Put a whisper.cpp model (e.g. ggml-base.en.bin) in the models folder \
+         or set a transcription server in settings"
+            .to_string()
+    })?;
+    tauri::async_runtime::spawn_blocking(move || run_whisper(&model_path, &wav))
+        .await
+        .map_err(|e| format!("Transcription task failed: {}", e))?
+}
+
+#[cfg(not(feature = "whisper"))]
+async fn transcribe_locally(_app_handle: &AppHandle, _wav: Vec<u8>) -> Result<String, String> {
+    Err("This build has no built-in speech recognition; set a transcription server in settings".to_string())
+}
+
+#[tauri::command]
+pub async fn transcribe_audio(
+    wav_bytes: Vec<u8>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Transcription, String> {
+    if wav_bytes.is_empty() {
+        return Err("No audio recorded".to_string());
+    }
+    let started = Instant::now();
+    let settings = current_settings(&state);
+
+    let (text, backend) = match settings.transcription_url {
+        Some(url) => (transcribe_with_server(&url, wav_bytes).await?, "server"),
+        None => (transcribe_locally(&app_handle, wav_bytes).await?, "whisper"),
+    };
+
+    Ok(Transcription {
+        text: text.trim().to_string(),
+        backend: backend.to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{TrayIcon, TrayIconBuilder};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::health::{self, HealthStatus};
+use crate::{current_provider, quick_vibe, AppState};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+enum TrayStatus {
+    Idle(String),
+    Generating(usize),
+    BackendDown(String),
+}
+
+impl TrayStatus {
+    fn label(&self) -> String {
+        match self {
+            TrayStatus::Idle(model) => format!("Idle · {}", model),
+            TrayStatus::Generating(1) => "Generating…".to_string(),
+            TrayStatus::Generating(count) => format!("Generating ({} jobs)…", count),
+            TrayStatus::BackendDown(provider) if provider == "ollama" => "Ollama is not running".to_string(),
+            TrayStatus::BackendDown(provider) => format!("{} is unreachable", provider),
+        }
+    }
+}
+
+fn build_error(e: tauri::Error) -> String {
+    format!("Failed to create tray icon: {}", e)
+}
+
+pub fn install(app_handle: &AppHandle) -> Result<(), String> {
+    let status = MenuItem::with_id(app_handle, "status", "Starting…", false, None::<&str>).map_err(build_error)?;
+    let new_vibe = MenuItem::with_id(app_handle, "new_vibe", "New vibe", true, None::<&str>).map_err(build_error)?;
+    let stop_all = MenuItem::with_id(app_handle, "stop_all", "Stop all generations", true, None::<&str>).map_err(build_error)?;
+    let gallery = MenuItem::with_id(app_handle, "open_gallery", "Open gallery", true, None::<&str>).map_err(build_error)?;
+    let menu = Menu::with_items(
+        app_handle,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app_handle).map_err(build_error)?,
+            &new_vibe,
+            &stop_all,
+            &gallery,
+            &PredefinedMenuItem::separator(app_handle).map_err(build_error)?,
+            &PredefinedMenuItem::quit(app_handle, None).map_err(build_error)?,
+        ],
+    )
+    .map_err(build_error)?;
+
+    let mut builder = TrayIconBuilder::with_id("main")
+        .menu(&menu)
+        .tooltip("Vibe Cherry")
+        .on_menu_event(|app_handle, event| handle_menu(app_handle, event.id().as_ref()));
+    if let Some(icon) = app_handle.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    let tray = builder.build(app_handle).map_err(build_error)?;
+
+    tauri::async_runtime::spawn(watch_status(app_handle.clone(), tray, status));
+    Ok(())
+}
+
+fn show_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_menu(app_handle: &AppHandle, id: &str) {
+    match id {
+        "new_vibe" => {
+            if let Err(e) = quick_vibe::show_window(app_handle) {
+                tracing::warn!("{}", e);
+            }
+        }
+        "stop_all" => {
+            let stopped = app_handle.state::<AppState>().jobs.cancel_all();
+            tracing::info!("Stopped {} generation(s) from the tray", stopped);
+        }
+        "open_gallery" => {
+            show_main_window(app_handle);
+            // The gallery lives in the main window's UI; it switches view on this event
+            if let Err(e) = app_handle.emit("open-gallery", ()) {
+                tracing::warn!("Failed to emit open gallery: {}", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn watch_status(app_handle: AppHandle, tray: TrayIcon, status_item: MenuItem<Wry>) {
+    let mut shown = None;
+
+    loop {
+        let state = app_handle.state::<AppState>();
+        let provider = current_provider(&state);
+        // The health poller does the pinging; before its first result, assume the best
+        let backend_up = !matches!(health::latest(), Some(health) if health.status == HealthStatus::Down);
+
+        let status = match state.jobs.count() {
+            0 if !backend_up => TrayStatus::BackendDown(provider.name().to_string()),
+            0 => TrayStatus::Idle(provider.model().to_string()),
+            count => TrayStatus::Generating(count),
+        };
+        if shown.as_ref() != Some(&status) {
+            let label = status.label();
+            if let Err(e) = status_item.set_text(&label) {
+                tracing::warn!("Failed to update tray status: {}", e);
+            }
+            let _ = tray.set_tooltip(Some(format!("Vibe Cherry: {}", label)));
+            shown = Some(status);
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,324 @@
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use serde::{Deserialize, Serialize};
+use swc_common::{sync::Lrc, FileName, SourceMap, Spanned};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+
+const MAX_PARSE_ERRORS: usize = 20;
+
+// Elements that never have a closing tag
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+    "track", "wbr",
+];
+
+// Elements whose end tag the HTML spec lets authors omit
+const OPTIONAL_END: &[&str] = &[
+    "html", "head", "body", "p", "li", "dt", "dd", "option", "optgroup", "tr", "td", "th",
+    "thead", "tbody", "tfoot", "colgroup", "caption", "rt", "rp",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HtmlIssue {
+    // missing_doctype, unclosed_tag, stray_end_tag, truncated, parse_error
+    pub kind: String,
+    pub message: String,
+    pub line: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HtmlValidation {
+    pub valid: bool,
+    pub has_doctype: bool,
+    pub truncated: bool,
+    pub issues: Vec<HtmlIssue>,
+}
+
+#[derive(Default)]
+struct ValidationSink {
+    has_doctype: bool,
+    open: Vec<(String, u64)>,
+    issues: Vec<HtmlIssue>,
+    parse_errors: usize,
+}
+
+impl ValidationSink {
+    fn issue(&mut self, kind: &str, message: String, line: Option<u64>) {
+        self.issues.push(HtmlIssue {
+            kind: kind.to_string(),
+            message,
+            line,
+        });
+    }
+}
+
+impl TokenSink for ValidationSink {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, line: u64) -> TokenSinkResult<()> {
+        let mut result = TokenSinkResult::Continue;
+        match token {
+            Token::DoctypeToken(_) => self.has_doctype = true,
+            Token::TagToken(tag) => {
+                let name = tag.name.to_string();
+                match tag.kind {
+                    TagKind::StartTag => {
+                        if !tag.self_closing && !VOID_ELEMENTS.contains(&name.as_str()) {
+                            // Script and style bodies are text, so `i<n` mustn't read as a tag
+                            result = match name.as_str() {
+                                "script" => TokenSinkResult::RawData(RawKind::ScriptData),
+                                "style" => TokenSinkResult::RawData(RawKind::Rawtext),
+                                _ => TokenSinkResult::Continue,
+                            };
+                            self.open.push((name, line));
+                        }
+                    }
+                    TagKind::EndTag => {
+                        match self.open.iter().rposition(|(open, _)| *open == name) {
+                            Some(index) => {
+                                // Anything opened after the matching tag was implicitly closed
+                                let closed = self.open.split_off(index);
+                                for (skipped, opened_at) in closed.into_iter().skip(1) {
+                                    if !OPTIONAL_END.contains(&skipped.as_str()) {
+                                        let message = format!(
+                                            "<{}> opened on line {} is never closed before </{}>",
+                                            skipped, opened_at, name
+                                        );
+                                        self.issue("unclosed_tag", message, Some(opened_at));
+                                    }
+                                }
+                            }
+                            None if !VOID_ELEMENTS.contains(&name.as_str()) => {
+                                let message = format!("</{}> has no matching opening tag", name);
+                                self.issue("stray_end_tag", message, Some(line));
+                            }
+                            None => {}
+                        }
+                    }
+                }
+            }
+            Token::ParseError(message) => {
+                self.parse_errors += 1;
+                if self.parse_errors <= MAX_PARSE_ERRORS {
+                    self.issue("parse_error", message.to_string(), Some(line));
+                }
+            }
+            _ => {}
+        }
+        result
+    }
+}
+
+pub fn validate(html: &str) -> HtmlValidation {
+    let mut tokenizer = Tokenizer::new(
+        ValidationSink::default(),
+        TokenizerOpts {
+            exact_errors: true,
+            ..Default::default()
+        },
+    );
+    let mut input = BufferQueue::new();
+    input.push_back(StrTendril::from_slice(html));
+    let _ = tokenizer.feed(&mut input);
+    tokenizer.end();
+
+    let mut sink = tokenizer.sink;
+    let lower = html.trim_end().to_ascii_lowercase();
+
+    if !sink.has_doctype {
+        sink.issue("missing_doctype", "Document does not start with <!DOCTYPE html>".to_string(), None);
+    }
+
+    // Whatever is still open at EOF was never closed
+    let still_open = std::mem::take(&mut sink.open);
+    let mut truncated = !lower.ends_with("</html>");
+    for (name, line) in still_open {
+        if name == "script" || name == "style" {
+            truncated = true;
+        }
+        if !OPTIONAL_END.contains(&name.as_str()) {
+            let message = format!("<{}> opened on line {} is never closed", name, line);
+            sink.issue("unclosed_tag", message, Some(line));
+        }
+    }
+
+    if truncated {
+        sink.issue(
+            "truncated",
+            "Output appears cut off: the document does not end with </html>".to_string(),
+            None,
+        );
+    }
+
+    HtmlValidation {
+        valid: sink.issues.is_empty(),
+        has_doctype: sink.has_doctype,
+        truncated,
+        issues: sink.issues,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsError {
+    pub message: String,
+    // Line in the full HTML document, so it matches what the preview reports
+    pub line: usize,
+    pub column: usize,
+    pub script_index: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsValidation {
+    pub valid: bool,
+    pub script_count: usize,
+    pub errors: Vec<JsError>,
+}
+
+struct InlineScript {
+    code: String,
+    // 1-based line of the first character of `code` in the document
+    start_line: usize,
+    is_module: bool,
+}
+
+fn attribute_value(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find(&format!("{}=", name))? + name.len() + 1;
+    let rest = &tag[start..];
+    let value = match rest.chars().next()? {
+        quote @ ('"' | '\'') => rest[1..].split(quote).next()?,
+        _ => rest.split(|c: char| c.is_whitespace() || c == '>').next()?,
+    };
+    Some(value.to_ascii_lowercase())
+}
+
+// Inline <script> bodies that browsers would execute as JavaScript
+fn inline_scripts(html: &str) -> Vec<InlineScript> {
+    let lower = html.to_ascii_lowercase();
+    let mut scripts = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(open) = lower[cursor..].find("<script").map(|i| i + cursor) {
+        let Some(tag_end) = lower[open..].find('>').map(|i| i + open + 1) else { break };
+        let close = lower[tag_end..].find("</script").map(|i| i + tag_end).unwrap_or(html.len());
+        let tag = &html[open..tag_end];
+        cursor = close.max(tag_end);
+
+        if attribute_value(tag, "src").is_some() {
+            continue;
+        }
+        let script_type = attribute_value(tag, "type");
+        let is_module = script_type.as_deref() == Some("module");
+        let is_js = match script_type.as_deref() {
+            None | Some("") | Some("module") => true,
+            Some(t) => t.contains("javascript") || t.contains("ecmascript"),
+        };
+        if is_js {
+            scripts.push(InlineScript {
+                code: html[tag_end..close].to_string(),
+                start_line: html[..tag_end].matches('\n').count() + 1,
+                is_module,
+            });
+        }
+    }
+
+    scripts
+}
+
+pub fn validate_scripts(html: &str) -> JsValidation {
+    let scripts = inline_scripts(html);
+    let mut errors = Vec::new();
+
+    for (script_index, script) in scripts.iter().enumerate() {
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(FileName::Anon, script.code.clone());
+        let lexer = Lexer::new(
+            Syntax::Es(Default::default()),
+            Default::default(),
+            StringInput::from(&*fm),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+
+        let fatal = if script.is_module {
+            parser.parse_module().err()
+        } else {
+            parser.parse_script().err()
+        };
+
+        for error in parser.take_errors().into_iter().chain(fatal) {
+            let position = cm.lookup_char_pos(error.span().lo);
+            errors.push(JsError {
+                message: error.kind().msg().to_string(),
+                line: script.start_line + position.line - 1,
+                column: position.col_display + 1,
+                script_index,
+            });
+        }
+    }
+
+    JsValidation {
+        valid: errors.is_empty(),
+        script_count: scripts.len(),
+        errors,
+    }
+}
+
+pub fn format_js_errors(validation: &JsValidation) -> String {
+    validation
+        .errors
+        .iter()
+        .map(|error| {
+            format!(
+                "- Line {}, column {}: JavaScript syntax error: {}",
+                error.line, error.column, error.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Bullet list suitable for dropping straight into a fix prompt
+pub fn format_issues(validation: &HtmlValidation) -> String {
+    validation
+        .issues
+        .iter()
+        .map(|issue| match issue.line {
+            Some(line) => format!("- Line {}: {}", line, issue.message),
+            None => format!("- {}", issue.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tauri::command]
+pub async fn validate_html(content: String) -> Result<HtmlValidation, String> {
+    Ok(validate(&content))
+}
+
+#[tauri::command]
+pub async fn validate_js(content: String) -> Result<JsValidation, String> {
+    Ok(validate_scripts(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparisons_in_inline_scripts_and_styles_are_not_tags() {
+        let html = "<!DOCTYPE html>\n<html>\n<head>\n<style>a > b { color: red; }</style>\n</head>\n<body>\n\
+                    <script>\nfor (let i = 0; i<items.length; i++) {}\nif (a<b && c>d) {}\n</script>\n</body>\n</html>";
+        let validation = validate(html);
+        assert!(validation.valid, "{:?}", validation.issues);
+    }
+
+    #[test]
+    fn unclosed_elements_are_still_reported() {
+        let validation = validate("<!DOCTYPE html>\n<html><body><div><span></div></body></html>");
+        assert!(validation.issues.iter().any(|issue| issue.kind == "unclosed_tag"));
+    }
+}
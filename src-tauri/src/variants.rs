@@ -0,0 +1,105 @@
+use futures_util::future::join_all;
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+
+use crate::{build_full_prompt, context, current_settings, extract, guardrails, keys, now_millis, run_generation, sessions, AppState};
+
+const DEFAULT_VARIANTS: usize = 3;
+const MAX_VARIANTS: usize = 4;
+// Each extra variant runs a little hotter so the options actually differ
+const TEMPERATURE_STEP: f32 = 0.15;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantToken {
+    pub variant: usize,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Variant {
+    pub variant: usize,
+    pub seed: u32,
+    pub temperature: f32,
+    pub response: String,
+    pub html: Option<String>,
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn generate_variants(
+    prompt: String,
+    n: Option<usize>,
+    session_id: Option<String>,
+    // Tokens of every variant, tagged with which one they belong to
+    on_token: Channel<VariantToken>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<Variant>, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+
+    let n = n.unwrap_or(DEFAULT_VARIANTS).clamp(1, MAX_VARIANTS);
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let user_turn = context::with_history(&state, &app_handle, &history, &prompt).await;
+    let full_prompt = build_full_prompt(&state, &app_handle, &user_turn).await;
+    let base_options = current_settings(&state).generation_options();
+    let base_temperature = base_options.temperature.unwrap_or(0.7);
+    let base_seed = now_millis() as u32;
+
+    let runs = (0..n).map(|variant| {
+        let mut options = base_options.clone();
+        let temperature = (base_temperature + variant as f32 * TEMPERATURE_STEP).min(2.0);
+        let seed = base_seed.wrapping_add(variant as u32 * 7919);
+        options.temperature = Some(temperature);
+        options.seed = Some(seed);
+
+        let channel = on_token.clone();
+        let app_handle = app_handle.clone();
+        let state = state.clone();
+        let prompt = prompt.clone();
+        let full_prompt = full_prompt.clone();
+
+        async move {
+            let on_token = move |token: String| {
+                if let Err(e) = channel.send(VariantToken { variant, token }) {
+                    tracing::warn!("Failed to send variant token: {}", e);
+                }
+            };
+            let result = run_generation(&state, &prompt, &full_prompt, &options, &on_token)
+                .await
+                .and_then(|mut generated| {
+                    generated.response = guardrails::enforce(&app_handle, &state, generated.response)?;
+                    Ok(generated)
+                });
+
+            let (response, cached, error) = match result {
+                Ok(generated) => (generated.response, generated.cache_hit.is_some(), None),
+                Err(e) => (String::new(), false, Some(keys::redact(&e))),
+            };
+            Variant {
+                variant,
+                seed,
+                temperature,
+                html: extract::extract(&response).html,
+                response,
+                cached,
+                error,
+            }
+        }
+    });
+
+    let variants = join_all(runs).await;
+    if variants.iter().all(|variant| variant.error.is_some()) {
+        let reason = variants[0].error.clone().unwrap_or_default();
+        return Err(format!("All {} variants failed: {}", n, reason));
+    }
+    // The conversation carries on from the first variant that worked
+    if let Some(first) = variants.iter().find(|variant| variant.error.is_none()) {
+        sessions::record_in(&app_handle, &state, session_id.as_deref(), &prompt, &first.response);
+    }
+    Ok(variants)
+}
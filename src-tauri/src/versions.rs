@@ -0,0 +1,155 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use tauri::AppHandle;
+
+use crate::{now_millis, projects};
+
+const INDEX_FILE: &str = "index.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionInfo {
+    // 1-based and never reused, even after a restore
+    pub number: u32,
+    // save, heal or restore
+    pub source: String,
+    pub note: Option<String>,
+    pub created_at: u64,
+    pub bytes: usize,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionDiff {
+    pub from: u32,
+    pub to: u32,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+    // Unified diff, ready for a <pre> or a diff viewer
+    pub unified: String,
+}
+
+fn versions_dir(app_handle: &AppHandle, project_id: &str) -> Result<PathBuf, String> {
+    Ok(projects::project_dir(app_handle, project_id)?.join("versions"))
+}
+
+fn version_path(app_handle: &AppHandle, project_id: &str, number: u32) -> Result<PathBuf, String> {
+    Ok(versions_dir(app_handle, project_id)?.join(format!("v{}.html", number)))
+}
+
+pub fn list(app_handle: &AppHandle, project_id: &str) -> Result<Vec<VersionInfo>, String> {
+    let path = versions_dir(app_handle, project_id)?.join(INDEX_FILE);
+    Ok(std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default())
+}
+
+fn save_index(app_handle: &AppHandle, project_id: &str, versions: &[VersionInfo]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(versions)
+        .map_err(|e| format!("Failed to serialize versions: {}", e))?;
+    std::fs::write(versions_dir(app_handle, project_id)?.join(INDEX_FILE), json)
+        .map_err(|e| format!("Failed to write versions: {}", e))
+}
+
+// Store html as the project's next version; None when it matches the latest one
+pub fn record(
+    app_handle: &AppHandle,
+    project_id: &str,
+    html: &str,
+    source: &str,
+    note: Option<String>,
+) -> Result<Option<VersionInfo>, String> {
+    let dir = versions_dir(app_handle, project_id)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create versions dir: {}", e))?;
+
+    let mut versions = list(app_handle, project_id)?;
+    let hash = blake3::hash(html.as_bytes()).to_hex().to_string();
+    if versions.last().is_some_and(|latest| latest.hash == hash) {
+        return Ok(None);
+    }
+
+    let version = VersionInfo {
+        number: versions.last().map(|latest| latest.number + 1).unwrap_or(1),
+        source: source.to_string(),
+        note,
+        created_at: now_millis(),
+        bytes: html.len(),
+        hash,
+    };
+    std::fs::write(version_path(app_handle, project_id, version.number)?, html)
+        .map_err(|e| format!("Failed to write version: {}", e))?;
+    versions.push(version.clone());
+    save_index(app_handle, project_id, &versions)?;
+    Ok(Some(version))
+}
+
+// Version history is a convenience; failing to keep it never fails the save itself
+pub fn record_quietly(app_handle: &AppHandle, project_id: &str, html: &str, source: &str, note: Option<String>) {
+    if let Err(e) = record(app_handle, project_id, html, source, note) {
+        tracing::warn!("Failed to record version of {}: {}", project_id, e);
+    }
+}
+
+pub fn read_version(app_handle: &AppHandle, project_id: &str, number: u32) -> Result<String, String> {
+    std::fs::read_to_string(version_path(app_handle, project_id, number)?)
+        .map_err(|_| format!("Version {} of project {} not found", number, project_id))
+}
+
+// Writes a full version history brought in from elsewhere (a .vibe bundle), keeping its numbering
+pub fn import(app_handle: &AppHandle, project_id: &str, versions: &[(VersionInfo, String)]) -> Result<(), String> {
+    let dir = versions_dir(app_handle, project_id)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create versions dir: {}", e))?;
+    for (version, html) in versions {
+        std::fs::write(version_path(app_handle, project_id, version.number)?, html)
+            .map_err(|e| format!("Failed to write version: {}", e))?;
+    }
+    let infos: Vec<VersionInfo> = versions.iter().map(|(version, _)| version.clone()).collect();
+    save_index(app_handle, project_id, &infos)
+}
+
+#[tauri::command]
+pub async fn list_versions(project_id: String, app_handle: AppHandle) -> Result<Vec<VersionInfo>, String> {
+    list(&app_handle, &project_id)
+}
+
+// Restoring adds the old html as a new version rather than rewinding, so nothing is lost
+#[tauri::command]
+pub async fn restore_version(
+    project_id: String,
+    number: u32,
+    app_handle: AppHandle,
+) -> Result<projects::Project, String> {
+    let meta = projects::read_meta(&app_handle, &project_id)?;
+    let html = read_version(&app_handle, &project_id, number)?;
+    record(&app_handle, &project_id, &html, "restore", Some(format!("Restored version {}", number)))?;
+    projects::write_project(&app_handle, Some(meta.id), Some(meta.name), meta.prompt, html, meta.model)
+}
+
+#[tauri::command]
+pub async fn diff_versions(
+    project_id: String,
+    from: u32,
+    to: u32,
+    app_handle: AppHandle,
+) -> Result<VersionDiff, String> {
+    let old = read_version(&app_handle, &project_id, from)?;
+    let new = read_version(&app_handle, &project_id, to)?;
+
+    let diff = TextDiff::from_lines(&old, &new);
+    let (mut added_lines, mut removed_lines) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added_lines += 1,
+            ChangeTag::Delete => removed_lines += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    let unified = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("v{}", from), &format!("v{}", to))
+        .to_string();
+
+    Ok(VersionDiff { from, to, added_lines, removed_lines, unified })
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+use std::sync::Arc;
+use base64::Engine;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+
+use crate::providers::{OllamaProvider, ProviderConfig};
+use crate::{build_full_prompt_for, context, current_provider, current_settings, keys, prompts, sessions, stream_generation, AppState, StreamEvent};
+
+// Ollama rejects very large requests, and a phone photo of a sketch never needs more
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+
+fn read_image(path: &Path) -> Result<String, String> {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("Unsupported image type: {}", path.display()));
+    }
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(format!("Image is too large ({} MB, max {} MB)", size / 1024 / 1024, MAX_IMAGE_BYTES / 1024 / 1024));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+// The configured vision model, or the main Ollama model when it can see images itself
+async fn vision_provider(state: &AppState) -> Result<OllamaProvider, String> {
+    let settings = current_settings(state);
+    let model = match (&settings.vision_model, &settings.provider) {
+        (Some(model), _) => model.clone(),
+        (None, ProviderConfig::Ollama { .. }) => current_provider(state).model().to_string(),
+        (None, _) => return Err("Image generation needs an Ollama vision model; set one in settings".to_string()),
+    };
+
+    let provider = OllamaProvider::new(model.clone());
+    if !provider.supports_vision().await {
+        return Err(format!(
+            "{} can't read images. Pull a vision model (e.g. `ollama pull llava` or `ollama pull gemma3`) and set it as the vision model",
+            model
+        ));
+    }
+    Ok(provider)
+}
+
+#[tauri::command]
+pub async fn generate_from_image(
+    image_path: String,
+    prompt: Option<String>,
+    session_id: Option<String>,
+    on_token: Channel<StreamEvent>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    if !*state.is_initialized.lock().unwrap() {
+        return Err("Model not initialized".to_string());
+    }
+    let state = sessions::scoped(&app_handle, &state, session_id.as_deref())?;
+
+    let image = read_image(Path::new(&image_path))?;
+    let provider = vision_provider(&state).await?;
+
+    let prompt = prompt.filter(|prompt| !prompt.trim().is_empty()).unwrap_or_else(|| "Build this app.".to_string());
+    // The image travels on the user message, so the turn only needs the instructions
+    let user_turn = format!("{}\n\n{}", prompts::IMAGE_INSTRUCTIONS, prompt);
+    let history = sessions::history_of(&app_handle, session_id.as_deref())?;
+    let user_turn = context::with_history(&state, &app_handle, &history, &user_turn).await;
+    let full_prompt = build_full_prompt_for(&state, &app_handle, &provider, &user_turn).await;
+
+    let mut options = current_settings(&state).generation_options();
+    options.images = vec![image];
+
+    let response = stream_generation(&state, &app_handle, &on_token, Arc::new(provider), &prompt, &full_prompt, &options, 1, None)
+        .await
+        .map_err(|e| keys::redact(&e))?;
+    sessions::record_in(&app_handle, &state, session_id.as_deref(), &prompt, &response);
+    Ok(response)
+}
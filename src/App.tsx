@@ -1,11 +1,14 @@
 import { useEffect, useState } from 'react'
-import { invoke } from '@tauri-apps/api/core'
+import { Channel, invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 import { useChatStore } from './store/chatStore'
 import ChatView from './components/ChatView'
 import PreviewPane from './components/PreviewPane'
 import { SelfHealingPreview } from './components/SelfHealingPreview'
 import InputBar from './components/InputBar'
+import type { InitStatus } from './types/init'
+import type { Session, SessionSummary } from './types/session'
+import type { StreamEvent } from './types/stream'
 
 export default function App() {
   const {
@@ -20,6 +23,7 @@ export default function App() {
     setViewMode,
     setCurrentCode,
     setModelInitialized,
+    restoreMessages,
   } = useChatStore()
 
   const [useSelfHealing, setUseSelfHealing] = useState(true)
@@ -36,16 +40,11 @@ export default function App() {
     const initializeModel = async () => {
       try {
         console.log('Initializing model...')
-        const response = await invoke<string>('initialize_model')
-        setModelInitialized(true)
-        console.log('Model initialized successfully')
-        
-        // Check Ollama status based on response
-        if (response.includes('ready') || response.includes('model ready')) {
-          setOllamaStatus('online')
-        } else {
-          setOllamaStatus('offline')
-        }
+        const status = await invoke<InitStatus>('initialize_model')
+        setModelInitialized(status.ready)
+        console.log(status.message)
+
+        setOllamaStatus(status.model_installed ? 'online' : 'offline')
       } catch (error) {
         console.error('Failed to initialize model:', error)
         setOllamaStatus('offline')
@@ -57,14 +56,33 @@ export default function App() {
     }
   }, [isModelInitialized, setModelInitialized])
 
+  // Restore the most recent session so closing the app doesn't lose the conversation
+  useEffect(() => {
+    const restoreSession = async () => {
+      try {
+        const sessions = await invoke<SessionSummary[]>('list_sessions')
+        if (sessions.length === 0) return
+        const session = await invoke<Session>('load_session', { id: sessions[0].id })
+        restoreMessages(
+          session.messages.map((message) => ({
+            id: crypto.randomUUID(),
+            role: message.role,
+            content: message.content,
+            code: message.html ?? undefined,
+            timestamp: message.created_at,
+          }))
+        )
+      } catch (error) {
+        console.error('Failed to restore session:', error)
+      }
+    }
+
+    restoreSession()
+  }, [restoreMessages])
+
   // Set up event listeners for streaming
   useEffect(() => {
     const setupEventListeners = async () => {
-      // Listen for streaming tokens
-      const unlistenToken = await listen<string>('vibe-token', (event) => {
-        updateLastMessage(event.payload)
-      })
-
       // Listen for completion
       const unlistenComplete = await listen<string>('vibe-complete', () => {
         console.log('Generation completed')
@@ -78,14 +96,13 @@ export default function App() {
       })
 
       return () => {
-        unlistenToken()
         unlistenComplete()
         unlistenError()
       }
     }
 
     setupEventListeners()
-  }, [updateLastMessage, setLoading])
+  }, [setLoading])
 
   const handleSendMessage = async (message: string) => {
     // Add user message
@@ -103,6 +120,16 @@ export default function App() {
         content: '',
       })
 
+      // Tokens for this request arrive in order on its own channel
+      const onToken = new Channel<StreamEvent>()
+      onToken.onmessage = (message) => {
+        if (message.event === 'token') {
+          updateLastMessage(message.data)
+        } else if (message.event === 'fallback') {
+          console.warn(`${message.data.from} failed, retrying with ${message.data.to}: ${message.data.reason}`)
+        }
+      }
+
       // Call the streaming generation command
       await invoke('generate_vibe_stream', {
         prompt: message,
@@ -110,6 +137,7 @@ export default function App() {
           role: msg.role,
           content: msg.content,
         })),
+        onToken,
       })
 
       // Wait a bit for streaming to complete, then check if we have valid code
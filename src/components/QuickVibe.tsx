@@ -0,0 +1,61 @@
+import { useState, useRef, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+
+// Rendered in the always-on-top window opened by the global shortcut
+export default function QuickVibe() {
+  const [input, setInput] = useState('')
+  const [isLoading, setIsLoading] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+  const inputRef = useRef<HTMLInputElement>(null)
+
+  useEffect(() => {
+    inputRef.current?.focus()
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) inputRef.current?.focus()
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  const handleSubmit = async () => {
+    if (!input.trim() || isLoading) return
+    setIsLoading(true)
+    setError(null)
+    try {
+      await invoke('submit_quick_vibe', { prompt: input.trim() })
+      setInput('')
+    } catch (e) {
+      setError(String(e))
+      getCurrentWindow().show()
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'Enter') {
+      e.preventDefault()
+      handleSubmit()
+    } else if (e.key === 'Escape') {
+      getCurrentWindow().hide()
+    }
+  }
+
+  return (
+    <div className="h-screen flex flex-col justify-center px-4 bg-gray-900">
+      <input
+        ref={inputRef}
+        type="text"
+        value={input}
+        onChange={(e) => setInput(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder={isLoading ? 'Vibing...' : 'Quick vibe: describe an app and press Enter'}
+        className="w-full bg-white/5 border border-white/10 rounded-xl px-4 py-3 text-white placeholder-white/40 focus:outline-none focus:border-pink-500/50 focus:ring-2 focus:ring-pink-500/20 transition"
+        disabled={isLoading}
+      />
+      {error && <div className="mt-1 text-xs text-red-400 truncate">{error}</div>}
+    </div>
+  )
+}
@@ -1,10 +1,13 @@
 import React from 'react'
 import ReactDOM from 'react-dom/client'
 import App from './App.tsx'
+import QuickVibe from './components/QuickVibe.tsx'
 import './index.css'
 
+const isQuickVibe = new URLSearchParams(window.location.search).get('window') === 'quick-vibe'
+
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
-    <App />
+    {isQuickVibe ? <QuickVibe /> : <App />}
   </React.StrictMode>,
 )
@@ -25,6 +25,7 @@ interface ChatStore {
   setCurrentCode: (code: string) => void
   setModelInitialized: (initialized: boolean) => void
   clearMessages: () => void
+  restoreMessages: (messages: Message[]) => void
 }
 
 export const useChatStore = create<ChatStore>((set, get) => ({
@@ -76,4 +77,8 @@ export const useChatStore = create<ChatStore>((set, get) => ({
   setCurrentCode: (code) => set({ currentCode: code }),
   setModelInitialized: (initialized) => set({ isModelInitialized: initialized }),
   clearMessages: () => set({ messages: [], currentCode: '', viewMode: 'code' }),
+  restoreMessages: (messages) => {
+    const lastCode = [...messages].reverse().find((message) => message.code)?.code ?? ''
+    set({ messages, currentCode: lastCode, viewMode: lastCode ? 'preview' : 'code' })
+  },
 }))
@@ -0,0 +1,42 @@
+// Mirrors AppStateSnapshot returned by the get_app_state command
+export interface AppStateSnapshot {
+  initialized: boolean;
+  provider: string;
+  model: string;
+  fallback_mode: 'local_server' | 'embedded' | 'mock' | 'test' | null;
+  mock_mode: boolean;
+  active_jobs: JobInfo[];
+  last_error: LastError | null;
+  backend_health: BackendHealth | null;
+  session_id: string | null;
+  api_server: ApiServerInfo | null;
+}
+
+export interface JobInfo {
+  id: number;
+  kind: string;
+  label: string;
+  started_at: number;
+  queued: boolean;
+}
+
+export interface LastError {
+  kind: 'initialization' | 'generation';
+  message: string;
+  at: number;
+}
+
+// Also the payload of backend-health events
+export interface BackendHealth {
+  status: 'up' | 'down' | 'model_loaded';
+  provider: string;
+  model: string;
+  error: string | null;
+  checked_at: number;
+}
+
+export interface ApiServerInfo {
+  url: string;
+  port: number;
+  token: string;
+}
@@ -0,0 +1,30 @@
+// Mirrors InitStatus returned by the initialize_model command
+export interface InitStatus {
+  ollama_found: boolean;
+  ollama_path: string | null;
+  ollama_version: string | null;
+  version_warning: string | null;
+  daemon_running: boolean;
+  model_installed: boolean;
+  model_name: string;
+  fallback_mode: 'local_server' | 'embedded' | 'mock' | 'test' | null;
+  ready: boolean;
+  message: string;
+}
+
+// check_ollama
+export interface OllamaCheck {
+  found: boolean;
+  path: string | null;
+  version: string | null;
+  version_warning: string | null;
+  daemon_running: boolean;
+  models: string[];
+}
+
+// check_model and ensure_model
+export interface ModelCheck {
+  name: string;
+  installed: boolean;
+  pulled: boolean;
+}
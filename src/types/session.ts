@@ -0,0 +1,23 @@
+// Mirror the session types returned by list_sessions and load_session
+export interface SessionSummary {
+  id: string;
+  title: string;
+  created_at: number;
+  updated_at: number;
+  message_count: number;
+}
+
+export interface SessionMessage {
+  role: 'user' | 'assistant';
+  content: string;
+  html: string | null;
+  created_at: number;
+}
+
+export interface Session {
+  id: string;
+  title: string;
+  created_at: number;
+  updated_at: number;
+  messages: SessionMessage[];
+}
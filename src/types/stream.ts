@@ -0,0 +1,44 @@
+// Mirrors StreamEvent sent on the onToken channel of streaming commands
+export type StreamEvent =
+  | { event: 'token'; data: string }
+  | { event: 'fallback'; data: { from: string; to: string; reason: string } }
+
+// Payloads of the generation-* events, emitted for every streamed generation
+export interface GenerationStarted {
+  generation_id: string;
+  provider: string;
+  model: string;
+  attempt: number;
+  fallback_from: string | null;
+  started_at: number;
+}
+
+export interface GenerationCompleted {
+  generation_id: string;
+  provider: string;
+  model: string;
+  history_id: number | null;
+  duration_ms: number;
+  total_tokens: number;
+  cached: boolean;
+  seed: number | null;
+}
+
+export interface GenerationFailed {
+  generation_id: string;
+  provider: string;
+  model: string;
+  error: string;
+}
+
+export interface GenerationCancelled {
+  generation_id: string;
+  provider: string;
+  model: string;
+}
+
+// Sent on the onToken channel of generate_variants
+export interface VariantToken {
+  variant: number;
+  token: string;
+}